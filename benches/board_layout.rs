@@ -0,0 +1,83 @@
+//! Compare the plain [Board] layout with the edge-array [EdgeBoard] layout, on the neighbour
+//! comparisons solvers make.
+//!
+//! Run with `cargo bench --bench board_layout`.
+
+#![feature(test)]
+
+extern crate test;
+
+use rand::{rngs::StdRng, SeedableRng};
+use test::{black_box, Bencher};
+
+use e2rs::generate::{generate, GeneratorConfig};
+use e2rs::model::{Board, Edge, EdgeBoard, Indx, SIDES};
+
+/// A solved board the size of the Eternity 2 Puzzle.
+fn solved() -> Board<u8> {
+    let puzzle = generate(&GeneratorConfig::E2_LIKE, &mut StdRng::seed_from_u64(1)).unwrap();
+    let spec = puzzle.spec().unwrap();
+    let mut board = spec.dimensions.new_board();
+    for clue in spec.load_clues(&puzzle.solution_file(), false).unwrap() {
+        clue.apply(&mut board).unwrap();
+    }
+    board
+}
+
+/// Every cell of the board, in row-major order.
+fn cells(board: &Board<u8>) -> Vec<Indx> {
+    let shape = board.shape();
+    (0..shape.rows)
+        .flat_map(|row| (0..shape.columns).map(move |col| Indx { col, row }))
+        .collect()
+}
+
+/// Check a placed tile against its neighbours, as a solver does when counting candidates.
+fn board_fits(board: &Board<u8>, at: Indx) -> bool {
+    let shape = board.shape();
+    let tile = board[at].unwrap();
+    SIDES.iter().all(|side| {
+        let edge = tile[*side];
+        match shape.neighbour(at, *side) {
+            None => edge.is_border(),
+            Some(n) => {
+                !edge.is_border() && board[n].as_ref().is_none_or(|t| t[side.flip()] == edge)
+            }
+        }
+    })
+}
+
+#[bench]
+fn score_board(b: &mut Bencher) {
+    let board = solved();
+    b.iter(|| black_box(&board).score());
+}
+
+#[bench]
+fn score_edge_board(b: &mut Bencher) {
+    let board = EdgeBoard::from(&solved());
+    b.iter(|| black_box(&board).score());
+}
+
+#[bench]
+fn fits_board(b: &mut Bencher) {
+    let board = solved();
+    let cells = cells(&board);
+    b.iter(|| cells.iter().filter(|at| board_fits(black_box(&board), **at)).count());
+}
+
+#[bench]
+fn fits_edge_board(b: &mut Bencher) {
+    let plain = solved();
+    let cells = cells(&plain);
+    let board = EdgeBoard::from(&plain);
+    b.iter(|| {
+        cells
+            .iter()
+            .filter(|at| {
+                let tile = board.get(**at).unwrap();
+                black_box(&board).fits(**at, &tile)
+            })
+            .count()
+    });
+}
@@ -0,0 +1,150 @@
+//! Analyses of boards and tilesets.
+//!
+//! These are diagnostics for examining puzzles and (partial) solutions, rather than for solving them.
+
+use crate::model::{Board, Edge, Indx, Side, Tile, TileSet, SIDES};
+
+mod diff;
+pub use diff::*;
+mod distance;
+pub use distance::*;
+mod difficulty;
+pub use difficulty::*;
+mod isomorphism;
+pub use isomorphism::*;
+mod marginals;
+pub use marginals::*;
+mod what_if;
+pub use what_if::*;
+
+/// Summary statistics of a tileset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileSetStats {
+    /// How many tile sides carry each edge, indexed by edge number.
+    pub edge_counts: Vec<usize>,
+    /// Number of corner tiles.
+    pub corners: usize,
+    /// Number of edge tiles.
+    pub edges: usize,
+    /// Number of interior tiles.
+    pub interior: usize,
+}
+
+impl TileSetStats {
+    /// How many tile sides carry the given edge.
+    pub fn count<E: Into<u8>>(&self, edge: E) -> usize {
+        self.edge_counts
+            .get(edge.into() as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+/// Gather statistics about a tileset.
+///
+/// ```
+/// use e2rs::analysis::tileset_stats;
+/// use e2rs::e2::{E2Edge, E2_BOARD_SPEC};
+///
+/// let stats = tileset_stats(&E2_BOARD_SPEC.tiles);
+/// assert_eq!((stats.corners, stats.edges, stats.interior), (4, 56, 196));
+/// assert_eq!(stats.count(E2Edge::Outside), 64);
+/// ```
+pub fn tileset_stats<E: Copy + Edge + Into<u8>>(tiles: &TileSet<E>) -> TileSetStats {
+    let mut stats = TileSetStats {
+        edge_counts: Vec::new(),
+        corners: 0,
+        edges: 0,
+        interior: 0,
+    };
+
+    for tile in tiles {
+        for side in SIDES {
+            let edge = tile[side].into() as usize;
+            if stats.edge_counts.len() <= edge {
+                stats.edge_counts.resize(edge + 1, 0);
+            }
+            stats.edge_counts[edge] += 1;
+        }
+
+        if tile.is_corner() {
+            stats.corners += 1;
+        } else if tile.is_edge() {
+            stats.edges += 1;
+        } else {
+            stats.interior += 1;
+        }
+    }
+
+    stats
+}
+
+/// A placed tile with the wrong kind of edge on one side, as found by [border_violations].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BorderViolation {
+    /// The cell holding the tile.
+    pub at: Indx,
+    /// The side of the cell with the wrong kind of edge.
+    pub side: Side,
+    /// What is wrong with the edge.
+    pub kind: BorderViolationKind,
+}
+
+/// The ways in which a placed tile can disagree with the board border.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderViolationKind {
+    /// An outside edge faces into the board.
+    InwardOutside,
+    /// A side on the border of the board does not have an outside edge.
+    MissingOutside,
+}
+
+/// List every side of every placed tile that disagrees with the board border.
+///
+/// Sides on the border must have outside edges, and sides facing into the board must not.
+/// Violations are listed in row-major cell order, and clockwise from north within each cell.
+///
+/// ```
+/// use e2rs::analysis::{border_violations, BorderViolationKind};
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::model::{Indx, Side};
+///
+/// let mut board = new_e2board();
+/// let mut clue = E2_CLUES[0];
+/// clue.at = Indx { col: 0, row: 5 };
+/// clue.apply(&mut board).unwrap();
+///
+/// let violations = border_violations(&board);
+/// assert_eq!(violations.len(), 1);
+/// assert_eq!(violations[0].side, Side::West);
+/// assert_eq!(violations[0].kind, BorderViolationKind::MissingOutside);
+/// ```
+pub fn border_violations<E: Edge>(board: &Board<E>) -> Vec<BorderViolation> {
+    let shape = board.shape();
+    let mut violations = Vec::new();
+
+    for row in 0..board.rows {
+        for col in 0..board.columns {
+            let at = Indx { col, row };
+            let Some(tile) = &board[at] else {
+                continue;
+            };
+
+            for side in SIDES {
+                let kind = match (shape.on_border(at, side), tile[side].is_border()) {
+                    (true, false) => BorderViolationKind::MissingOutside,
+                    (false, true) => BorderViolationKind::InwardOutside,
+                    _ => continue,
+                };
+                violations.push(BorderViolation { at, side, kind });
+            }
+        }
+    }
+
+    violations
+}
+
+/// Check a tile against the border and any placed neighbours of a cell.
+pub(crate) fn fits<E: Copy + Edge + PartialEq>(board: &Board<E>, at: Indx, tile: &Tile<E>) -> bool {
+    board.fits(at, tile)
+}
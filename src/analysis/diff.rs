@@ -0,0 +1,59 @@
+use crate::model::{Board, Indx, Tile};
+
+/// A cell that differs between two boards, as found by [board_diff].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellDiff<E> {
+    /// The cell.
+    pub at: Indx,
+    /// The tile in the cell on the first board, placed as it lies.
+    pub before: Option<Tile<E>>,
+    /// The tile in the cell on the second board, placed as it lies.
+    pub after: Option<Tile<E>>,
+}
+
+/// The cells that differ between two boards, in row-major order.
+///
+/// A cell differs if it holds a tile on one board and not the other, or holds different tiles,
+/// or the same tile turned differently.
+/// Where the boards are different shapes, cells beyond the edge of one board count as empty
+/// on that board.
+///
+/// ```
+/// use e2rs::analysis::board_diff;
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+///
+/// let mut before = new_e2board();
+/// E2_CLUES[0].apply(&mut before).unwrap();
+/// let mut after = before.clone();
+/// E2_CLUES[1].apply(&mut after).unwrap();
+///
+/// let diff = board_diff(&before, &after);
+/// assert_eq!(diff.len(), 1);
+/// assert_eq!(diff[0].at, E2_CLUES[1].at);
+/// assert!(diff[0].before.is_none());
+/// ```
+pub fn board_diff<E: Copy + PartialEq>(before: &Board<E>, after: &Board<E>) -> Vec<CellDiff<E>> {
+    let cell = |board: &Board<E>, at: Indx| {
+        if board.shape().contains(at) {
+            board[at]
+        } else {
+            None
+        }
+    };
+
+    let mut diffs = Vec::new();
+    for row in 0..before.rows.max(after.rows) {
+        for col in 0..before.columns.max(after.columns) {
+            let at = Indx { col, row };
+            let (b, a) = (cell(before, at), cell(after, at));
+            if b != a {
+                diffs.push(CellDiff {
+                    at,
+                    before: b,
+                    after: a,
+                });
+            }
+        }
+    }
+    diffs
+}
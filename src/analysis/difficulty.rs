@@ -0,0 +1,196 @@
+use std::fmt;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::model::{BoardSpec, Edge, Indx, Rotate, Tile, ROTATIONS};
+
+use super::{fits, tileset_stats, TileSetStats};
+
+/// An estimate of how hard a puzzle is to solve by search, as made by [difficulty].
+#[derive(Clone, Debug)]
+pub struct Difficulty {
+    /// Statistics of the tileset.
+    pub stats: TileSetStats,
+    /// Number of random probes the estimates were made from.
+    pub probes: usize,
+    /// The mean number of candidate placements for a cell, over all cells the probes visited.
+    pub mean_candidates: f64,
+    /// The mean number of cells a probe filled before it hit a dead end.
+    pub mean_depth: f64,
+    /// Base 10 logarithm of Knuth's estimate of the size of the search tree.
+    ///
+    /// This is the number of nodes a plain row-major backtracking search visits to exhaust the
+    /// puzzle, and is the headline difficulty figure: puzzles can be ranked by it.
+    pub log10_tree_size: f64,
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = &self.stats;
+        writeln!(
+            f,
+            "tiles:            {} ({} corners, {} edges, {} interior)",
+            s.corners + s.edges + s.interior,
+            s.corners,
+            s.edges,
+            s.interior
+        )?;
+        writeln!(
+            f,
+            "edge types:       {}",
+            s.edge_counts.iter().filter(|c| **c > 0).count()
+        )?;
+        writeln!(f, "probes:           {}", self.probes)?;
+        writeln!(f, "mean candidates:  {:.2}", self.mean_candidates)?;
+        writeln!(f, "mean probe depth: {:.1}", self.mean_depth)?;
+        write!(f, "search tree size: 10^{:.1}", self.log10_tree_size)
+    }
+}
+
+/// Estimate the difficulty of a puzzle.
+///
+/// The estimate combines the tileset statistics with random probes of a row-major backtracking
+/// search, in the manner of Knuth's estimator.
+/// Each probe fills cells in row-major order, picking uniformly among the tiles and rotations
+/// that fit the neighbours already placed and the border, until it completes the board or hits
+/// a cell with no candidates.
+/// The product of the candidate counts along the way gives an unbiased estimate of the tree size.
+///
+/// More probes give steadier estimates; a few hundred are enough to rank puzzles.
+///
+/// ```
+/// use e2rs::analysis::difficulty;
+/// use e2rs::generate::{generate, GeneratorConfig};
+/// use e2rs::model::BoardShape;
+///
+/// let mut rng = rand::thread_rng();
+/// let config = |columns, rows| GeneratorConfig {
+///     shape: BoardShape { columns, rows },
+///     border_colours: 3,
+///     inner_colours: 4,
+/// };
+/// let small = generate(&config(4, 4), &mut rng).unwrap().spec().unwrap();
+/// let large = generate(&config(10, 10), &mut rng).unwrap().spec().unwrap();
+///
+/// let small = difficulty(&small, 200, &mut rng);
+/// let large = difficulty(&large, 200, &mut rng);
+/// assert!(small.log10_tree_size < large.log10_tree_size);
+/// ```
+pub fn difficulty<E, R>(spec: &BoardSpec<E>, probes: usize, rng: &mut R) -> Difficulty
+where
+    E: Copy + Edge + PartialEq + Into<u8>,
+    R: Rng,
+{
+    let mut candidates = 0usize;
+    let mut visited = 0usize;
+    let mut depth = 0usize;
+    let mut log_estimates = Vec::with_capacity(probes);
+
+    for _ in 0..probes {
+        let probe = probe(spec, rng);
+        candidates += probe.branching.iter().sum::<usize>();
+        visited += probe.branching.len();
+        depth += probe.depth;
+        log_estimates.push(log10_tree_size(&probe.branching));
+    }
+
+    Difficulty {
+        stats: tileset_stats(&spec.tiles),
+        probes,
+        mean_candidates: ratio(candidates, visited),
+        mean_depth: ratio(depth, probes),
+        log10_tree_size: log10_mean(&log_estimates),
+    }
+}
+
+/// The outcome of one random probe of the search tree.
+struct Probe {
+    /// The number of candidates at each cell visited, ending with 0 at a dead end.
+    branching: Vec<usize>,
+    /// The number of cells filled.
+    depth: usize,
+}
+
+fn probe<E, R>(spec: &BoardSpec<E>, rng: &mut R) -> Probe
+where
+    E: Copy + Edge + PartialEq,
+    R: Rng,
+{
+    let shape = spec.dimensions;
+    let tiles = &spec.tiles[..];
+    let mut board = shape.new_board::<E>();
+    let mut used = vec![false; tiles.len()];
+    let mut branching = Vec::new();
+    let mut options: Vec<(usize, Tile<E>)> = Vec::new();
+
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let at = Indx { col, row };
+            options.clear();
+            for (i, tile) in tiles.iter().enumerate() {
+                if used[i] {
+                    continue;
+                }
+                for rotation in ROTATIONS {
+                    let placed = tile.rotate(rotation).apply();
+                    if fits(&board, at, &placed) {
+                        options.push((i, placed));
+                    }
+                }
+            }
+
+            branching.push(options.len());
+            let Some(&(i, placed)) = options.choose(rng) else {
+                return Probe {
+                    depth: branching.len() - 1,
+                    branching,
+                };
+            };
+            used[i] = true;
+            board[at] = Some(placed);
+        }
+    }
+
+    Probe {
+        depth: branching.len(),
+        branching,
+    }
+}
+
+/// Knuth's estimate of the tree size from one probe: 1 + d1 + d1 d2 + ..., as a base 10 log.
+fn log10_tree_size(branching: &[usize]) -> f64 {
+    let mut log_level = 0.0f64;
+    let mut levels = vec![0.0];
+    for &d in branching {
+        if d == 0 {
+            break;
+        }
+        log_level += (d as f64).log10();
+        levels.push(log_level);
+    }
+    log10_sum(&levels)
+}
+
+/// The base 10 log of the mean of values given as base 10 logs.
+pub(crate) fn log10_mean(logs: &[f64]) -> f64 {
+    if logs.is_empty() {
+        return 0.0;
+    }
+    log10_sum(logs) - (logs.len() as f64).log10()
+}
+
+/// The base 10 log of the sum of values given as base 10 logs, without overflowing.
+pub(crate) fn log10_sum(logs: &[f64]) -> f64 {
+    let max = logs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let sum: f64 = logs.iter().map(|l| 10f64.powf(l - max)).sum();
+    max + sum.log10()
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
@@ -0,0 +1,190 @@
+use crate::model::{Board, BoardShape, Indx, Tile, SIDES};
+
+use super::board_diff;
+
+/// How far apart two boards are, by each of the measures in this module.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardDistance {
+    /// The number of cells in which the boards differ, as by [hamming_distance].
+    pub hamming: usize,
+    /// The number of cells in the largest region the boards agree on, as by
+    /// [largest_common_region].
+    pub common_region: usize,
+    /// The fraction of the frame the boards share, as by [frame_similarity].
+    pub frame_similarity: f64,
+}
+
+/// Measure how far apart two boards are, by all of [hamming_distance],
+/// [largest_common_region] and [frame_similarity].
+///
+/// ```
+/// use e2rs::analysis::distance;
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+///
+/// let spec = toy_3x3();
+/// let mut solved = spec.dimensions.new_board();
+/// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+///     clue.apply(&mut solved).unwrap();
+/// }
+/// let same = distance(&solved, &solved);
+/// assert_eq!((same.hamming, same.common_region, same.frame_similarity), (0, 9, 1.0));
+///
+/// let empty = spec.dimensions.new_board();
+/// let apart = distance(&solved, &empty);
+/// assert_eq!((apart.hamming, apart.common_region, apart.frame_similarity), (9, 0, 0.0));
+/// ```
+pub fn distance<E: Copy + PartialEq>(a: &Board<E>, b: &Board<E>) -> BoardDistance {
+    BoardDistance {
+        hamming: hamming_distance(a, b),
+        common_region: largest_common_region(a, b).len(),
+        frame_similarity: frame_similarity(a, b),
+    }
+}
+
+/// The number of cells in which two boards differ.
+///
+/// Cells differ as for [board_diff]: an empty cell differs from any placed tile, and the same
+/// tile turned differently is a different tile.
+///
+/// ```
+/// use e2rs::analysis::hamming_distance;
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+///
+/// let mut clues = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut clues).unwrap();
+/// }
+/// assert_eq!(hamming_distance(&clues, &new_e2board()), 5);
+/// ```
+pub fn hamming_distance<E: Copy + PartialEq>(a: &Board<E>, b: &Board<E>) -> usize {
+    board_diff(a, b).len()
+}
+
+/// The cells of the largest connected region that two boards agree on and that is correctly
+/// matched, in row-major order.
+///
+/// A cell is in common if it holds the same tile, turned the same way, on both boards.
+/// Two neighbouring common cells are connected if their facing edges match, so a region is a
+/// patch of the solution that both boards have found in the same place.
+/// Where several regions are the largest, the one holding the first cell in row-major order is
+/// given.
+///
+/// ```
+/// use e2rs::analysis::largest_common_region;
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::model::Indx;
+///
+/// let spec = toy_3x3();
+/// let mut solved = spec.dimensions.new_board();
+/// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+///     clue.apply(&mut solved).unwrap();
+/// }
+///
+/// // clear the middle row
+/// let mut split = solved.clone();
+/// for col in 0..3 {
+///     split[Indx { col, row: 1 }] = None;
+/// }
+/// assert_eq!(largest_common_region(&solved, &split).len(), 3);
+/// ```
+pub fn largest_common_region<E: Copy + PartialEq>(a: &Board<E>, b: &Board<E>) -> Vec<Indx> {
+    let shape = BoardShape {
+        columns: a.columns.min(b.columns),
+        rows: a.rows.min(b.rows),
+    };
+    let common = |at: Indx| -> Option<Tile<E>> {
+        match (&a[at], &b[at]) {
+            (Some(x), Some(y)) if x == y => Some(*x),
+            _ => None,
+        }
+    };
+    let cell = |at: Indx| at.col + at.row * shape.columns;
+
+    let mut seen = vec![false; shape.columns * shape.rows];
+    let mut largest = Vec::new();
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let start = Indx { col, row };
+            if seen[cell(start)] || common(start).is_none() {
+                continue;
+            }
+            seen[cell(start)] = true;
+            let mut region = vec![start];
+            let mut next = 0;
+            while let Some(&at) = region.get(next) {
+                next += 1;
+                let Some(tile) = common(at) else {
+                    continue;
+                };
+                for side in SIDES {
+                    let Some(near) = shape.neighbour(at, side) else {
+                        continue;
+                    };
+                    if seen[cell(near)] {
+                        continue;
+                    }
+                    if common(near).is_some_and(|other| tile[side] == other[side.flip()]) {
+                        seen[cell(near)] = true;
+                        region.push(near);
+                    }
+                }
+            }
+            if region.len() > largest.len() {
+                largest = region;
+            }
+        }
+    }
+    largest.sort_by_key(|at| (at.row, at.col));
+    largest
+}
+
+/// The fraction of the frame, the cells around the border of the board, in which two boards
+/// hold the same tile turned the same way, from 0 to 1.
+///
+/// Frames are often built first and kept, so this says how much two boards have committed to
+/// the same frame whatever they have done inside it.
+/// Cells empty on both boards don't count as shared.
+/// Boards of different shapes have no frame in common and score 0.
+///
+/// ```
+/// use e2rs::analysis::frame_similarity;
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::model::Indx;
+///
+/// let spec = toy_3x3();
+/// let mut solved = spec.dimensions.new_board();
+/// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+///     clue.apply(&mut solved).unwrap();
+/// }
+/// let mut frame_only = solved.clone();
+/// frame_only[Indx { col: 1, row: 1 }] = None;
+/// assert_eq!(frame_similarity(&solved, &frame_only), 1.0);
+///
+/// frame_only[Indx { col: 0, row: 0 }] = None;
+/// assert_eq!(frame_similarity(&solved, &frame_only), 7.0 / 8.0);
+/// ```
+pub fn frame_similarity<E: Copy + PartialEq>(a: &Board<E>, b: &Board<E>) -> f64 {
+    let shape = a.shape();
+    if shape != b.shape() {
+        return 0.0;
+    }
+    let mut frame = 0;
+    let mut shared = 0;
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let at = Indx { col, row };
+            if !SIDES.iter().any(|side| shape.on_border(at, *side)) {
+                continue;
+            }
+            frame += 1;
+            if a[at].is_some() && a[at] == b[at] {
+                shared += 1;
+            }
+        }
+    }
+    if frame == 0 {
+        0.0
+    } else {
+        shared as f64 / frame as f64
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+
+use crate::model::{Rotate, Rotation, Side, Tile, TileId, TileSet, ROTATIONS};
+
+/// A witness that two tilesets are the same puzzle, as found by [isomorphic].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Isomorphism {
+    /// The colour of the second tileset that each colour of the first becomes, indexed by the
+    /// colour in the first.
+    ///
+    /// The outside edge, 0, always maps to itself.
+    /// Colours that don't appear in the first tileset map to 0.
+    pub colours: Vec<u8>,
+    /// The tile of the second tileset that each tile of the first becomes, in the order of the
+    /// first tileset.
+    ///
+    /// Each tile of the first tileset, recoloured with [Isomorphism::colours], is the matching
+    /// tile of the second placed with the given rotation.
+    pub tiles: Vec<(TileId, Rotation)>,
+}
+
+/// Determine whether two tilesets are the same puzzle, up to the naming of edge colours and
+/// the numbering and rotation of tiles.
+///
+/// The outside edge is never renamed.
+/// Returns the mapping from the first tileset to the second, if there is one.
+///
+/// The search over colour mappings tries the correspondence given by
+/// [TileSet::canonical_recolouring] first, which settles most cases at once, and otherwise pairs
+/// colours of equal frequency, backtracking as soon as a recoloured tile has no partner left.
+///
+/// ```
+/// use e2rs::analysis::isomorphic;
+/// use e2rs::e2::E2_BOARD_SPEC;
+/// use e2rs::model::{Rotate, Rotation, Side, Tile, TileSet};
+///
+/// let tiles = E2_BOARD_SPEC.tiles.remap_edges(u8::from);
+///
+/// // republish the puzzle with colours swapped, tiles in reverse order and every tile turned
+/// let republished = TileSet::new(
+///     tiles
+///         .remap_edges(|e| match e { 1 => 2, 2 => 1, e => e })[..]
+///         .iter()
+///         .rev()
+///         .map(|t| t.rotate(Rotation::Rot90).apply())
+///         .collect(),
+/// );
+///
+/// let iso = isomorphic(&tiles, &republished).unwrap();
+/// assert_eq!((iso.colours[1], iso.colours[2]), (2, 1));
+///
+/// let (id, rotation) = iso.tiles[0];
+/// let recoloured = tiles.remap_edges(|e| iso.colours[e as usize]);
+/// assert_eq!(republished[..][id.index()].rotate(rotation).apply(), recoloured[..][0]);
+///
+/// let mut altered = tiles[..].to_vec();
+/// altered[100] = Tile::new(altered[100][Side::East], altered[100][Side::North],
+///                          altered[100][Side::South], altered[100][Side::West]);
+/// assert!(isomorphic(&tiles, &TileSet::new(altered)).is_none());
+/// ```
+pub fn isomorphic<E, F>(a: &TileSet<E>, b: &TileSet<F>) -> Option<Isomorphism>
+where
+    E: Copy + Into<u8>,
+    F: Copy + Into<u8>,
+{
+    let a = a.remap_edges(Into::<u8>::into);
+    let b = b.remap_edges(Into::<u8>::into);
+    if a.len() != b.len() {
+        return None;
+    }
+
+    let a_counts = colour_counts(&a);
+    let b_counts = colour_counts(&b);
+    let (_, a_canonical) = a.canonical_recolouring();
+    let (_, b_canonical) = b.canonical_recolouring();
+    let mut b_from_canonical = [0u8; 256];
+    for (old, new) in b_canonical.iter().enumerate() {
+        b_from_canonical[*new as usize] = old as u8;
+    }
+
+    // assign the colours of `a` in canonical order, the most frequent first, after the outside edge
+    let mut order: Vec<u8> = (1..a_canonical.len())
+        .filter(|c| a_counts[*c] > 0)
+        .map(|c| c as u8)
+        .collect();
+    order.sort_by_key(|c| a_canonical[*c as usize]);
+    order.insert(0, 0);
+
+    let candidates = order
+        .iter()
+        .map(|&c| {
+            if c == 0 {
+                return if a_counts[0] == b_counts[0] { vec![0] } else { vec![] };
+            }
+            let preferred = a_canonical
+                .get(c as usize)
+                .map(|new| b_from_canonical[*new as usize]);
+            let mut ds: Vec<u8> = (1..=255u8)
+                .filter(|d| b_counts[*d as usize] == a_counts[c as usize])
+                .collect();
+            ds.sort_by_key(|d| (Some(*d) != preferred, *d));
+            ds
+        })
+        .collect();
+
+    // each tile of `a` is checked at the step that assigns the last of its colours
+    let mut step_of = [0usize; 256];
+    for (step, c) in order.iter().enumerate() {
+        step_of[*c as usize] = step;
+    }
+    let mut complete = vec![Vec::new(); order.len()];
+    for tile in &a {
+        let edges = edges(tile);
+        let step = edges.iter().map(|e| step_of[*e as usize]).max().unwrap_or(0);
+        complete[step].push(edges);
+    }
+
+    let mut available: HashMap<[u8; 4], usize> = HashMap::new();
+    for tile in &b {
+        *available.entry(canonical(edges(tile))).or_insert(0) += 1;
+    }
+
+    let mut search = Search {
+        order,
+        candidates,
+        complete,
+        available,
+        map: [None; 256],
+        taken: [false; 256],
+    };
+    if !search.assign(0) {
+        return None;
+    }
+
+    let colours: Vec<u8> = (0..a_canonical.len().max(1))
+        .map(|c| search.map[c].unwrap_or(0))
+        .collect();
+
+    // pair up the tiles, now that the recoloured tilesets are known to agree
+    let mut partners: HashMap<[u8; 4], Vec<TileId>> = HashMap::new();
+    for id in b.ids().collect::<Vec<_>>().into_iter().rev() {
+        partners
+            .entry(canonical(edges(&b[..][id.index()])))
+            .or_default()
+            .push(id);
+    }
+    let tiles = a[..]
+        .iter()
+        .map(|tile| {
+            let recoloured = Tile::new(
+                colours[tile[Side::North] as usize],
+                colours[tile[Side::East] as usize],
+                colours[tile[Side::South] as usize],
+                colours[tile[Side::West] as usize],
+            );
+            let id = partners.get_mut(&canonical(edges(&recoloured)))?.pop()?;
+            let partner = &b[..][id.index()];
+            let rotation = ROTATIONS
+                .into_iter()
+                .find(|r| partner.rotate(*r).apply() == recoloured)?;
+            Some((id, rotation))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Isomorphism { colours, tiles })
+}
+
+/// The state of the search for a colour mapping.
+struct Search {
+    /// The colours of the first tileset, in the order they are assigned.
+    order: Vec<u8>,
+    /// The colours of the second tileset each step may assign, in the order to try them.
+    candidates: Vec<Vec<u8>>,
+    /// The tiles of the first tileset whose colours are all assigned by each step.
+    complete: Vec<Vec<[u8; 4]>>,
+    /// The tiles of the second tileset not yet matched, in canonical rotation, with their counts.
+    available: HashMap<[u8; 4], usize>,
+    /// The colour each colour of the first tileset is mapped to so far.
+    map: [Option<u8>; 256],
+    /// The colours of the second tileset mapped to so far.
+    taken: [bool; 256],
+}
+
+impl Search {
+    fn assign(&mut self, step: usize) -> bool {
+        if step == self.order.len() {
+            return true;
+        }
+        let c = self.order[step] as usize;
+
+        for i in 0..self.candidates[step].len() {
+            let d = self.candidates[step][i];
+            if self.taken[d as usize] {
+                continue;
+            }
+            self.map[c] = Some(d);
+            self.taken[d as usize] = true;
+
+            let mut matched = Vec::new();
+            let mut fits = true;
+            for edges in &self.complete[step] {
+                let recoloured = canonical(edges.map(|e| self.map[e as usize].unwrap_or(0)));
+                match self.available.get_mut(&recoloured) {
+                    Some(n) if *n > 0 => {
+                        *n -= 1;
+                        matched.push(recoloured);
+                    }
+                    _ => {
+                        fits = false;
+                        break;
+                    }
+                }
+            }
+            if fits && self.assign(step + 1) {
+                return true;
+            }
+
+            for m in matched {
+                if let Some(n) = self.available.get_mut(&m) {
+                    *n += 1;
+                }
+            }
+            self.map[c] = None;
+            self.taken[d as usize] = false;
+        }
+
+        false
+    }
+}
+
+fn colour_counts(tiles: &TileSet<u8>) -> [usize; 256] {
+    let mut counts = [0; 256];
+    for tile in tiles {
+        for e in edges(tile) {
+            counts[e as usize] += 1;
+        }
+    }
+    counts
+}
+
+fn edges(tile: &Tile<u8>) -> [u8; 4] {
+    [
+        tile[Side::North],
+        tile[Side::East],
+        tile[Side::South],
+        tile[Side::West],
+    ]
+}
+
+/// The least of the rotations of a tile's edges, so that turned copies of a tile compare equal.
+fn canonical(edges: [u8; 4]) -> [u8; 4] {
+    (0..4)
+        .map(|r| {
+            let mut turned = edges;
+            turned.rotate_left(r);
+            turned
+        })
+        .min()
+        .unwrap_or(edges)
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardShape, Indx, Rotation, TileId, TileSet};
+
+/// How often each tile, in each rotation, was found in each cell across a population of boards,
+/// as gathered by [tile_marginals].
+///
+/// The marginals of a population of high-scoring boards show which placements they agree on,
+/// which is a prior for solvers and a guide for deciding which placements to trust.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileMarginals {
+    shape: BoardShape,
+    boards: usize,
+    /// The count of each placement in each cell, in row-major order.
+    counts: Vec<HashMap<(TileId, Rotation), usize>>,
+}
+
+impl TileMarginals {
+    /// The shape of the boards.
+    pub fn shape(&self) -> BoardShape {
+        self.shape
+    }
+
+    /// The number of boards gathered.
+    pub fn boards(&self) -> usize {
+        self.boards
+    }
+
+    /// The placements found in a cell with the fraction of boards holding each, most common
+    /// first, with ties in tile order.
+    ///
+    /// The fractions add up to less than 1 where some boards left the cell empty.
+    pub fn distribution(&self, at: Indx) -> Vec<(TileId, Rotation, f64)> {
+        let Some(counts) = self.cell(at) else {
+            return Vec::new();
+        };
+        let mut found: Vec<_> = counts
+            .iter()
+            .map(|((id, rotation), count)| (*id, *rotation, *count))
+            .collect();
+        found.sort_by(|a, b| {
+            b.2.cmp(&a.2)
+                .then(a.0.cmp(&b.0))
+                .then((a.1 as usize).cmp(&(b.1 as usize)))
+        });
+        found
+            .into_iter()
+            .map(|(id, rotation, count)| (id, rotation, count as f64 / self.boards as f64))
+            .collect()
+    }
+
+    /// The fraction of boards with a tile in a cell, in any rotation.
+    pub fn probability(&self, at: Indx, tile: TileId) -> f64 {
+        let Some(counts) = self.cell(at) else {
+            return 0.0;
+        };
+        let count: usize = counts
+            .iter()
+            .filter(|((id, _), _)| *id == tile)
+            .map(|(_, count)| count)
+            .sum();
+        count as f64 / self.boards.max(1) as f64
+    }
+
+    /// The most common placement in a cell, with the fraction of boards holding it.
+    pub fn most_likely(&self, at: Indx) -> Option<(TileId, Rotation, f64)> {
+        self.distribution(at).into_iter().next()
+    }
+
+    /// How strongly the boards agree on a cell: the fraction holding its most common
+    /// placement, or 0 if no board fills it.
+    pub fn agreement(&self, at: Indx) -> f64 {
+        self.most_likely(at).map_or(0.0, |(_, _, p)| p)
+    }
+
+    fn cell(&self, at: Indx) -> Option<&HashMap<(TileId, Rotation), usize>> {
+        self.shape
+            .contains(at)
+            .then(|| &self.counts[at.col + at.row * self.shape.columns])
+    }
+}
+
+/// Gather the per-cell distributions of placements across a population of boards, such as the
+/// best boards of an archive or the final boards of many solver runs.
+///
+/// Every board must have the shape of the first, and every placed tile must be in the tileset,
+/// as found by [TileSet::identify].
+///
+/// ```
+/// use e2rs::analysis::tile_marginals;
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+///
+/// let mut all = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut all).unwrap();
+/// }
+/// let mut one = new_e2board();
+/// E2_CLUES[0].apply(&mut one).unwrap();
+///
+/// let marginals = tile_marginals(&E2_BOARD_SPEC.tiles, [&all, &one]).unwrap();
+/// assert_eq!(marginals.agreement(E2_CLUES[0].at), 1.0);
+/// assert_eq!(marginals.agreement(E2_CLUES[1].at), 0.5);
+///
+/// let (id, _) = E2_BOARD_SPEC.tiles.identify(&E2_CLUES[1].placed_tile()).unwrap();
+/// assert_eq!(marginals.probability(E2_CLUES[1].at, id), 0.5);
+/// ```
+pub fn tile_marginals<'a, E, I>(
+    tiles: &TileSet<E>,
+    boards: I,
+) -> Result<TileMarginals, PlacementError>
+where
+    E: Copy + PartialEq + 'a,
+    I: IntoIterator<Item = &'a Board<E>>,
+{
+    let mut marginals: Option<TileMarginals> = None;
+    for board in boards {
+        let shape = board.shape();
+        let marginals = marginals.get_or_insert_with(|| TileMarginals {
+            shape,
+            boards: 0,
+            counts: vec![HashMap::new(); shape.columns * shape.rows],
+        });
+        if shape != marginals.shape {
+            return Err(PlacementError::ShapeMismatch {
+                expected: marginals.shape,
+                found: shape,
+            });
+        }
+
+        for row in 0..shape.rows {
+            for col in 0..shape.columns {
+                let at = Indx { col, row };
+                let Some(tile) = &board[at] else {
+                    continue;
+                };
+                let placement = tiles
+                    .identify(tile)
+                    .ok_or(PlacementError::UnknownTile(at))?;
+                *marginals.counts[col + row * shape.columns]
+                    .entry(placement)
+                    .or_default() += 1;
+            }
+        }
+        marginals.boards += 1;
+    }
+
+    Ok(marginals.unwrap_or(TileMarginals {
+        shape: BoardShape {
+            columns: 0,
+            rows: 0,
+        },
+        boards: 0,
+        counts: Vec::new(),
+    }))
+}
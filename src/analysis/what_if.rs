@@ -0,0 +1,154 @@
+use crate::error::PlacementError;
+use crate::model::{Board, Clue, Edge, Indx, Rotate, TileSet, ROTATIONS};
+
+use super::fits;
+
+/// The effect a placement would have on a board, as previewed by [what_if].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WhatIf {
+    /// Whether the placed tile fits the border and the tiles already next to it.
+    pub fits: bool,
+    /// The empty cells whose candidate counts would change, in row-major order.
+    pub changes: Vec<CandidateChange>,
+}
+
+impl WhatIf {
+    /// The cells that would be left with no candidates, having had some before.
+    pub fn dead_ends(&self) -> impl Iterator<Item = Indx> + '_ {
+        self.changes
+            .iter()
+            .filter(|c| c.is_dead_end())
+            .map(|c| c.at)
+    }
+
+    /// True if the placement would leave any cell with no candidates.
+    pub fn creates_dead_end(&self) -> bool {
+        self.dead_ends().next().is_some()
+    }
+}
+
+/// The change in the number of candidates for an empty cell, as found by [what_if].
+///
+/// A candidate is an unused tile of the tileset, in one of its rotations, that fits the border
+/// and the tiles placed next to the cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CandidateChange {
+    /// The cell.
+    pub at: Indx,
+    /// The number of candidates for the cell on the board as it stands.
+    pub before: usize,
+    /// The number of candidates for the cell once the tile is placed.
+    pub after: usize,
+}
+
+impl CandidateChange {
+    /// True if the cell would be left with no candidates, having had some before.
+    pub fn is_dead_end(&self) -> bool {
+        self.before > 0 && self.after == 0
+    }
+}
+
+/// Preview the effect of a placement, without changing the board.
+///
+/// Placing a tile narrows the candidates of the cells next to it, which must now match its
+/// edges, and of every cell that could have taken the same tile, which is no longer free.
+/// Every placed tile must be in the tileset, so that the tiles in use can be told apart.
+/// A tile already in the clue's cell is taken to be replaced, and so freed.
+///
+/// ```
+/// use e2rs::analysis::what_if;
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+/// use e2rs::model::Indx;
+///
+/// let board = new_e2board();
+/// let preview = what_if(&board, &E2_BOARD_SPEC.tiles, &E2_CLUES[0]).unwrap();
+/// assert!(preview.fits);
+/// assert!(!preview.creates_dead_end());
+///
+/// // the cell above the clue must now match its north edge
+/// let above = preview.changes.iter().find(|c| c.at == Indx { col: 7, row: 7 }).unwrap();
+/// assert!(above.after < above.before);
+/// assert!(board[Indx { col: 7, row: 8 }].is_none());
+/// ```
+pub fn what_if<E>(
+    board: &Board<E>,
+    tiles: &TileSet<E>,
+    clue: &Clue<E>,
+) -> Result<WhatIf, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+{
+    let shape = board.shape();
+    if !shape.contains(clue.at) {
+        return Err(PlacementError::OutOfBounds {
+            at: clue.at,
+            columns: shape.columns,
+            rows: shape.rows,
+        });
+    }
+    let placed = clue.placed_tile();
+    let mut after = board.clone();
+    after[clue.at] = None;
+    let fits = fits(&after, clue.at, &placed);
+    after[clue.at] = Some(placed);
+
+    let before_counts = candidate_counts(board, tiles)?;
+    let after_counts = candidate_counts(&after, tiles)?;
+
+    let changes = before_counts
+        .into_iter()
+        .zip(after_counts)
+        .filter_map(|((at, before), (_, after))| match (before, after) {
+            (Some(before), Some(after)) if before != after => {
+                Some(CandidateChange { at, before, after })
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(WhatIf { fits, changes })
+}
+
+/// The number of candidates for each cell in row-major order, or `None` for filled cells.
+fn candidate_counts<E>(
+    board: &Board<E>,
+    tiles: &TileSet<E>,
+) -> Result<Vec<(Indx, Option<usize>)>, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+{
+    let mut used = vec![false; tiles.len()];
+    let mut cells = Vec::with_capacity(board.columns * board.rows);
+    for row in 0..board.rows {
+        for col in 0..board.columns {
+            let at = Indx { col, row };
+            if let Some(placed) = &board[at] {
+                let (id, _) = tiles
+                    .identify(placed)
+                    .ok_or(PlacementError::UnknownTile(at))?;
+                used[id.index()] = true;
+            }
+            cells.push(at);
+        }
+    }
+
+    Ok(cells
+        .into_iter()
+        .map(|at| {
+            let count = board[at].is_none().then(|| {
+                tiles
+                    .into_iter()
+                    .zip(&used)
+                    .filter(|(_, used)| !**used)
+                    .map(|(tile, _)| {
+                        ROTATIONS
+                            .iter()
+                            .filter(|r| fits(board, at, &tile.rotate(**r).apply()))
+                            .count()
+                    })
+                    .sum()
+            });
+            (at, count)
+        })
+        .collect())
+}
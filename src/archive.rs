@@ -0,0 +1,457 @@
+//! A persistent archive of boards, backed by SQLite.
+//!
+//! Long solving campaigns produce a great many partial boards.
+//! The archive stores each one together with its score, a canonical hash and a record of where it
+//! came from, all in a single SQLite file.
+//! The file can be queried directly with SQL, and copied between machines to share results.
+//!
+//! This module is only available with the `archive` feature.
+//!
+//! ## Schema
+//!
+//! Boards live in a single `boards` table:
+//!
+//! | Column    | Type    | Meaning                                              |
+//! |-----------|---------|------------------------------------------------------|
+//! | `id`      | INTEGER | The [ArchiveId] of the board.                        |
+//! | `columns` | INTEGER | Number of columns in the board.                      |
+//! | `rows`    | INTEGER | Number of rows in the board.                         |
+//! | `cells`   | BLOB    | The cells, 5 bytes each, in row-major order.         |
+//! | `placed`  | INTEGER | Number of cells that hold a tile.                    |
+//! | `score`   | INTEGER | The score recorded for the board.                    |
+//! | `hash`    | INTEGER | The canonical hash of the board.                     |
+//! | `source`  | TEXT    | The tool or solver that produced the board.          |
+//! | `seed`    | INTEGER | The random seed the producer ran with, if any.       |
+//! | `note`    | TEXT    | Free-text notes about the board, if any.             |
+//! | `created` | INTEGER | When the board was archived, in seconds since 1970.  |
+//!
+//! Each cell is encoded as a presence byte (0 for empty, 1 for placed) followed by the
+//! north, east, south and west edges of the placed tile.
+//!
+//! ## Queries
+//!
+//! Besides raw SQL via [Archive::connection], the archive answers the queries solvers need to
+//! recombine earlier results: [Archive::best], [Archive::best_containing],
+//! [Archive::compatible_with] and [Archive::nearest].
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use thiserror::Error;
+
+use crate::model::{Board, Side::*, Tile};
+
+mod query;
+
+/// The ID of a board within an archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArchiveId(pub i64);
+
+/// Where an archived board came from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// The tool or solver that produced the board.
+    pub source: String,
+    /// The random seed the producer was run with, if any.
+    pub seed: Option<u64>,
+    /// Free-text notes, for example the host or campaign name.
+    pub note: Option<String>,
+}
+
+impl Provenance {
+    /// Provenance naming just the source of a board.
+    pub fn new<S: Into<String>>(source: S) -> Self {
+        Provenance {
+            source: source.into(),
+            seed: None,
+            note: None,
+        }
+    }
+}
+
+/// A board retrieved from an archive.
+#[derive(Debug)]
+pub struct ArchiveEntry<E> {
+    /// The ID of the board within the archive.
+    pub id: ArchiveId,
+    /// The board itself.
+    pub board: Board<E>,
+    /// The score recorded for the board.
+    pub score: u32,
+    /// The canonical hash of the board.
+    pub hash: u64,
+    /// Where the board came from.
+    pub provenance: Provenance,
+    /// When the board was archived, in seconds since the unix epoch.
+    pub created: u64,
+}
+
+/// Errors raised by the archive.
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    /// The underlying database reported an error.
+    #[error("archive database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    /// A stored board could not be decoded.
+    #[error("archived board {} is corrupt", .0.0)]
+    Corrupt(ArchiveId),
+}
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS boards (
+    id      INTEGER PRIMARY KEY,
+    columns INTEGER NOT NULL,
+    rows    INTEGER NOT NULL,
+    cells   BLOB    NOT NULL,
+    placed  INTEGER NOT NULL,
+    score   INTEGER NOT NULL,
+    hash    INTEGER NOT NULL,
+    source  TEXT    NOT NULL,
+    seed    INTEGER,
+    note    TEXT,
+    created INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS boards_score ON boards (score DESC);
+CREATE INDEX IF NOT EXISTS boards_hash ON boards (hash);
+";
+
+/// A solution archive stored in an SQLite database.
+///
+/// Boards that are identical up to rotation share a canonical hash, and are only stored once.
+///
+/// ```
+/// use e2rs::archive::{Archive, Provenance};
+/// use e2rs::e2::{new_e2board, E2Edge, E2_CLUES};
+///
+/// let archive = Archive::open_in_memory().unwrap();
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+///
+/// let id = archive.insert(&board, 0, &Provenance::new("clues")).unwrap();
+/// assert_eq!(archive.insert(&board, 0, &Provenance::new("again")).unwrap(), id);
+///
+/// let entry = archive.get::<E2Edge>(id).unwrap().unwrap();
+/// assert_eq!(entry.provenance.source, "clues");
+/// ```
+pub struct Archive {
+    conn: Connection,
+}
+
+impl Archive {
+    /// Open the archive in the given file, creating it if necessary.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Archive, ArchiveError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a fresh archive held in memory.
+    ///
+    /// This is mostly useful for tests and short-lived tools.
+    pub fn open_in_memory() -> Result<Archive, ArchiveError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Archive, ArchiveError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Archive { conn })
+    }
+
+    /// The underlying database connection, for running arbitrary SQL against the archive.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Number of boards in the archive.
+    pub fn len(&self) -> Result<usize, ArchiveError> {
+        let count: i64 = self
+            .conn
+            .query_row("SELECT COUNT(*) FROM boards", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Check if the archive holds no boards.
+    pub fn is_empty(&self) -> Result<bool, ArchiveError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Add a board to the archive.
+    ///
+    /// If the board, or a rotation of it, is already archived then the existing ID is returned
+    /// and nothing is written.
+    pub fn insert<E: Copy + Into<u8>>(
+        &self,
+        board: &Board<E>,
+        score: u32,
+        provenance: &Provenance,
+    ) -> Result<ArchiveId, ArchiveError> {
+        insert_into(&self.conn, board, score, provenance)
+    }
+
+    /// Add many boards in a single transaction, all with the same provenance.
+    ///
+    /// This is much faster than calling [Archive::insert] repeatedly.
+    pub fn insert_all<'b, E, I>(
+        &mut self,
+        boards: I,
+        provenance: &Provenance,
+    ) -> Result<Vec<ArchiveId>, ArchiveError>
+    where
+        E: Copy + Into<u8> + 'b,
+        I: IntoIterator<Item = (&'b Board<E>, u32)>,
+    {
+        let tx = self.conn.transaction()?;
+        let ids = boards
+            .into_iter()
+            .map(|(board, score)| insert_into(&tx, board, score, provenance))
+            .collect::<Result<Vec<_>, _>>()?;
+        tx.commit()?;
+        Ok(ids)
+    }
+
+    /// Look up the ID of a board, or of any rotation of it.
+    pub fn find<E: Copy + Into<u8>>(
+        &self,
+        board: &Board<E>,
+    ) -> Result<Option<ArchiveId>, ArchiveError> {
+        find_in(&self.conn, board.columns, board.rows, &encode(board))
+    }
+
+    /// Fetch a board by its ID.
+    pub fn get<E: Copy + From<u8>>(
+        &self,
+        id: ArchiveId,
+    ) -> Result<Option<ArchiveEntry<E>>, ArchiveError> {
+        let entry = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM boards WHERE id = ?1", ENTRY_COLUMNS),
+                params![id.0],
+                RawEntry::from_row,
+            )
+            .optional()?;
+        entry.map(RawEntry::decode).transpose()
+    }
+}
+
+/// The columns read back by [RawEntry::from_row], in order.
+pub(crate) const ENTRY_COLUMNS: &str =
+    "id, columns, rows, cells, score, hash, source, seed, note, created";
+
+/// An archive row, before its board has been decoded.
+pub(crate) struct RawEntry {
+    pub(crate) id: ArchiveId,
+    pub(crate) columns: usize,
+    pub(crate) rows: usize,
+    pub(crate) cells: Vec<u8>,
+    score: u32,
+    hash: u64,
+    provenance: Provenance,
+    created: u64,
+}
+
+impl RawEntry {
+    pub(crate) fn from_row(row: &Row<'_>) -> rusqlite::Result<RawEntry> {
+        Ok(RawEntry {
+            id: ArchiveId(row.get(0)?),
+            columns: row.get::<_, i64>(1)? as usize,
+            rows: row.get::<_, i64>(2)? as usize,
+            cells: row.get(3)?,
+            score: row.get::<_, i64>(4)? as u32,
+            hash: row.get::<_, i64>(5)? as u64,
+            provenance: Provenance {
+                source: row.get(6)?,
+                seed: row.get::<_, Option<i64>>(7)?.map(|s| s as u64),
+                note: row.get(8)?,
+            },
+            created: row.get::<_, i64>(9)? as u64,
+        })
+    }
+
+    pub(crate) fn decode<E: Copy + From<u8>>(self) -> Result<ArchiveEntry<E>, ArchiveError> {
+        let board =
+            decode(self.columns, self.rows, &self.cells).ok_or(ArchiveError::Corrupt(self.id))?;
+        Ok(ArchiveEntry {
+            id: self.id,
+            board,
+            score: self.score,
+            hash: self.hash,
+            provenance: self.provenance,
+            created: self.created,
+        })
+    }
+}
+
+fn insert_into<E: Copy + Into<u8>>(
+    conn: &Connection,
+    board: &Board<E>,
+    score: u32,
+    provenance: &Provenance,
+) -> Result<ArchiveId, ArchiveError> {
+    let cells = encode(board);
+    if let Some(id) = find_in(conn, board.columns, board.rows, &cells)? {
+        return Ok(id);
+    }
+
+    let placed = board.squares.iter().filter(|s| s.is_some()).count();
+    let hash = canonical_hash(board.columns, board.rows, &cells);
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    conn.execute(
+        "INSERT INTO boards (columns, rows, cells, placed, score, hash, source, seed, note, created)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            board.columns as i64,
+            board.rows as i64,
+            cells,
+            placed as i64,
+            score as i64,
+            hash as i64,
+            provenance.source,
+            provenance.seed.map(|s| s as i64),
+            provenance.note,
+            created as i64,
+        ],
+    )?;
+
+    Ok(ArchiveId(conn.last_insert_rowid()))
+}
+
+fn find_in(
+    conn: &Connection,
+    columns: usize,
+    rows: usize,
+    cells: &[u8],
+) -> Result<Option<ArchiveId>, ArchiveError> {
+    let canonical = canonical_cells(columns, rows, cells);
+    let hash = fnv1a(&canonical);
+
+    let mut stmt =
+        conn.prepare_cached("SELECT id, columns, rows, cells FROM boards WHERE hash = ?1")?;
+    let mut matches = stmt.query(params![hash as i64])?;
+    while let Some(row) = matches.next()? {
+        let c = row.get::<_, i64>(1)? as usize;
+        let r = row.get::<_, i64>(2)? as usize;
+        let stored: Vec<u8> = row.get(3)?;
+        if canonical_cells(c, r, &stored) == canonical {
+            return Ok(Some(ArchiveId(row.get(0)?)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Bytes used to encode each cell.
+pub(crate) const CELL_BYTES: usize = 5;
+
+/// Encode the cells of a board, in row-major order.
+pub(crate) fn encode<E: Copy + Into<u8>>(board: &Board<E>) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(board.squares.len() * CELL_BYTES);
+    for square in &board.squares {
+        match square {
+            Some(t) => bytes.extend_from_slice(&[
+                1,
+                t[North].into(),
+                t[East].into(),
+                t[South].into(),
+                t[West].into(),
+            ]),
+            None => bytes.extend_from_slice(&[0; CELL_BYTES]),
+        }
+    }
+    bytes
+}
+
+/// Decode the cells of a board, as written by [encode].
+pub(crate) fn decode<E: Copy + From<u8>>(
+    columns: usize,
+    rows: usize,
+    cells: &[u8],
+) -> Option<Board<E>> {
+    if cells.len() != columns * rows * CELL_BYTES {
+        return None;
+    }
+
+    let squares = cells
+        .chunks_exact(CELL_BYTES)
+        .map(|c| match c[0] {
+            0 => Some(None),
+            1 => Some(Some(Tile::new(
+                c[1].into(),
+                c[2].into(),
+                c[3].into(),
+                c[4].into(),
+            ))),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(Board {
+        columns,
+        rows,
+        squares,
+        hash: None,
+    })
+}
+
+/// Rotate encoded cells a quarter turn anti-clockwise.
+///
+/// The rotated board has the rows and columns swapped.
+fn rotate_cells(columns: usize, rows: usize, cells: &[u8]) -> Vec<u8> {
+    let mut rotated = vec![0; cells.len()];
+    for r in 0..rows {
+        for c in 0..columns {
+            let from = (c + r * columns) * CELL_BYTES;
+            // (c, r) moves to (r, columns - 1 - c) in a board that is `rows` wide
+            let to = (r + (columns - 1 - c) * rows) * CELL_BYTES;
+            let cell = &cells[from..from + CELL_BYTES];
+            rotated[to..to + CELL_BYTES]
+                .copy_from_slice(&[cell[0], cell[2], cell[3], cell[4], cell[1]]);
+        }
+    }
+    rotated
+}
+
+/// The four rotations of encoded cells, each a quarter turn anti-clockwise from the one before
+/// and starting with the cells as they are, with the columns and rows of each.
+pub(crate) fn turns(columns: usize, rows: usize, cells: &[u8]) -> Vec<(usize, usize, Vec<u8>)> {
+    let mut turns = vec![(columns, rows, cells.to_vec())];
+    while turns.len() < 4 {
+        let (c, r, current) = &turns[turns.len() - 1];
+        turns.push((*r, *c, rotate_cells(*c, *r, current)));
+    }
+    turns
+}
+
+/// The canonical key of a board: the smallest encoding over its four rotations, prefixed by its shape.
+fn canonical_cells(columns: usize, rows: usize, cells: &[u8]) -> Vec<u8> {
+    turns(columns, rows, cells)
+        .into_iter()
+        .map(|(c, r, current)| {
+            let mut key = Vec::with_capacity(8 + current.len());
+            key.extend_from_slice(&(c as u32).to_le_bytes());
+            key.extend_from_slice(&(r as u32).to_le_bytes());
+            key.extend_from_slice(&current);
+            key
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// The canonical hash of some encoded cells.
+///
+/// This is stable across platforms and releases, so that archives can be shared.
+pub(crate) fn canonical_hash(columns: usize, rows: usize, cells: &[u8]) -> u64 {
+    fnv1a(&canonical_cells(columns, rows, cells))
+}
+
+/// The 64-bit FNV-1a hash.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
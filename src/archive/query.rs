@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use rusqlite::params;
+
+use crate::model::{Board, Region};
+
+use super::{
+    encode, turns, Archive, ArchiveEntry, ArchiveError, ArchiveId, RawEntry, CELL_BYTES,
+    ENTRY_COLUMNS,
+};
+
+/// Queries for picking boards back out of an archive.
+///
+/// These make the archive a source of material for recombination by solvers.
+/// As [Archive::insert] takes a board and its turns to be the same board, positional queries
+/// compare the boards with every turn of what they are asked about, and give the boards back
+/// turned to match it.
+impl Archive {
+    /// The highest-scoring boards, best first.
+    pub fn best<E: Copy + From<u8>>(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<ArchiveEntry<E>>, ArchiveError> {
+        self.scan_by_score(None, limit, |_| Some(0))
+    }
+
+    /// The highest-scoring boards of the given shape in which every cell of the region is filled.
+    pub fn best_containing<E: Copy + From<u8>>(
+        &self,
+        columns: usize,
+        rows: usize,
+        region: Region,
+        limit: usize,
+    ) -> Result<Vec<ArchiveEntry<E>>, ArchiveError> {
+        if region.cells().any(|at| at.col >= columns || at.row >= rows) {
+            return Ok(Vec::new());
+        }
+        let mut wanted = vec![0; columns * rows * CELL_BYTES];
+        for at in region.cells() {
+            wanted[(at.col + at.row * columns) * CELL_BYTES] = 1;
+        }
+        let turns = turns(columns, rows, &wanted);
+        self.scan_by_score(Some((columns, rows)), limit, |raw| {
+            matching_turn(raw, &turns, |have, want| want[0] == 0 || have[0] != 0)
+        })
+    }
+
+    /// The highest-scoring boards that can be merged with a partial board.
+    ///
+    /// A board can be merged if it has the same shape as the partial, and wherever both boards
+    /// have a tile placed, the tiles are identical.
+    ///
+    /// ```
+    /// use e2rs::archive::{Archive, Provenance};
+    /// use e2rs::e2::{new_e2board, E2Edge, E2_CLUES};
+    /// use e2rs::model::{Rotate, Rotation};
+    ///
+    /// let archive = Archive::open_in_memory().unwrap();
+    /// let mut clues = new_e2board();
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut clues).unwrap();
+    /// }
+    /// archive.insert(&clues, 5, &Provenance::new("clues")).unwrap();
+    ///
+    /// let mut centre = new_e2board();
+    /// E2_CLUES[0].apply(&mut centre).unwrap();
+    /// assert_eq!(archive.compatible_with(&centre, 10).unwrap().len(), 1);
+    ///
+    /// let (distance, _) = archive.nearest::<E2Edge>(&centre, 1).unwrap().remove(0);
+    /// assert_eq!(distance, 4);
+    ///
+    /// // a turned partial finds the same board, turned to match it
+    /// let turned = centre.rotate(Rotation::Rot90);
+    /// let (distance, found) = archive.nearest::<E2Edge>(&turned, 1).unwrap().remove(0);
+    /// assert_eq!(distance, 4);
+    /// assert_eq!(found.board, clues.rotate(Rotation::Rot90));
+    /// ```
+    pub fn compatible_with<E: Copy + Into<u8> + From<u8>>(
+        &self,
+        partial: &Board<E>,
+        limit: usize,
+    ) -> Result<Vec<ArchiveEntry<E>>, ArchiveError> {
+        let turns = turns(partial.columns, partial.rows, &encode(partial));
+        self.scan_by_score(Some((partial.columns, partial.rows)), limit, |raw| {
+            matching_turn(raw, &turns, |have, want| {
+                have[0] == 0 || want[0] == 0 || have == want
+            })
+        })
+    }
+
+    /// The boards of the same shape closest to the given board by Hamming distance, nearest first.
+    ///
+    /// The Hamming distance is the number of cells in which the two boards differ,
+    /// counting an empty cell as differing from any placed tile, as by
+    /// [hamming_distance](crate::analysis::hamming_distance).
+    /// A board's distance is from whichever of its turns is nearest.
+    /// Boards equally near are given in the order they were archived.
+    /// The boards are streamed past the nearest found so far, so only `limit` of them are held
+    /// and decoded, however large the archive.
+    pub fn nearest<E: Copy + PartialEq + Into<u8> + From<u8>>(
+        &self,
+        board: &Board<E>,
+        limit: usize,
+    ) -> Result<Vec<(usize, ArchiveEntry<E>)>, ArchiveError> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM boards
+             WHERE (columns = ?1 AND rows = ?2) OR (columns = ?2 AND rows = ?1)",
+            ENTRY_COLUMNS
+        ))?;
+        let mut rows = stmt.query(params![board.columns as i64, board.rows as i64])?;
+
+        let turns = turns(board.columns, board.rows, &encode(board));
+        let mut nearest = BinaryHeap::with_capacity(limit + 1);
+        while let Some(row) = rows.next()? {
+            let raw = RawEntry::from_row(row)?;
+            let Some((distance, turn)) = turns
+                .iter()
+                .enumerate()
+                .filter(|(_, (c, r, _))| (raw.columns, raw.rows) == (*c, *r))
+                .map(|(turn, (_, _, wanted))| {
+                    let distance = raw
+                        .cells
+                        .chunks_exact(CELL_BYTES)
+                        .zip(wanted.chunks_exact(CELL_BYTES))
+                        .filter(|(have, want)| have != want)
+                        .count();
+                    (distance, turn)
+                })
+                .min()
+            else {
+                continue;
+            };
+            nearest.push(Near {
+                distance,
+                turn,
+                raw,
+            });
+            if nearest.len() > limit {
+                nearest.pop();
+            }
+        }
+        nearest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|near| Ok((near.distance, turn_back(near.raw, near.turn).decode()?)))
+            .collect()
+    }
+
+    /// Walk boards in descending score order, keeping up to `limit` that the filter gives a
+    /// turn for, turned back by it.
+    fn scan_by_score<E, F>(
+        &self,
+        shape: Option<(usize, usize)>,
+        limit: usize,
+        mut keep: F,
+    ) -> Result<Vec<ArchiveEntry<E>>, ArchiveError>
+    where
+        E: Copy + From<u8>,
+        F: FnMut(&RawEntry) -> Option<usize>,
+    {
+        let mut found = Vec::new();
+        if limit == 0 {
+            return Ok(found);
+        }
+
+        let (columns, rows) = shape.map_or((-1, -1), |(c, r)| (c as i64, r as i64));
+        let mut stmt = self.conn.prepare_cached(&format!(
+            "SELECT {} FROM boards
+             WHERE ?1 < 0 OR (columns = ?1 AND rows = ?2) OR (columns = ?2 AND rows = ?1)
+             ORDER BY score DESC, id",
+            ENTRY_COLUMNS
+        ))?;
+        let mut results = stmt.query(params![columns, rows])?;
+
+        while let Some(row) = results.next()? {
+            let raw = RawEntry::from_row(row)?;
+            if let Some(turn) = keep(&raw) {
+                found.push(turn_back(raw, turn).decode()?);
+                if found.len() == limit {
+                    break;
+                }
+            }
+        }
+
+        Ok(found)
+    }
+}
+
+/// The first turn of what was asked about that has the shape of the archived board, and whose
+/// cells each fit the board's, if any.
+fn matching_turn<F>(raw: &RawEntry, turns: &[(usize, usize, Vec<u8>)], fits: F) -> Option<usize>
+where
+    F: Fn(&[u8], &[u8]) -> bool,
+{
+    turns.iter().position(|(columns, rows, wanted)| {
+        (raw.columns, raw.rows) == (*columns, *rows)
+            && raw
+                .cells
+                .chunks_exact(CELL_BYTES)
+                .zip(wanted.chunks_exact(CELL_BYTES))
+                .all(|(have, want)| fits(have, want))
+    })
+}
+
+/// Turn an archived board that matched the given turn of what was asked about, so that it
+/// matches what was asked about as it was given.
+fn turn_back(raw: RawEntry, turn: usize) -> RawEntry {
+    let (columns, rows, cells) =
+        turns(raw.columns, raw.rows, &raw.cells).swap_remove((4 - turn) % 4);
+    RawEntry {
+        columns,
+        rows,
+        cells,
+        ..raw
+    }
+}
+
+/// A board, its distance from the one asked about, and the turn of that board it is nearest,
+/// ordered nearest first, then by ID.
+struct Near {
+    distance: usize,
+    turn: usize,
+    raw: RawEntry,
+}
+
+impl Near {
+    fn key(&self) -> (usize, ArchiveId) {
+        (self.distance, self.raw.id)
+    }
+}
+
+impl PartialEq for Near {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Near {}
+
+impl PartialOrd for Near {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Near {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
@@ -0,0 +1,245 @@
+//! Benchmarking solvers on a standard suite of small puzzles.
+//!
+//! [suite] gives the puzzles, built into the crate so that every run sees the same ones, and
+//! [run] solves each of them with a [Solver], timing it and collecting its node counts into a
+//! [BenchReport].
+//! Comparing the reports of two solvers, or of one solver before and after a change to its
+//! heuristics, shows whether the change helped.
+//!
+//! ```
+//! use e2rs::bench::{run, suite};
+//! use e2rs::solver::{Budget, Registry};
+//!
+//! let registry = Registry::<u8>::standard();
+//! let budget = Budget::default().with_nodes(10_000);
+//! let suite = &suite()[..2];
+//! let plain = run(&mut *registry.create("backtrack", budget.clone(), 0).unwrap(), suite).unwrap();
+//! let fc = run(&mut *registry.create("forward-checking", budget, 0).unwrap(), suite).unwrap();
+//! assert_eq!(plain.solved(), 2);
+//! assert_eq!(fc.solved(), 2);
+//! println!("{}", fc);
+//! ```
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::error::PlacementError;
+use crate::examples::{toy_3x3, toy_4x4, SOLUTION};
+use crate::generate::{generate, GeneratorConfig};
+use crate::model::{BoardShape, BoardSpec, Clue};
+use crate::solver::{SearchStats, SolveOutcome, Solver};
+
+/// A puzzle to benchmark solvers on.
+#[derive(Debug)]
+pub struct Instance<E> {
+    /// The name the puzzle is reported under.
+    pub name: String,
+    /// The puzzle.
+    pub spec: BoardSpec<E>,
+    /// The clues the solver must extend.
+    pub clues: Vec<Clue<E>>,
+}
+
+/// The standard suite of puzzles, from easiest to hardest.
+///
+/// These are the two [examples](crate::examples), the 4x4 one again with a corner and the
+/// centre given as clues, and random puzzles of 5x5 and 6x6 cells made by
+/// [generate] from fixed seeds.
+/// Every puzzle has a solution.
+pub fn suite() -> Vec<Instance<u8>> {
+    let toy = toy_4x4();
+    let solution = &toy.clue_set(SOLUTION).expect("examples have solutions").clues;
+    let clues = solution
+        .iter()
+        .filter(|c| (c.at.col, c.at.row) == (0, 0) || (c.at.col, c.at.row) == (1, 1))
+        .copied()
+        .collect();
+
+    vec![
+        instance("toy-3x3", toy_3x3(), Vec::new()),
+        instance("toy-4x4", toy_4x4(), Vec::new()),
+        instance("toy-4x4-clued", toy, clues),
+        random("random-5x5", 5, 4, 1),
+        random("random-6x6", 6, 5, 2),
+    ]
+}
+
+fn instance(name: &str, spec: BoardSpec<u8>, clues: Vec<Clue<u8>>) -> Instance<u8> {
+    Instance {
+        name: name.to_string(),
+        spec,
+        clues,
+    }
+}
+
+/// A generated square puzzle with 3 border colours.
+fn random(name: &str, side: usize, inner_colours: u8, seed: u64) -> Instance<u8> {
+    let config = GeneratorConfig {
+        shape: BoardShape {
+            columns: side,
+            rows: side,
+        },
+        border_colours: 3,
+        inner_colours,
+    };
+    let puzzle = generate(&config, &mut StdRng::seed_from_u64(seed))
+        .expect("the suite's generator settings are valid");
+    let spec = puzzle.spec().expect("the suite's puzzles are small");
+    instance(name, spec, Vec::new())
+}
+
+/// How a solver's run on one puzzle ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The solver found a solution.
+    Solved,
+    /// The solver showed there is no solution.
+    NoSolution,
+    /// The solver used up its budget first.
+    BudgetExhausted,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Verdict::Solved => "solved",
+            Verdict::NoSolution => "no solution",
+            Verdict::BudgetExhausted => "budget exhausted",
+        })
+    }
+}
+
+/// A solver's run on one puzzle of a suite.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BenchResult {
+    /// The name of the puzzle.
+    pub instance: String,
+    /// How the run ended.
+    pub verdict: Verdict,
+    /// What the solver did.
+    pub stats: SearchStats,
+    /// How long the solver ran for.
+    pub elapsed: Duration,
+}
+
+/// The results of running a solver over a suite, one for each puzzle in suite order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    /// The results.
+    pub results: Vec<BenchResult>,
+}
+
+/// Solve each puzzle of the suite in turn, timing the solver.
+///
+/// Clues the solver can't place are reported as an error, as by [Solver::solve].
+pub fn run<E>(
+    solver: &mut dyn Solver<E>,
+    suite: &[Instance<E>],
+) -> Result<BenchReport, PlacementError> {
+    let mut results = Vec::with_capacity(suite.len());
+    for instance in suite {
+        let start = Instant::now();
+        let outcome = solver.solve(&instance.spec, &instance.clues)?;
+        let elapsed = start.elapsed();
+        let verdict = match &outcome {
+            SolveOutcome::Solved { .. } => Verdict::Solved,
+            SolveOutcome::NoSolution { .. } => Verdict::NoSolution,
+            SolveOutcome::BudgetExhausted { .. } => Verdict::BudgetExhausted,
+        };
+        results.push(BenchResult {
+            instance: instance.name.clone(),
+            verdict,
+            stats: *outcome.stats(),
+            elapsed,
+        });
+    }
+    Ok(BenchReport { results })
+}
+
+impl BenchReport {
+    /// The number of puzzles solved.
+    pub fn solved(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|r| r.verdict == Verdict::Solved)
+            .count()
+    }
+
+    /// The nodes tried over the whole suite.
+    pub fn nodes(&self) -> u64 {
+        self.results.iter().map(|r| r.stats.nodes).sum()
+    }
+
+    /// The time taken over the whole suite.
+    pub fn elapsed(&self) -> Duration {
+        self.results.iter().map(|r| r.elapsed).sum()
+    }
+
+    /// The report as CSV, with a header line and then one line per puzzle, times in seconds.
+    ///
+    /// ```
+    /// use e2rs::bench::{run, suite};
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let report = run(&mut Backtracker::new(), &suite()[..1]).unwrap();
+    /// let csv = report.to_csv();
+    /// let mut lines = csv.lines();
+    /// assert_eq!(lines.next(), Some("instance,verdict,nodes,backtracks,solutions,seconds"));
+    /// assert!(lines.next().unwrap().starts_with("toy-3x3,solved,"));
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("instance,verdict,nodes,backtracks,solutions,seconds\n");
+        for r in &self.results {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.6}\n",
+                r.instance,
+                r.verdict,
+                r.stats.nodes,
+                r.stats.backtracks,
+                r.stats.solutions,
+                r.elapsed.as_secs_f64()
+            ));
+        }
+        csv
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .results
+            .iter()
+            .map(|r| r.instance.len())
+            .chain(["instance".len(), "total".len()])
+            .max()
+            .unwrap_or(0);
+        writeln!(
+            f,
+            "{:<width$}  {:<16}  {:>12}  {:>12}  {:>10}",
+            "instance", "verdict", "nodes", "backtracks", "seconds"
+        )?;
+        for r in &self.results {
+            writeln!(
+                f,
+                "{:<width$}  {:<16}  {:>12}  {:>12}  {:>10.3}",
+                r.instance,
+                r.verdict.to_string(),
+                r.stats.nodes,
+                r.stats.backtracks,
+                r.elapsed.as_secs_f64()
+            )?;
+        }
+        write!(
+            f,
+            "{:<width$}  {:<16}  {:>12}  {:>12}  {:>10.3}",
+            "total",
+            format!("{}/{} solved", self.solved(), self.results.len()),
+            self.nodes(),
+            self.results.iter().map(|r| r.stats.backtracks).sum::<u64>(),
+            self.elapsed().as_secs_f64()
+        )
+    }
+}
@@ -0,0 +1,333 @@
+//! Integration with the [Bevy](https://bevyengine.org) game engine.
+//!
+//! [BoardPlugin] spawns a board as sprites, drawn with the crate's [EdgeArt], and keeps the
+//! sprites in step with a [BoardResource].
+//! Tiles are placed and removed by sending [PlaceTile] and [RemoveTile] events.
+//! Placements are checked with [BoardSpec::validate_clues] against the clues already on the
+//! board, and a [PlacementRejected] event is sent for any that don't fit.
+//! Clicking a placed tile with the left mouse button turns it a quarter turn, subject to the
+//! same checks.
+//!
+//! This module is only available with the `bevy` feature.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use e2rs::bevy::{BoardPlugin, BoardResource};
+//! use e2rs::e2::{board_spec, E2Edge, E2_CLUES};
+//! use e2rs::images::EdgeArt;
+//!
+//! let mut board = BoardResource::new(board_spec(), EdgeArt::e2().unwrap());
+//! for clue in E2_CLUES.iter() {
+//!     board.place(*clue).unwrap();
+//! }
+//!
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins(BoardPlugin::<E2Edge>::default())
+//!     .insert_resource(board)
+//!     .run();
+//! ```
+
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use ::bevy::prelude::*;
+use ::bevy::render::render_asset::RenderAssetUsages;
+use ::bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use ::bevy::window::PrimaryWindow;
+use image::RgbaImage;
+
+use crate::error::RenderError;
+use crate::images::EdgeArt;
+use crate::model::{Board, BoardShape, BoardSpec, Clue, ClueError, Edge, Indx, Rotation, Tile};
+
+/// The bounds an edge type needs to be used in a Bevy app.
+pub trait BevyEdge: Copy + Debug + PartialEq + Edge + Into<u8> + Send + Sync + 'static {}
+
+impl<E: Copy + Debug + PartialEq + Edge + Into<u8> + Send + Sync + 'static> BevyEdge for E {}
+
+/// The puzzle being shown, with the clues placed on it so far.
+#[derive(Resource)]
+pub struct BoardResource<E: BevyEdge> {
+    /// The puzzle.
+    pub spec: BoardSpec<E>,
+    /// The art the tiles are drawn with.
+    pub art: EdgeArt,
+    clues: Vec<Clue<E>>,
+    board: Board<E>,
+}
+
+impl<E: BevyEdge> BoardResource<E> {
+    /// An empty board for a puzzle.
+    pub fn new(spec: BoardSpec<E>, art: EdgeArt) -> Self {
+        let board = spec.dimensions.new_board();
+        BoardResource {
+            spec,
+            art,
+            clues: Vec::new(),
+            board,
+        }
+    }
+
+    /// The board as it stands.
+    pub fn board(&self) -> &Board<E> {
+        &self.board
+    }
+
+    /// The placements on the board, in the order they were made.
+    pub fn clues(&self) -> &[Clue<E>] {
+        &self.clues
+    }
+
+    /// Place a tile, replacing any tile already in its cell.
+    ///
+    /// The placement is checked against the other placements with
+    /// [BoardSpec::validate_clues], and the board is left unchanged if it doesn't fit.
+    pub fn place(&mut self, clue: Clue<E>) -> Result<(), Vec<ClueError>> {
+        let mut clues: Vec<_> = self
+            .clues
+            .iter()
+            .filter(|c| c.at != clue.at)
+            .copied()
+            .collect();
+        clues.push(clue);
+        self.spec.validate_clues(&clues)?;
+
+        self.board[clue.at] = Some(clue.placed_tile());
+        self.clues = clues;
+        Ok(())
+    }
+
+    /// Remove the tile from a cell, returning its placement if there was one.
+    pub fn remove(&mut self, at: Indx) -> Option<Clue<E>> {
+        let i = self.clues.iter().position(|c| c.at == at)?;
+        if self.spec.dimensions.contains(at) {
+            self.board[at] = None;
+        }
+        Some(self.clues.remove(i))
+    }
+}
+
+/// Marks the entity that all the tile sprites of a board are children of.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct BoardComponent {
+    /// The shape of the board.
+    pub shape: BoardShape,
+}
+
+/// Marks the sprite showing the tile in a cell.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileSprite {
+    /// The cell the sprite shows.
+    pub at: Indx,
+}
+
+/// The components of a tile sprite.
+#[derive(Bundle)]
+pub struct TileSpriteBundle {
+    /// The cell the sprite shows.
+    pub tile: TileSprite,
+    /// The sprite itself.
+    pub sprite: SpriteBundle,
+}
+
+/// A request to place a tile on the board.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PlaceTile<E: BevyEdge>(pub Clue<E>);
+
+/// A request to remove the tile from a cell.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct RemoveTile(pub Indx);
+
+/// Sent when a [PlaceTile] request is refused, with the reasons it was refused.
+#[derive(Event, Clone, Debug)]
+pub struct PlacementRejected {
+    /// The cell the tile was to go in.
+    pub at: Indx,
+    /// Why the tile doesn't fit.
+    pub errors: Vec<ClueError>,
+}
+
+/// A plugin that shows a [BoardResource] as sprites, and handles placements on it.
+///
+/// The app must insert a [BoardResource] for the same edge type.
+/// The plugin spawns a 2D camera, unless [BoardPlugin::camera] is turned off.
+pub struct BoardPlugin<E> {
+    /// Whether to spawn a 2D camera looking at the board.
+    pub camera: bool,
+    edge: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for BoardPlugin<E> {
+    fn default() -> Self {
+        BoardPlugin {
+            camera: true,
+            edge: PhantomData,
+        }
+    }
+}
+
+impl<E: BevyEdge> Plugin for BoardPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaceTile<E>>()
+            .add_event::<RemoveTile>()
+            .add_event::<PlacementRejected>()
+            .add_systems(Startup, spawn_board::<E>)
+            .add_systems(
+                Update,
+                (rotate_on_click::<E>, apply_placements::<E>, sync_sprites::<E>).chain(),
+            );
+        if self.camera {
+            app.add_systems(Startup, |mut commands: Commands| {
+                commands.spawn(Camera2dBundle::default());
+            });
+        }
+    }
+}
+
+/// Convert a rendered tile to a Bevy image.
+pub fn to_bevy_image(img: RgbaImage) -> Image {
+    let (width, height) = img.dimensions();
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        img.into_raw(),
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Draw a single tile with the given art.
+fn tile_image<E: BevyEdge>(art: &EdgeArt, tile: &Tile<E>) -> Result<RgbaImage, RenderError> {
+    let size = art.tile_size();
+    let mut img = RgbaImage::new(size, size);
+    art.draw_tile(&mut img, tile)?;
+    Ok(img)
+}
+
+/// The position of the centre of a cell, with the board centred on the origin.
+fn cell_centre(shape: BoardShape, size: f32, at: Indx) -> Vec2 {
+    Vec2::new(
+        (at.col as f32 - (shape.columns as f32 - 1.0) / 2.0) * size,
+        ((shape.rows as f32 - 1.0) / 2.0 - at.row as f32) * size,
+    )
+}
+
+/// The cell under a point, with the board centred on the origin.
+fn cell_at(shape: BoardShape, size: f32, point: Vec2) -> Option<Indx> {
+    let col = point.x / size + shape.columns as f32 / 2.0;
+    let row = shape.rows as f32 / 2.0 - point.y / size;
+    if col < 0.0 || row < 0.0 {
+        return None;
+    }
+    let at = Indx {
+        col: col as usize,
+        row: row as usize,
+    };
+    shape.contains(at).then_some(at)
+}
+
+fn spawn_board<E: BevyEdge>(mut commands: Commands, board: Res<BoardResource<E>>) {
+    commands.spawn((
+        BoardComponent {
+            shape: board.spec.dimensions,
+        },
+        SpatialBundle::default(),
+    ));
+}
+
+fn apply_placements<E: BevyEdge>(
+    mut board: ResMut<BoardResource<E>>,
+    mut places: EventReader<PlaceTile<E>>,
+    mut removes: EventReader<RemoveTile>,
+    mut rejected: EventWriter<PlacementRejected>,
+) {
+    for PlaceTile(clue) in places.read() {
+        if let Err(errors) = board.place(*clue) {
+            rejected.send(PlacementRejected {
+                at: clue.at,
+                errors,
+            });
+        }
+    }
+    for RemoveTile(at) in removes.read() {
+        board.remove(*at);
+    }
+}
+
+fn rotate_on_click<E: BevyEdge>(
+    board: Res<BoardResource<E>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut places: EventWriter<PlaceTile<E>>,
+) {
+    if !buttons.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cursor) = windows.iter().next().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+    let Some(point) = cameras
+        .iter()
+        .find_map(|(camera, transform)| camera.viewport_to_world_2d(transform, cursor))
+    else {
+        return;
+    };
+
+    let size = board.art.tile_size() as f32;
+    let Some(at) = cell_at(board.spec.dimensions, size, point) else {
+        return;
+    };
+    if let Some(clue) = board.clues().iter().find(|c| c.at == at) {
+        places.send(PlaceTile(Clue {
+            rotation: clue.rotation + Rotation::Rot90,
+            ..*clue
+        }));
+    }
+}
+
+fn sync_sprites<E: BevyEdge>(
+    mut commands: Commands,
+    board: Res<BoardResource<E>>,
+    mut images: ResMut<Assets<Image>>,
+    boards: Query<(Entity, &BoardComponent)>,
+    sprites: Query<Entity, With<TileSprite>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+    let Some((parent, component)) = boards.iter().next() else {
+        return;
+    };
+
+    for sprite in &sprites {
+        commands.entity(sprite).despawn();
+    }
+
+    let size = board.art.tile_size() as f32;
+    commands.entity(parent).with_children(|parent| {
+        for clue in board.clues() {
+            let img = match tile_image(&board.art, &clue.placed_tile()) {
+                Ok(img) => img,
+                Err(e) => {
+                    warn!("can't draw the tile at {:?}: {}", clue.at, e);
+                    continue;
+                }
+            };
+            let centre = cell_centre(component.shape, size, clue.at);
+            parent.spawn(TileSpriteBundle {
+                tile: TileSprite { at: clue.at },
+                sprite: SpriteBundle {
+                    texture: images.add(to_bevy_image(img)),
+                    transform: Transform::from_translation(centre.extend(0.0)),
+                    ..default()
+                },
+            });
+        }
+    });
+}
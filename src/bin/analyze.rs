@@ -0,0 +1,71 @@
+use std::fs;
+
+use clap::Parser;
+use rand::{rngs::StdRng, SeedableRng};
+
+use e2rs::analysis::difficulty;
+use e2rs::convert::parse_side_order;
+use e2rs::e2::board_spec;
+use e2rs::error::{Error, IoError};
+use e2rs::model::{parse_tiles_in_order, BoardShape, BoardSpec, Edge};
+
+/// Estimate the difficulty of a puzzle.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// tiles file to analyze (default the Eternity 2 Puzzle)
+    tiles: Option<std::path::PathBuf>,
+    /// side order of the edges in the tiles file
+    #[arg(long, default_value = "nesw")]
+    order: String,
+    /// number of columns on the board (default square)
+    #[arg(long)]
+    columns: Option<usize>,
+    /// number of rows on the board (default square)
+    #[arg(long)]
+    rows: Option<usize>,
+    /// number of random probes of the search tree
+    #[arg(long, default_value_t = 200)]
+    probes: usize,
+    /// seed for the random number generator (default random)
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    match &args.tiles {
+        None => report(&board_spec(), &args),
+        Some(path) => {
+            let txt = fs::read_to_string(path).map_err(|e| IoError::new(path, e))?;
+            let tiles = parse_tiles_in_order::<u8>(&txt, parse_side_order(&args.order)?)?;
+            let side = (tiles.len() as f64).sqrt().round() as usize;
+            let columns = args.columns.unwrap_or(side);
+            let rows = args.rows.unwrap_or(side);
+            if columns * rows != tiles.len() {
+                return Err(Error::InvalidConfig(
+                    "the board must have exactly one cell per tile; give --columns and --rows",
+                ));
+            }
+            let spec = BoardSpec {
+                dimensions: BoardShape { columns, rows },
+                tiles,
+                clue_sets: Vec::new(),
+            };
+            report(&spec, &args)
+        }
+    }
+
+    Ok(())
+}
+
+fn report<E: Copy + Edge + PartialEq + Into<u8>>(spec: &BoardSpec<E>, args: &Cli) {
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    println!("{}", difficulty(spec, args.probes, &mut rng));
+}
@@ -0,0 +1,46 @@
+use std::fs;
+
+use clap::Parser;
+use e2rs::convert::{parse_side_order, reorder_tiles, reverse_clue_rotations};
+use e2rs::error::{Error, IoError};
+
+/// Convert tiles and clues files between conventions.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// file to read
+    input: std::path::PathBuf,
+    /// file to write the converted data to
+    output: std::path::PathBuf,
+    /// rewrite a tiles file from one side order to another, e.g. `nswe:nesw`
+    #[arg(long, value_name = "FROM:TO")]
+    reorder: Option<String>,
+    /// rewrite a clues file between anti-clockwise and clockwise rotations
+    #[arg(long)]
+    reverse_rotation: bool,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+
+    let txt = fs::read_to_string(&args.input).map_err(|e| IoError::new(&args.input, e))?;
+
+    let converted = match (args.reorder, args.reverse_rotation) {
+        (Some(reorder), false) => {
+            let (from, to) = reorder
+                .split_once(':')
+                .ok_or(Error::InvalidConfig("--reorder expects FROM:TO, e.g. nswe:nesw"))?;
+            reorder_tiles(&txt, parse_side_order(from)?, parse_side_order(to)?)?
+        }
+        (None, true) => reverse_clue_rotations(&txt)?,
+        _ => {
+            return Err(Error::InvalidConfig(
+                "give exactly one of --reorder or --reverse-rotation",
+            ))
+        }
+    };
+
+    fs::write(&args.output, converted).map_err(|e| IoError::new(&args.output, e))?;
+
+    Ok(())
+}
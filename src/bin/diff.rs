@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use e2rs::analysis::board_diff;
+use e2rs::e2::{E2Board, E2Tile, E2_BOARD_SPEC};
+use e2rs::error::{Error, IoError, RenderError};
+use e2rs::images::{board_image, outline_cells, tile_size, HIGHLIGHT};
+
+/// Compare two solution files, cell by cell.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// the earlier clues or solution file
+    before: PathBuf,
+    /// the later clues or solution file
+    after: PathBuf,
+    /// read rotations as clockwise (default anti-clockwise)
+    #[arg(long)]
+    clockwise: bool,
+    /// also render the later board to this file, with the changed cells outlined
+    #[arg(long, value_name = "FILE")]
+    image: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    let before = load(&args.before, args.clockwise)?;
+    let after = load(&args.after, args.clockwise)?;
+
+    let diff = board_diff(&before, &after);
+    for change in &diff {
+        println!(
+            "{},{}: {} -> {}",
+            change.at.col,
+            change.at.row,
+            describe(change.before.as_ref()),
+            describe(change.after.as_ref())
+        );
+    }
+    let (was, now) = (before.score() as i64, after.score() as i64);
+    println!("{} cells differ", diff.len());
+    println!("score: {} -> {} ({:+})", was, now, now - was);
+
+    if let Some(path) = &args.image {
+        let mut img = board_image(&after)?;
+        let changed: Vec<_> = diff.iter().map(|d| d.at).collect();
+        let size = tile_size()?;
+        outline_cells(&mut img, size, &changed, (size / 16).max(1), HIGHLIGHT);
+        img.save(path).map_err(RenderError::from)?;
+    }
+
+    Ok(())
+}
+
+/// Read a clues file onto a fresh board.
+fn load(path: &Path, clockwise: bool) -> Result<E2Board, Error> {
+    let txt = fs::read_to_string(path).map_err(|e| IoError::new(path, e))?;
+    let mut board = E2_BOARD_SPEC.dimensions.new_board();
+    for clue in E2_BOARD_SPEC.parse_clues(&txt, clockwise)? {
+        clue.apply(&mut board)?;
+    }
+    Ok(board)
+}
+
+/// A placed tile as its number and anti-clockwise rotation, as in clue files.
+fn describe(tile: Option<&E2Tile>) -> String {
+    match tile {
+        None => "empty".to_string(),
+        Some(tile) => match E2_BOARD_SPEC.tiles.identify(tile) {
+            Some((id, rotation)) => format!("{}.{}", id.number(), rotation as usize),
+            None => "unknown tile".to_string(),
+        },
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::io::BufReader;
+
+use clap::Parser;
+
+use e2rs::e2::{board_spec, E2_BOARD_SPEC};
+use e2rs::error::{Error, IoError};
+use e2rs::model::{BoardShape, Indx, Region, Side::*};
+use e2rs::transform::extract_region;
+
+/// Cut a standalone puzzle out of a region of an Eternity 2 Puzzle solution.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// file to read the (partial) solution from, as clues
+    clues: std::path::PathBuf,
+    /// file to write the tiles of the new puzzle to, in NESW order
+    tiles: std::path::PathBuf,
+    /// file to write the solution of the new puzzle to, as clues
+    solution: std::path::PathBuf,
+    /// the region to cut out, as `COL,ROW,COLUMNSxROWS`, e.g. `0,0,4x4`
+    #[arg(long)]
+    region: String,
+    /// read clue rotations as clockwise (default anti-clockwise)
+    #[arg(long)]
+    clockwise: bool,
+    /// read the clues file as clue sets and use the named set; repeat to combine sets
+    #[arg(long = "clue-set", value_name = "NAME")]
+    clue_sets: Vec<String>,
+}
+
+/// Parse a region written as `COL,ROW,COLUMNSxROWS`.
+fn parse_region(txt: &str) -> Option<Region> {
+    let (col, rest) = txt.split_once(',')?;
+    let (row, size) = rest.split_once(',')?;
+    let (columns, rows) = size.split_once('x')?;
+    Some(Region {
+        origin: Indx {
+            col: col.trim().parse().ok()?,
+            row: row.trim().parse().ok()?,
+        },
+        shape: BoardShape {
+            columns: columns.trim().parse().ok()?,
+            rows: rows.trim().parse().ok()?,
+        },
+    })
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    let region = parse_region(&args.region).ok_or(Error::InvalidConfig(
+        "--region expects COL,ROW,COLUMNSxROWS, e.g. 0,0,4x4",
+    ))?;
+
+    let mut board = E2_BOARD_SPEC.dimensions.new_board();
+    let clues = if args.clue_sets.is_empty() {
+        let file = fs::File::open(&args.clues).map_err(|e| IoError::new(&args.clues, e))?;
+        E2_BOARD_SPEC
+            .read_clues(BufReader::new(file), args.clockwise)
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        let txt = fs::read_to_string(&args.clues).map_err(|e| IoError::new(&args.clues, e))?;
+        let mut spec = board_spec();
+        spec.load_clue_sets(&txt, args.clockwise)?;
+        spec.combine_clue_sets(&args.clue_sets)?
+    };
+    for clue in clues {
+        clue.apply(&mut board)?;
+    }
+
+    let sub = extract_region(&board, &E2_BOARD_SPEC.tiles, region)?;
+    let tiles = sub.spec.tiles.write([North, East, South, West]);
+    fs::write(&args.tiles, tiles).map_err(|e| IoError::new(&args.tiles, e))?;
+    fs::write(&args.solution, sub.solution_file())
+        .map_err(|e| IoError::new(&args.solution, e))?;
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::fs;
+
+use clap::Parser;
+use rand::{rngs::StdRng, SeedableRng};
+
+use e2rs::error::{Error, IoError};
+use e2rs::generate::{generate, GeneratorConfig};
+use e2rs::model::{BoardShape, Side::*};
+
+/// Generate a random puzzle with a known solution.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// file to write the tiles to, in NESW order
+    tiles: std::path::PathBuf,
+    /// file to write the solution to, as clues
+    solution: std::path::PathBuf,
+    /// number of columns on the board
+    #[arg(long, default_value_t = 16)]
+    columns: usize,
+    /// number of rows on the board
+    #[arg(long, default_value_t = 16)]
+    rows: usize,
+    /// number of colours for edges between border tiles
+    #[arg(long, default_value_t = 5)]
+    border_colours: u8,
+    /// number of colours for all other edges between tiles
+    #[arg(long, default_value_t = 17)]
+    inner_colours: u8,
+    /// seed for the random number generator (default random)
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+
+    let config = GeneratorConfig {
+        shape: BoardShape {
+            columns: args.columns,
+            rows: args.rows,
+        },
+        border_colours: args.border_colours,
+        inner_colours: args.inner_colours,
+    };
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let puzzle = generate(&config, &mut rng)?;
+    fs::write(&args.tiles, puzzle.tiles_file([North, East, South, West]))
+        .map_err(|e| IoError::new(&args.tiles, e))?;
+    fs::write(&args.solution, puzzle.solution_file())
+        .map_err(|e| IoError::new(&args.solution, e))?;
+
+    Ok(())
+}
@@ -1,12 +1,16 @@
 #![feature(is_some_and)]
 
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-};
+use std::fs;
 
 use clap::Parser;
-use e2rs::{e2::E2_BOARD_SPEC, images::board_image};
+use e2rs::{
+    e2::{board_spec, E2_BOARD_SPEC},
+    error::{Error, IoError, RenderError},
+    images::{
+        board_image, renditions, PngCompression, PngWriter, SheetGrid, SpriteSheet, THUMBNAIL_SIZE,
+        WEB_SIZE,
+    },
+};
 
 /// Render a clues file to an image.
 #[derive(Parser, Debug)]
@@ -18,25 +22,95 @@ struct Cli {
     image: std::path::PathBuf,
     /// set the rotation direction to clockwise (default anti-clockwise)
     clockwise: Option<bool>,
+    /// draw the pieces from a picture of all 256 pieces in a 16x16 grid, in tile order
+    #[arg(long, value_name = "SHEET")]
+    sprites: Option<std::path::PathBuf>,
+    /// size of each piece in pixels, when drawing from a sprite sheet
+    #[arg(long, default_value_t = 128)]
+    tile_size: u32,
+    /// read the clues file as clue sets and draw the named set; repeat to combine sets
+    #[arg(long = "clue-set", value_name = "NAME")]
+    clue_sets: Vec<String>,
+    /// save a PNG with the fastest encoding, trading file size for speed
+    #[arg(long)]
+    fast_png: bool,
+    /// also write a thumbnail of the board image to this file
+    #[arg(long, value_name = "FILE")]
+    thumbnail: Option<std::path::PathBuf>,
+    /// longest side of the thumbnail in pixels
+    #[arg(long, default_value_t = THUMBNAIL_SIZE)]
+    thumbnail_size: u32,
+    /// also write a mid-size version of the board image, for the web, to this file
+    #[arg(long, value_name = "FILE")]
+    web: Option<std::path::PathBuf>,
+    /// longest side of the web version in pixels
+    #[arg(long, default_value_t = WEB_SIZE)]
+    web_size: u32,
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<(), Error> {
     let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
 
-    let mut solution = BufReader::new(File::open(args.clues)?);
-    let mut solution_txt = String::new();
-    solution.read_to_string(&mut solution_txt)?;
+    let solution_txt =
+        fs::read_to_string(&args.clues).map_err(|e| IoError::new(&args.clues, e))?;
 
-    let clues = E2_BOARD_SPEC
-        .parse_clues(solution_txt.as_str(), args.clockwise.is_some_and(|b| b));
+    let clockwise = args.clockwise.is_some_and(|b| b);
+    let clues = if args.clue_sets.is_empty() {
+        let specs = E2_BOARD_SPEC.parse_clue_specs(solution_txt.as_str(), clockwise)?;
+        match specs.iter().map(|s| s.fixed()).collect::<Option<Vec<_>>>() {
+            Some(clues) => {
+                if let Err(errors) = E2_BOARD_SPEC.validate_clues(&clues) {
+                    for error in errors {
+                        eprintln!("warning: {}", error);
+                    }
+                }
+                clues
+            }
+            // some rotations are open, so the clues must fit for them to be found
+            None => E2_BOARD_SPEC.resolve_clues(&specs)?,
+        }
+    } else {
+        let mut spec = board_spec();
+        spec.load_clue_sets(&solution_txt, clockwise)?;
+        spec.combine_clue_sets(&args.clue_sets)?
+    };
     let mut board = E2_BOARD_SPEC.dimensions.new_board();
 
     for clue in clues {
-        clue.apply(&mut board);
+        clue.apply(&mut board)?;
     }
 
-    let img = board_image(&board);
-    img.save(args.image)?;
+    let img = match &args.sprites {
+        Some(path) => {
+            let sheet = image::open(path).map_err(RenderError::from)?;
+            let grid = SheetGrid::uniform(image::GenericImageView::dimensions(&sheet), 16, 16);
+            SpriteSheet::cut(&sheet, &grid, args.tile_size)?
+                .draw_board(&E2_BOARD_SPEC.tiles, &board)?
+        }
+        None => board_image(&board)?,
+    };
+    let mut png = PngWriter::new(PngCompression::Fastest);
+    let mut save = |img: &image::RgbaImage, path: &std::path::Path| -> Result<(), Error> {
+        if args.fast_png {
+            png.save(img, path)
+        } else {
+            img.save(path).map_err(|e| RenderError::from(e).into())
+        }
+    };
+    save(&img, &args.image)?;
+
+    let smaller: Vec<_> = [
+        (&args.thumbnail, args.thumbnail_size),
+        (&args.web, args.web_size),
+    ]
+    .into_iter()
+    .filter_map(|(path, size)| path.as_ref().map(|p| (p, size)))
+    .collect();
+    let sizes: Vec<_> = smaller.iter().map(|(_, size)| *size).collect();
+    for ((path, _), scaled) in smaller.iter().zip(renditions(&img, &sizes)) {
+        save(&scaled, path)?;
+    }
 
     Ok(())
 }
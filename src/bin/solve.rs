@@ -0,0 +1,289 @@
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use rand::random;
+
+use e2rs::convert::parse_side_order;
+use e2rs::e2::board_spec;
+use e2rs::error::{Error, IoError};
+use e2rs::model::{parse_tiles_in_order, Board, BoardShape, BoardSpec, Clue, Edge, Rotation};
+use e2rs::solver::{
+    Backtracker, Budget, Cancel, Event, EventSink, EventWriter, MostConstrained, Record, Registry,
+    SolveOutcome,
+};
+
+/// Cancelled by the first Ctrl-C, to stop the solver and report the best board it reached.
+static INTERRUPTED: OnceLock<Cancel> = OnceLock::new();
+
+/// Solve a puzzle with one of the solvers, chosen by name.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// tiles file to solve (default the Eternity 2 Puzzle)
+    tiles: Option<PathBuf>,
+    /// side order of the edges in the tiles file
+    #[arg(long, default_value = "nesw")]
+    order: String,
+    /// number of columns on the board (default square)
+    #[arg(long)]
+    columns: Option<usize>,
+    /// number of rows on the board (default square)
+    #[arg(long)]
+    rows: Option<usize>,
+    /// clues file to extend
+    #[arg(long, value_name = "FILE")]
+    clues: Option<PathBuf>,
+    /// read the clues file as clue sets and extend the named set; repeat to combine sets
+    #[arg(long = "clue-set", value_name = "NAME")]
+    clue_sets: Vec<String>,
+    /// read rotations as clockwise (default anti-clockwise)
+    #[arg(long)]
+    clockwise: bool,
+    /// solver to use
+    #[arg(long, default_value = "backtrack")]
+    solver: String,
+    /// list the solvers and exit
+    #[arg(long)]
+    list: bool,
+    /// give up after this many nodes
+    #[arg(long)]
+    nodes: Option<u64>,
+    /// give up after backtracking this many times
+    #[arg(long)]
+    backtracks: Option<u64>,
+    /// give up after this many seconds
+    #[arg(long)]
+    seconds: Option<f64>,
+    /// seed for randomised solvers (default random)
+    #[arg(long)]
+    seed: Option<u64>,
+    /// file to write the solution, or the best board reached, to as clues
+    #[arg(long, value_name = "FILE")]
+    output: Option<PathBuf>,
+    /// directory to write each new best board to, as numbered clues files
+    #[arg(long, value_name = "DIR")]
+    records: Option<PathBuf>,
+    /// file to write a replayable log of every step of the search to
+    #[arg(long, value_name = "FILE")]
+    events: Option<PathBuf>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    match &args.tiles {
+        None => solve(board_spec(), &args),
+        Some(path) => {
+            let txt = fs::read_to_string(path).map_err(|e| IoError::new(path, e))?;
+            let tiles = parse_tiles_in_order::<u8>(&txt, parse_side_order(&args.order)?)?;
+            let side = (tiles.len() as f64).sqrt().round() as usize;
+            let columns = args.columns.unwrap_or(side);
+            let rows = args.rows.unwrap_or(side);
+            if columns * rows != tiles.len() {
+                return Err(Error::InvalidConfig(
+                    "the board must have exactly one cell per tile; give --columns and --rows",
+                ));
+            }
+            let spec = BoardSpec {
+                dimensions: BoardShape { columns, rows },
+                tiles,
+                clue_sets: Vec::new(),
+            };
+            solve(spec, &args)
+        }
+    }
+}
+
+fn solve<E: Copy + Edge + PartialEq + 'static>(
+    mut spec: BoardSpec<E>,
+    args: &Cli,
+) -> Result<(), Error> {
+    let registry = Registry::standard();
+    if args.list {
+        for name in registry.names() {
+            println!(
+                "{}: {}",
+                name,
+                registry.description(name).unwrap_or_default()
+            );
+        }
+        return Ok(());
+    }
+
+    let clues = load_clues(&mut spec, args)?;
+    let spec = &spec;
+    let budget = Budget {
+        nodes: args.nodes,
+        backtracks: args.backtracks,
+        duration: args.seconds.map(Duration::from_secs_f64),
+        cancel: Some(INTERRUPTED.get_or_init(Cancel::new).clone()),
+    };
+    let mut solver = registry
+        .create(
+            &args.solver,
+            budget.clone(),
+            args.seed.unwrap_or_else(random),
+        )
+        .ok_or(Error::InvalidConfig(
+            "there is no solver by that name; use --list to see them",
+        ))?;
+
+    catch_interrupts();
+    let start = Instant::now();
+    let outcome = match (&args.records, &args.events) {
+        (None, None) => solver.solve(spec, &clues)?,
+        (Some(_), Some(_)) => {
+            return Err(Error::InvalidConfig(
+                "--records and --events can't be used together",
+            ))
+        }
+        (None, Some(path)) => {
+            if !matches!(args.solver.as_str(), "backtrack" | "forward-checking") {
+                return Err(Error::InvalidConfig(
+                    "--events needs the backtrack or forward-checking solver",
+                ));
+            }
+            let file = File::create(path).map_err(|e| IoError::new(path, e))?;
+            let mut writer = EventWriter::new(BufWriter::new(file), spec.dimensions)?;
+            let mut count = 0;
+            let mut log = |event: Event| {
+                count += 1;
+                writer.event(event);
+            };
+            let outcome = match args.solver.as_str() {
+                "backtrack" => Backtracker::new()
+                    .with_budget(budget)
+                    .solve_with_events(spec, &clues, &mut log)?,
+                _ => Backtracker::new()
+                    .with_cell_ordering(MostConstrained)
+                    .with_forward_checking()
+                    .with_budget(budget)
+                    .solve_with_events(spec, &clues, &mut log)?,
+            };
+            writer.finish()?;
+            println!("{} events", count);
+            outcome
+        }
+        (Some(dir), None) => {
+            fs::create_dir_all(dir).map_err(|e| IoError::new(dir, e))?;
+            let mut count = 0;
+            let mut failed = None;
+            let outcome = solver.solve_with_hook(spec, &clues, &mut |record: Record<E>| {
+                count += 1;
+                let path = dir.join(format!("record-{:06}.txt", count));
+                let written = clues_file(spec, &record.board).and_then(|txt| {
+                    fs::write(&path, txt).map_err(|e| IoError::new(&path, e).into())
+                });
+                if let Err(e) = written {
+                    failed.get_or_insert(e);
+                }
+            })?;
+            if let Some(e) = failed {
+                return Err(e);
+            }
+            println!("{} records", count);
+            outcome
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let stats = outcome.stats();
+    let board = match &outcome {
+        SolveOutcome::Solved { board, .. } => {
+            println!("solved");
+            Some(board)
+        }
+        SolveOutcome::NoSolution { .. } => {
+            println!("no solution");
+            None
+        }
+        SolveOutcome::BudgetExhausted { best_partial, .. } => {
+            if INTERRUPTED.get().is_some_and(Cancel::is_cancelled) {
+                println!("interrupted");
+            } else {
+                println!("budget exhausted");
+            }
+            Some(best_partial)
+        }
+    };
+    println!(
+        "{} nodes, {} backtracks in {:.3}s",
+        stats.nodes,
+        stats.backtracks,
+        elapsed.as_secs_f64()
+    );
+
+    if let Some(board) = board {
+        println!("score: {}", board.score());
+        if let Some(path) = &args.output {
+            fs::write(path, clues_file(spec, board)?).map_err(|e| IoError::new(path, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Stop the solver on Ctrl-C rather than killing the program, so that the stats and the best
+/// board are still reported; a second Ctrl-C kills it as usual.
+///
+/// This needs the `signals` feature; without it Ctrl-C kills the program straight away.
+#[cfg(all(unix, feature = "signals"))]
+fn catch_interrupts() {
+    extern "C" fn interrupted(_: libc::c_int) {
+        if let Some(cancel) = INTERRUPTED.get() {
+            cancel.cancel();
+        }
+        // SAFETY: signal is async-signal-safe, and restores the default action.
+        unsafe { libc::signal(libc::SIGINT, libc::SIG_DFL) };
+    }
+    // SAFETY: the handler only reads an initialised cell, stores to an atomic and calls signal.
+    unsafe { libc::signal(libc::SIGINT, interrupted as *const () as libc::sighandler_t) };
+}
+
+/// Interrupts kill the program where there are no signal handlers.
+#[cfg(not(all(unix, feature = "signals")))]
+fn catch_interrupts() {}
+
+/// The clues to extend: those of the clues file, or with `--clue-set`, the named sets of the
+/// spec and of the clues file, combined.
+fn load_clues<E: Copy + Edge + PartialEq>(
+    spec: &mut BoardSpec<E>,
+    args: &Cli,
+) -> Result<Vec<Clue<E>>, Error> {
+    let txt = match &args.clues {
+        None => None,
+        Some(path) => Some(fs::read_to_string(path).map_err(|e| IoError::new(path, e))?),
+    };
+    if args.clue_sets.is_empty() {
+        return match txt {
+            None => Ok(Vec::new()),
+            Some(txt) => spec.load_clues(&txt, args.clockwise),
+        };
+    }
+    if let Some(txt) = txt {
+        spec.load_clue_sets(&txt, args.clockwise)?;
+    }
+    Ok(spec.combine_clue_sets(&args.clue_sets)?)
+}
+
+/// The tiles on a board, as a clues file written by [BoardSpec::write_clues].
+fn clues_file<E: Copy + Edge + PartialEq>(
+    spec: &BoardSpec<E>,
+    board: &Board<E>,
+) -> Result<String, Error> {
+    let clues: Vec<Clue<E>> = board
+        .cells()
+        .filter_map(|(at, cell)| {
+            cell.map(|tile| Clue {
+                tile,
+                rotation: Rotation::Rot0,
+                at,
+            })
+        })
+        .collect();
+    Ok(spec.write_clues(&clues)?)
+}
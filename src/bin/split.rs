@@ -0,0 +1,125 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use e2rs::convert::parse_side_order;
+use e2rs::e2::board_spec;
+use e2rs::error::{Error, IoError};
+use e2rs::model::{parse_tiles_in_order, BoardShape, BoardSpec, Clue, Edge};
+use e2rs::solver::Backtracker;
+
+/// Split a puzzle into units of work, each a clues file that can be solved on its own.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// directory to write the units to
+    units: PathBuf,
+    /// tiles file to split (default the Eternity 2 Puzzle)
+    tiles: Option<PathBuf>,
+    /// side order of the edges in the tiles file
+    #[arg(long, default_value = "nesw")]
+    order: String,
+    /// number of columns on the board (default square)
+    #[arg(long)]
+    columns: Option<usize>,
+    /// number of rows on the board (default square)
+    #[arg(long)]
+    rows: Option<usize>,
+    /// clues file to extend
+    #[arg(long, value_name = "FILE")]
+    clues: Option<PathBuf>,
+    /// read the clues file as clue sets and extend the named set; repeat to combine sets
+    #[arg(long = "clue-set", value_name = "NAME")]
+    clue_sets: Vec<String>,
+    /// read rotations as clockwise (default anti-clockwise)
+    #[arg(long)]
+    clockwise: bool,
+    /// number of cells to fill beyond the clues, in row-major order (default one row)
+    #[arg(long)]
+    depth: Option<usize>,
+    /// prune the split with forward checking
+    #[arg(long)]
+    forward_checking: bool,
+    /// leave out units that are turns of others
+    #[arg(long)]
+    symmetry_breaking: bool,
+    /// prune the split by counting edge colours
+    #[arg(long)]
+    edge_counting: bool,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    match &args.tiles {
+        None => split(board_spec(), &args),
+        Some(path) => {
+            let txt = fs::read_to_string(path).map_err(|e| IoError::new(path, e))?;
+            let tiles = parse_tiles_in_order::<u8>(&txt, parse_side_order(&args.order)?)?;
+            let side = (tiles.len() as f64).sqrt().round() as usize;
+            let columns = args.columns.unwrap_or(side);
+            let rows = args.rows.unwrap_or(side);
+            if columns * rows != tiles.len() {
+                return Err(Error::InvalidConfig(
+                    "the board must have exactly one cell per tile; give --columns and --rows",
+                ));
+            }
+            let spec = BoardSpec {
+                dimensions: BoardShape { columns, rows },
+                tiles,
+                clue_sets: Vec::new(),
+            };
+            split(spec, &args)
+        }
+    }
+}
+
+fn split<E: Copy + Edge + PartialEq>(mut spec: BoardSpec<E>, args: &Cli) -> Result<(), Error> {
+    let clues = load_clues(&mut spec, args)?;
+    let spec = &spec;
+
+    let mut solver = Backtracker::new();
+    if args.forward_checking {
+        solver = solver.with_forward_checking();
+    }
+    if args.symmetry_breaking {
+        solver = solver.with_symmetry_breaking();
+    }
+    if args.edge_counting {
+        solver = solver.with_edge_counting();
+    }
+    let depth = args.depth.unwrap_or(spec.dimensions.columns);
+    let split = solver.split(spec, &clues, depth)?;
+
+    fs::create_dir_all(&args.units).map_err(|e| IoError::new(&args.units, e))?;
+    for (i, unit) in split.units.iter().enumerate() {
+        let path = args.units.join(format!("unit-{:06}.txt", i + 1));
+        fs::write(&path, spec.write_clues(unit)?).map_err(|e| IoError::new(&path, e))?;
+    }
+    println!("{} units, {} nodes", split.units.len(), split.stats.nodes);
+    Ok(())
+}
+
+/// The clues to extend: those of the clues file, or with `--clue-set`, the named sets of the
+/// spec and of the clues file, combined.
+fn load_clues<E: Copy + Edge + PartialEq>(
+    spec: &mut BoardSpec<E>,
+    args: &Cli,
+) -> Result<Vec<Clue<E>>, Error> {
+    let txt = match &args.clues {
+        None => None,
+        Some(path) => Some(fs::read_to_string(path).map_err(|e| IoError::new(path, e))?),
+    };
+    if args.clue_sets.is_empty() {
+        return match txt {
+            None => Ok(Vec::new()),
+            Some(txt) => spec.load_clues(&txt, args.clockwise),
+        };
+    }
+    if let Some(txt) = txt {
+        spec.load_clue_sets(&txt, args.clockwise)?;
+    }
+    Ok(spec.combine_clue_sets(&args.clue_sets)?)
+}
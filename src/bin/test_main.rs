@@ -12,6 +12,7 @@ use e2rs::{
 };
 
 fn main() {
+    e2::use_configured_data().expect("configured data is usable");
     let spec = e2::board_spec();
     let tiles = &spec.tiles;
     let dims = &spec.dimensions;
@@ -21,8 +22,8 @@ fn main() {
         println!("{}:\t{:?}", i, t);
     }
 
-    let clue = tiles[tiles.id(139)];
-    println!("{:?}", clue); // todo: tiles are cannonically indexed from 1, not 0
+    let clue = tiles[139];
+    println!("{:?}", clue);
 
     for r in ROTATIONS {
         let rt = clue.rotate(r).apply();
@@ -49,10 +50,10 @@ fn main() {
         };
         println!("Applying clue: {:?}", clue);
 
-        clue.apply(&mut rand_board);
+        clue.apply(&mut rand_board).unwrap();
     }
     println!("Built randomised board.");
-    let rand_img = board_image(&rand_board);
+    let rand_img = board_image(&rand_board).unwrap();
     println!("Constructed board image.");
     rand_img.save("randomised_board.png").unwrap();
     println!("Saved image to file");
@@ -60,8 +61,8 @@ fn main() {
     println!("Creating clue board");
     let mut clue_board = dims.new_board();
     for clue in E2_CLUES.iter() {
-        clue.apply(&mut clue_board);
+        clue.apply(&mut clue_board).unwrap();
     }
-    let clue_img = board_image(&clue_board);
+    let clue_img = board_image(&clue_board).unwrap();
     clue_img.save("clues.png").unwrap();
 }
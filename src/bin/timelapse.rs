@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Parser;
+use e2rs::e2::E2_BOARD_SPEC;
+use e2rs::error::{Error, IoError};
+use e2rs::export::{draw_score_bar, Timelapse};
+use e2rs::images::{board_image, thumbnail, WEB_SIZE};
+
+/// Make a timelapse video from a directory of checkpointed solution files.
+#[derive(Parser, Debug)]
+#[command(author, version)]
+struct Cli {
+    /// directory of clues files, one per checkpoint, taken in file name order
+    checkpoints: PathBuf,
+    /// video file to write, such as `run.mp4` or `run.webm`
+    video: PathBuf,
+    /// frames per second
+    #[arg(long, default_value_t = 10)]
+    fps: u32,
+    /// longest side of the video in pixels
+    #[arg(long, default_value_t = WEB_SIZE)]
+    size: u32,
+    /// read rotations as clockwise (default anti-clockwise)
+    #[arg(long)]
+    clockwise: bool,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Cli::parse();
+    e2rs::e2::use_configured_data()?;
+
+    let dir = &args.checkpoints;
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| IoError::new(dir, e))?
+        .map(|entry| entry.map(|e| e.path()).map_err(|e| IoError::new(dir, e)))
+        .collect::<Result<_, _>>()?;
+    files.retain(|path| path.is_file());
+    files.sort();
+
+    // the maximum score of the Eternity 2 Puzzle board
+    let shape = E2_BOARD_SPEC.dimensions;
+    let max_score = 2 * shape.columns * shape.rows - shape.columns - shape.rows;
+
+    let mut video: Option<Timelapse> = None;
+    for path in &files {
+        let txt = fs::read_to_string(path).map_err(|e| IoError::new(path, e))?;
+        let mut board = shape.new_board();
+        for clue in E2_BOARD_SPEC.parse_clues(&txt, args.clockwise)? {
+            clue.apply(&mut board)?;
+        }
+
+        let mut frame = thumbnail(&board_image(&board)?, args.size);
+        // video codecs want even frame sizes
+        let (w, h) = frame.dimensions();
+        frame = image::imageops::crop_imm(&frame, 0, 0, w & !1, h & !1).to_image();
+        draw_score_bar(&mut frame, board.score(), max_score);
+
+        let video = match &mut video {
+            Some(video) => video,
+            None => video.insert(Timelapse::start(&args.video, frame.dimensions(), args.fps)?),
+        };
+        video.push(&frame)?;
+        println!("{}: {}", path.display(), board.score());
+    }
+
+    match video {
+        Some(video) => video.finish(),
+        None => Err(Error::InvalidConfig("there are no checkpoint files to make frames from")),
+    }
+}
@@ -0,0 +1,124 @@
+//! Conversion between the conventions used by different data files.
+//!
+//! Tiles files in the wild list their edges in different side orders (for example NESW or NSWE),
+//! and clues files give rotations either anti-clockwise or clockwise.
+//! These functions rewrite files from one convention to another, checking that the result reads
+//! back as the same data.
+
+use thiserror::Error;
+
+use crate::error::ParseError;
+use crate::model::{parse_tiles_in_order, Side, ROTATIONS, SIDES};
+
+/// Errors raised while converting a file.
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    /// The input could not be parsed.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// The converted data did not read back as the original.
+    #[error("converted data does not match the original")]
+    RoundTrip,
+}
+
+/// Parse a side order written as four letters, such as `nesw` or `NSWE`.
+///
+/// ```
+/// use e2rs::convert::parse_side_order;
+/// use e2rs::model::Side::*;
+///
+/// assert!(parse_side_order("nswe").unwrap() == [North, South, West, East]);
+/// assert!(parse_side_order("nnsw").is_err());
+/// ```
+pub fn parse_side_order(txt: &str) -> Result<[Side; 4], ParseError> {
+    let bad = || ParseError::BadSideOrder(txt.to_string());
+
+    let sides = txt
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'n' => Ok(Side::North),
+            'e' => Ok(Side::East),
+            's' => Ok(Side::South),
+            'w' => Ok(Side::West),
+            _ => Err(bad()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let order: [Side; 4] = sides.try_into().map_err(|_| bad())?;
+    if SIDES.iter().all(|s| order.contains(s)) {
+        Ok(order)
+    } else {
+        Err(bad())
+    }
+}
+
+/// Rewrite a tiles file from one side order to another.
+///
+/// The result is parsed back and checked against the input before it is returned.
+pub fn reorder_tiles(txt: &str, from: [Side; 4], to: [Side; 4]) -> Result<String, ConvertError> {
+    let tiles = parse_tiles_in_order::<u8>(txt, from)?;
+    let converted = tiles.write(to);
+
+    let reread = parse_tiles_in_order::<u8>(&converted, to)?;
+    if reread.write(from) != tiles.write(from) {
+        return Err(ConvertError::RoundTrip);
+    }
+
+    Ok(converted)
+}
+
+/// Rewrite a clues file between anti-clockwise and clockwise rotations.
+///
+/// The conversion is its own inverse.
+/// The result is converted back and checked against the input before it is returned.
+///
+/// ```
+/// use e2rs::convert::reverse_clue_rotations;
+///
+/// assert_eq!(reverse_clue_rotations("139 7 8 1\n").unwrap(), "139 7 8 3\n");
+/// ```
+pub fn reverse_clue_rotations(txt: &str) -> Result<String, ConvertError> {
+    let converted = rewrite_clues(txt, true)?;
+    if rewrite_clues(&converted, true)? != rewrite_clues(txt, false)? {
+        return Err(ConvertError::RoundTrip);
+    }
+
+    Ok(converted)
+}
+
+/// Re-emit each clue line, optionally reversing its rotation.
+fn rewrite_clues(txt: &str, reverse: bool) -> Result<String, ParseError> {
+    let mut out = String::new();
+    for (i, line) in txt.lines().enumerate() {
+        let line_no = i + 1;
+        let digits = line
+            .split_whitespace()
+            .map(|d| {
+                d.parse::<usize>().map_err(|_| ParseError::BadNumber {
+                    line: line_no,
+                    text: d.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() != 4 {
+            return Err(ParseError::FieldCount {
+                line: line_no,
+                expected: 4,
+                found: digits.len(),
+            });
+        }
+        let mut rotation = *ROTATIONS.get(digits[3]).ok_or(ParseError::BadRotation {
+            line: line_no,
+            rotation: digits[3],
+        })?;
+        if reverse {
+            rotation = rotation.reverse();
+        }
+
+        out.push_str(&format!(
+            "{} {} {} {}\n",
+            digits[0], digits[1], digits[2], rotation as usize
+        ));
+    }
+    Ok(out)
+}
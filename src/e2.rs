@@ -5,13 +5,18 @@
 //!
 //! For datastructures useful for prepresenting an arbitrary Eternity 2 style puzzle, see [crate::board].
 
-use lazy_static::lazy_static;
-
+use std::borrow::Cow;
 use std::mem::transmute;
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
 
 use embed_doc_image::embed_doc_image;
 
-use crate::model::{BoardSpec, Clue, BoardShape, Board, Tile, TileSet};
+use crate::error::{Error, IoError, ParseError};
+use crate::model::{BoardSpec, Clue, BoardShape, Board, Tile, TileSet, SIDES};
+
+mod data_dir;
+pub use data_dir::*;
 
 /// Number of columns in the Eternity 2 Puzzle.
 pub const E2_COLUMNS: usize = 16;
@@ -45,7 +50,7 @@ pub fn new_e2board() -> E2Board {
     E2_DIMENSIONS.new_board()
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 /// An edge is either *outside* (grey) or one of the 22 two-color patterns.
 ///
@@ -170,9 +175,11 @@ impl From<u8> for E2Edge {
     }
 }
 
-/// Error case for an invalid edge letter.
-#[derive(Debug)]
-pub struct InvalidEdge(char);
+impl From<E2Edge> for u8 {
+    fn from(value: E2Edge) -> Self {
+        value as u8
+    }
+}
 
 /// Parse edge letters into edges.
 ///
@@ -180,11 +187,11 @@ pub struct InvalidEdge(char);
 /// Some applications, such asthe [bucas board renderer][e2.bucas.name] use row-major indexing.
 impl TryFrom<char> for E2Edge {
     // perhaps consider refactoring this so it can be generic for Edge
-    type Error = InvalidEdge;
+    type Error = ParseError;
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value as i32 - 'a' as i32 {
-            v if v < 0 => Err(InvalidEdge(value)),
-            v if v >= EDGES.len() as i32 => Err(InvalidEdge(value)),
+            v if v < 0 => Err(ParseError::InvalidEdge(value)),
+            v if v >= EDGES.len() as i32 => Err(ParseError::InvalidEdge(value)),
             v => Ok(From::from(v as u8)),
         }
     }
@@ -211,28 +218,113 @@ pub const EDGES: [E2Edge; 23] = {
 #[embed_doc_image("e2-edges", "data/E2-Colors.png")]
 static TILE_DATA: &str = include_str!("../data/e2pieces-nesw.txt");
 
-lazy_static! {
-    /// The board spec for the Eternity 2 Puzzle.
-    pub static ref E2_BOARD_SPEC: BoardSpec<E2Edge> = board_spec();
-}
+/// The board spec for the Eternity 2 Puzzle.
+///
+/// This is built from the embedded data, or from the data given to [set_data] if that was
+/// called first.
+pub static E2_BOARD_SPEC: LazyLock<BoardSpec<E2Edge>> = LazyLock::new(board_spec);
 
-/// Retrieve a new copy of the Eternity 2 Puzzle tileset.
+/// Retrieve a new copy of the Eternity 2 Puzzle tileset, with the official clues as a clue set.
 pub fn board_spec() -> BoardSpec<E2Edge> {
+    let data = data();
+    spec_from(&data.tiles, &data.clues).expect("Eternity 2 Puzzle data is checked before use")
+}
+
+/// The tiles and clues files that the Eternity 2 Puzzle data is read from.
+struct E2Data {
+    tiles: Cow<'static, str>,
+    clues: Cow<'static, str>,
+}
+
+/// The data in use, fixed the first time it is needed or by [set_data].
+static DATA: OnceLock<E2Data> = OnceLock::new();
+
+fn data() -> &'static E2Data {
+    DATA.get_or_init(|| E2Data {
+        tiles: Cow::Borrowed(TILE_DATA),
+        clues: Cow::Borrowed(CLUE_DATA),
+    })
+}
+
+/// Build the board spec from a tiles file, in north, east, south, west order, and a clues file.
+fn spec_from(tiles_txt: &str, clues_txt: &str) -> Result<BoardSpec<E2Edge>, Error> {
     use crate::model::Side::*;
-    let tiles =
-        crate::model::parse_tiles::<E2Edge, { North }, { East }, { South }, { West }>(TILE_DATA);
-    assert_eq!(tiles.len(), E2_TILE_COUNT);
+    let numbered =
+        crate::model::parse_tiles::<u8, { North }, { East }, { South }, { West }>(tiles_txt)?;
+    if numbered.len() != E2_TILE_COUNT {
+        return Err(Error::InvalidConfig(
+            "the Eternity 2 Puzzle needs exactly 256 tiles",
+        ));
+    }
+    for (i, tile) in numbered[..].iter().enumerate() {
+        if let Some(side) = SIDES.iter().find(|s| tile[**s] as usize >= E2_EDGE_COUNT) {
+            return Err(ParseError::BadNumber {
+                line: i + 1,
+                text: tile[*side].to_string(),
+            }
+            .into());
+        }
+    }
 
-    BoardSpec {
+    let mut spec = BoardSpec {
         dimensions: E2_DIMENSIONS,
-        tiles: tiles,
-    }
+        tiles: numbered.remap_edges(E2Edge::from),
+        clue_sets: Vec::new(),
+    };
+    let clues = spec.parse_clues(clues_txt, false)?;
+    spec.add_clue_set(E2_OFFICIAL_CLUES, clues)?;
+    Ok(spec)
+}
+
+/// Use other tile and clue data for the Eternity 2 Puzzle in place of the embedded data.
+///
+/// The tiles file lists the edges of each tile in north, east, south, west order, as the
+/// embedded one does, and must hold exactly [E2_TILE_COUNT] tiles with edges numbered below
+/// [E2_EDGE_COUNT].
+/// The clues file is read as by [BoardSpec::parse_clues], and the clues must fit the board.
+/// Both are checked before anything changes.
+///
+/// This must be called before [E2_BOARD_SPEC], [E2_CLUES] or [board_spec] are first used,
+/// as the data is fixed from then on; later calls fail with [Error::InvalidConfig].
+///
+/// ```
+/// use e2rs::e2::{set_data, E2_BOARD_SPEC};
+///
+/// let tiles = std::fs::read_to_string("data/e2pieces-nesw.txt").unwrap();
+/// let clues = "139 7 8 2\n";
+///
+/// let short: String = tiles.lines().skip(1).map(|l| format!("{l}\n")).collect();
+/// assert!(set_data(&short, clues).is_err());
+///
+/// set_data(&tiles, clues).unwrap();
+/// assert_eq!(E2_BOARD_SPEC.clue_sets[0].clues.len(), 1);
+/// assert!(set_data(&tiles, clues).is_err());
+/// ```
+pub fn set_data(tiles_txt: &str, clues_txt: &str) -> Result<(), Error> {
+    spec_from(tiles_txt, clues_txt)?;
+    DATA.set(E2Data {
+        tiles: Cow::Owned(tiles_txt.to_string()),
+        clues: Cow::Owned(clues_txt.to_string()),
+    })
+    .map_err(|_| Error::InvalidConfig("the Eternity 2 Puzzle data is already in use"))
 }
 
+/// Read the tiles and clues files for the Eternity 2 Puzzle, and use them as by [set_data].
+pub fn set_data_files<P: AsRef<Path>, Q: AsRef<Path>>(tiles: P, clues: Q) -> Result<(), Error> {
+    let tiles_txt = std::fs::read_to_string(&tiles).map_err(|e| IoError::new(&tiles, e))?;
+    let clues_txt = std::fs::read_to_string(&clues).map_err(|e| IoError::new(&clues, e))?;
+    set_data(&tiles_txt, &clues_txt)
+}
+
+/// The name of the clue set holding the five official clues, in [board_spec].
+pub const E2_OFFICIAL_CLUES: &str = "official";
+
 /// E2 clues data string literal.
 static CLUE_DATA: &str = include_str!("../data/e2clues.txt");
 
-lazy_static! {
-    /// The five Eternity 2 Puzzle clues.
-    pub static ref E2_CLUES: Vec<Clue<E2Edge>> = E2_BOARD_SPEC.parse_clues(CLUE_DATA, false );
-}
+/// The five Eternity 2 Puzzle clues, or the clues given to [set_data].
+pub static E2_CLUES: LazyLock<Vec<Clue<E2Edge>>> = LazyLock::new(|| {
+    E2_BOARD_SPEC
+        .parse_clues(&data().clues, false)
+        .expect("Eternity 2 Puzzle data is checked before use")
+});
@@ -0,0 +1,114 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, IoError};
+
+use super::{set_data, CLUE_DATA, TILE_DATA};
+
+/// The environment variable naming a data directory, which takes priority over [CONFIG_FILE].
+pub const DATA_DIR_VAR: &str = "E2RS_DATA_DIR";
+
+/// The config file looked for in the working directory when [DATA_DIR_VAR] isn't set.
+///
+/// Each line is a `key = value` pair, and lines starting with `#` are comments.
+/// The only key is `data_dir`, the data directory, relative to the config file if it isn't an
+/// absolute path.
+pub const CONFIG_FILE: &str = "e2rs.conf";
+
+/// The tiles file in a data directory, with edges in north, east, south, west order.
+pub const TILES_FILE: &str = "e2pieces-nesw.txt";
+
+/// The clues file in a data directory.
+pub const CLUES_FILE: &str = "e2clues.txt";
+
+/// The directory of edge images in a data directory, holding `0.png`, `1.png` and so on.
+pub const EDGE_IMAGES_DIR: &str = "edge_images";
+
+/// The data directory chosen by [DATA_DIR_VAR] or [CONFIG_FILE], if either is present.
+pub fn configured_data_dir() -> Result<Option<PathBuf>, Error> {
+    if let Some(dir) = std::env::var_os(DATA_DIR_VAR) {
+        return Ok(Some(PathBuf::from(dir)));
+    }
+
+    let txt = match fs::read_to_string(CONFIG_FILE) {
+        Ok(txt) => txt,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(IoError::new(CONFIG_FILE, e).into()),
+    };
+    for line in txt.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(Error::InvalidConfig("config lines must be `key = value`"));
+        };
+        match key.trim() {
+            "data_dir" => return Ok(Some(PathBuf::from(value.trim()))),
+            _ => return Err(Error::InvalidConfig("the only config key is `data_dir`")),
+        }
+    }
+    Ok(None)
+}
+
+/// Use the data in a directory in place of the embedded Eternity 2 Puzzle data.
+///
+/// The directory may hold any of [TILES_FILE], [CLUES_FILE] and [EDGE_IMAGES_DIR], named as in
+/// the crate's own `data` directory, and the embedded data is used for anything missing.
+/// The data is checked and installed as by [set_data] and
+/// [set_edge_images](crate::images::set_edge_images), so this must be called before the data
+/// is first used.
+///
+/// ```
+/// use e2rs::e2::{load_data_dir, E2_CLUES};
+///
+/// // the crate's own data directory holds the same data as is embedded
+/// load_data_dir("data").unwrap();
+/// assert_eq!(E2_CLUES.len(), 5);
+/// ```
+pub fn load_data_dir<P: AsRef<Path>>(dir: P) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let tiles = read_if_present(&dir.join(TILES_FILE))?;
+    let clues = read_if_present(&dir.join(CLUES_FILE))?;
+    if tiles.is_some() || clues.is_some() {
+        set_data(
+            tiles.as_deref().unwrap_or(TILE_DATA),
+            clues.as_deref().unwrap_or(CLUE_DATA),
+        )?;
+    }
+
+    #[cfg(feature = "images")]
+    {
+        let images = dir.join(EDGE_IMAGES_DIR);
+        let paths: Vec<PathBuf> = (0..)
+            .map(|edge| images.join(format!("{}.png", edge)))
+            .take_while(|path| path.is_file())
+            .collect();
+        if !paths.is_empty() {
+            crate::images::set_edge_image_files(&paths)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Use the data directory chosen by [DATA_DIR_VAR] or [CONFIG_FILE], if any, as by
+/// [load_data_dir], returning the directory used.
+///
+/// Binaries call this at startup, so the embedded data can be corrected or extended without
+/// rebuilding.
+pub fn use_configured_data() -> Result<Option<PathBuf>, Error> {
+    let dir = configured_data_dir()?;
+    if let Some(dir) = &dir {
+        load_data_dir(dir)?;
+    }
+    Ok(dir)
+}
+
+fn read_if_present(path: &Path) -> Result<Option<String>, Error> {
+    match fs::read_to_string(path) {
+        Ok(txt) => Ok(Some(txt)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(IoError::new(path, e).into()),
+    }
+}
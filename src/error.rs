@@ -0,0 +1,278 @@
+//! Errors raised by the crate.
+//!
+//! Each kind of failure has its own error type, so callers can match on the cases they care about.
+//! These all convert into the crate-wide [Error], which is convenient when the details are not
+//! important.
+
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::model::{BoardShape, ClueError, Indx};
+
+/// Any error raised by the crate.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Data could not be parsed.
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    /// Tiles could not be placed.
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    /// A data stream could not be read.
+    #[error(transparent)]
+    Read(#[from] ReadError),
+    /// A board could not be rendered.
+    #[cfg(feature = "images")]
+    #[error(transparent)]
+    Render(#[from] RenderError),
+    /// A file could not be read or written.
+    #[error(transparent)]
+    Io(#[from] IoError),
+    /// Data could not be converted between conventions.
+    #[error(transparent)]
+    Convert(#[from] crate::convert::ConvertError),
+    /// The solution archive failed.
+    #[cfg(feature = "archive")]
+    #[error(transparent)]
+    Archive(#[from] crate::archive::ArchiveError),
+    /// An event log could not be written or read.
+    #[error(transparent)]
+    EventLog(#[from] crate::solver::EventLogError),
+    /// The GPU could not be used.
+    #[cfg(feature = "gpu")]
+    #[error(transparent)]
+    Gpu(#[from] crate::solver::GpuError),
+    /// Settings passed to the crate were unusable.
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(&'static str),
+}
+
+/// Errors raised while parsing data files.
+///
+/// Lines are numbered from 1, as they would be in an editor.
+///
+/// ```
+/// use e2rs::error::ParseError;
+/// use e2rs::model::{parse_tiles_in_order, SIDES};
+///
+/// let err = parse_tiles_in_order::<u8>("1 2 3 4\n1 2 x 4\n", SIDES).unwrap_err();
+/// assert_eq!(err, ParseError::BadNumber { line: 2, text: "x".to_string() });
+/// ```
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line had the wrong number of fields.
+    #[error("line {line}: expected {expected} numbers, found {found}")]
+    FieldCount {
+        /// The line with the problem.
+        line: usize,
+        /// How many fields the line should have.
+        expected: usize,
+        /// How many fields the line has.
+        found: usize,
+    },
+    /// A field was not a valid number.
+    #[error("line {line}: `{text}` is not a valid number")]
+    BadNumber {
+        /// The line with the problem.
+        line: usize,
+        /// The text of the field.
+        text: String,
+    },
+    /// A clue referred to a tile that is not in the tileset.
+    #[error("line {line}: there is no tile numbered {tile}")]
+    UnknownTile {
+        /// The line with the problem.
+        line: usize,
+        /// The tile number given.
+        tile: usize,
+    },
+    /// A clue gave a rotation outside `0..=3`.
+    #[error("line {line}: {rotation} is not a rotation, expected 0 to 3")]
+    BadRotation {
+        /// The line with the problem.
+        line: usize,
+        /// The rotation given.
+        rotation: usize,
+    },
+    /// A clue left its rotation open where a fixed rotation is needed.
+    #[error("line {line}: the rotation is open, but a fixed rotation is needed here")]
+    OpenRotation {
+        /// The line with the problem.
+        line: usize,
+    },
+    /// A board code was malformed.
+    #[error("invalid board code: {0}")]
+    BadCode(&'static str),
+    /// A tiles file held more tiles than a tileset can.
+    #[error("{0} tiles given, but tilesets hold at most 256")]
+    TooManyTiles(usize),
+    /// A character was not an edge letter.
+    #[error("`{0}` is not an edge letter")]
+    InvalidEdge(char),
+    /// A side order was not a permutation of the four sides.
+    #[error("`{0}` is not a side order")]
+    BadSideOrder(String),
+}
+
+/// Errors raised while streaming a data file, with where in the stream they were found.
+///
+/// Byte offsets count from 0 at the start of the stream, and give the start of the line with the
+/// problem.
+#[derive(Debug, Error)]
+pub enum ReadError {
+    /// The stream could not be read, or was not valid UTF-8.
+    #[error("byte {byte}: {source}")]
+    Io {
+        /// The offset of the line that could not be read.
+        byte: u64,
+        /// The underlying error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A line could not be parsed.
+    #[error("byte {byte}, {source}")]
+    Parse {
+        /// The offset of the line.
+        byte: u64,
+        /// The problem, which names the line.
+        #[source]
+        source: ParseError,
+    },
+    /// A clue did not fit the board, or the clues before it.
+    #[error("line {line} (byte {byte}): {source}")]
+    Clue {
+        /// The line of the clue, counting from 1.
+        line: usize,
+        /// The offset of the line.
+        byte: u64,
+        /// The problem.
+        #[source]
+        source: ClueError,
+    },
+}
+
+/// Errors raised while placing tiles on a board.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum PlacementError {
+    /// A tile was placed outside the board.
+    #[error("cell {},{} is outside the {columns}x{rows} board", at.col, at.row)]
+    OutOfBounds {
+        /// Where the tile was placed.
+        at: Indx,
+        /// Number of columns in the board.
+        columns: usize,
+        /// Number of rows in the board.
+        rows: usize,
+    },
+    /// Clues did not fit the board, as found by
+    /// [BoardSpec::validate_clues](crate::model::BoardSpec::validate_clues).
+    #[error("{}", summarise_clues(.0))]
+    Clues(Vec<ClueError>),
+    /// A cell that needed a tile was empty.
+    #[error("cell {},{} is empty", .0.col, .0.row)]
+    EmptyCell(Indx),
+    /// A placed tile did not match any tile in the tileset.
+    #[error("the tile at {},{} is not in the tileset", .0.col, .0.row)]
+    UnknownTile(Indx),
+    /// Boards that should all be the same shape were not.
+    #[error("a {}x{} board was given, but the boards are {}x{}", found.columns, found.rows, expected.columns, expected.rows)]
+    ShapeMismatch {
+        /// The shape of the boards.
+        expected: BoardShape,
+        /// The shape of the board that differs.
+        found: BoardShape,
+    },
+    /// No choice of rotations for the clues with open rotations fits the board and the other
+    /// clues.
+    #[error("no rotations of the open clues fit the board and the other clues")]
+    UnresolvedRotations,
+    /// No unused tile could be placed in a cell.
+    #[error("no unused tile can be placed in cell {},{}", .0.col, .0.row)]
+    NoTileFor(Indx),
+    /// A clue set was asked for by a name the board spec doesn't carry.
+    #[error("no clue set named '{0}'")]
+    UnknownClueSet(String),
+}
+
+fn summarise_clues(errors: &[ClueError]) -> String {
+    match errors {
+        [] => "invalid clues".to_string(),
+        [only] => only.to_string(),
+        [first, rest @ ..] => format!("{} (and {} more clue problems)", first, rest.len()),
+    }
+}
+
+/// Errors raised while rendering boards as images.
+#[cfg(feature = "images")]
+#[derive(Debug, Error)]
+pub enum RenderError {
+    /// The image library reported an error.
+    #[error("image error: {0}")]
+    Image(#[from] image::ImageError),
+    /// An embedded edge image could not be used.
+    #[error("edge image {edge} is unusable: {reason}")]
+    Resource {
+        /// The edge whose image is broken.
+        edge: usize,
+        /// What is wrong with the image.
+        reason: String,
+    },
+    /// A tile has an edge with no image.
+    #[error("there is no image for edge {0}")]
+    UnknownEdge(usize),
+    /// An image to draw a tile into was not the size of a tile.
+    #[error("tile images are {}x{}, but the target is {}x{}", expected.0, expected.1, found.0, found.1)]
+    SizeMismatch {
+        /// The size of a tile image.
+        expected: (u32, u32),
+        /// The size of the target image.
+        found: (u32, u32),
+    },
+    /// A sprite sheet was too small for the grid it was to be cut along.
+    #[error("the sprite grid needs a {}x{} sheet, but the sheet is {}x{}", needed.0, needed.1, found.0, found.1)]
+    SheetTooSmall {
+        /// The size of image the grid covers.
+        needed: (u32, u32),
+        /// The size of the sheet.
+        found: (u32, u32),
+    },
+    /// A placed tile did not match any tile in the tileset.
+    #[error("the tile at {},{} is not in the tileset", .0.col, .0.row)]
+    UnidentifiedTile(Indx),
+    /// A tile had no sprite.
+    #[error("there is no sprite for tile {0}")]
+    MissingSprite(usize),
+    /// An external video encoder failed.
+    #[error("video encoding failed: {0}")]
+    Encoder(String),
+    /// A board was too large to render as a single image.
+    #[error("a {columns}x{rows} board is too large to render")]
+    TooLarge {
+        /// Number of columns in the board.
+        columns: usize,
+        /// Number of rows in the board.
+        rows: usize,
+    },
+}
+
+/// A file could not be read or written.
+#[derive(Debug, Error)]
+#[error("{}: {source}", path.display())]
+pub struct IoError {
+    /// The file being accessed.
+    pub path: PathBuf,
+    /// The underlying error.
+    #[source]
+    pub source: std::io::Error,
+}
+
+impl IoError {
+    /// Wrap an IO error with the path of the file that caused it.
+    pub fn new<P: AsRef<Path>>(path: P, source: std::io::Error) -> Self {
+        IoError {
+            path: path.as_ref().to_path_buf(),
+            source,
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Tiny example puzzles, for learning the API and trying out analyses and solvers.
+//!
+//! Each puzzle is small enough to solve by hand, and has exactly one solution, up to turning the
+//! whole board.
+//! The solution is given as the [SOLUTION] clue set, which places every tile.
+//!
+//! ```
+//! use e2rs::examples::{toy_4x4, SOLUTION};
+//!
+//! let spec = toy_4x4();
+//! let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+//! assert_eq!(solution.len(), spec.tiles.len());
+//!
+//! let mut board = spec.dimensions.new_board();
+//! for clue in solution {
+//!     clue.apply(&mut board).unwrap();
+//! }
+//! ```
+
+use crate::model::{parse_tiles, BoardShape, BoardSpec, Side::*};
+
+/// The name of the clue set holding the solution of each example puzzle.
+pub const SOLUTION: &str = "solution";
+
+/// The 3x3 tiles, in north, east, south, west order.
+static TOY_3X3_TILES: &str = concat!(
+    "1 1 0 0\n",
+    "1 4 3 0\n",
+    "2 3 0 0\n",
+    "3 1 0 0\n",
+    "0 2 6 2\n",
+    "6 1 0 3\n",
+    "4 6 6 5\n",
+    "1 5 3 0\n",
+    "0 3 2 0\n",
+);
+
+/// The 3x3 solution.
+static TOY_3X3_SOLUTION: &str = concat!(
+    "1 0 0 1\n",
+    "6 1 0 2\n",
+    "3 2 0 2\n",
+    "2 0 1 0\n",
+    "7 1 1 3\n",
+    "5 2 1 1\n",
+    "4 0 2 0\n",
+    "8 1 2 3\n",
+    "9 2 2 2\n",
+);
+
+/// The 4x4 tiles, in north, east, south, west order.
+static TOY_4X4_TILES: &str = concat!(
+    "5 2 0 2\n",
+    "2 7 3 0\n",
+    "3 5 2 0\n",
+    "7 2 0 1\n",
+    "0 1 2 0\n",
+    "3 0 0 3\n",
+    "4 5 6 6\n",
+    "0 0 2 3\n",
+    "4 7 7 5\n",
+    "2 0 3 4\n",
+    "5 6 5 5\n",
+    "6 3 0 2\n",
+    "4 1 0 3\n",
+    "6 7 7 5\n",
+    "3 6 3 0\n",
+    "0 2 1 0\n",
+);
+
+/// The 4x4 solution.
+static TOY_4X4_SOLUTION: &str = concat!(
+    "5 0 0 0\n",
+    "13 1 0 2\n",
+    "15 2 0 1\n",
+    "8 3 0 0\n",
+    "12 0 1 1\n",
+    "7 1 1 0\n",
+    "11 2 1 3\n",
+    "1 3 1 3\n",
+    "3 0 2 0\n",
+    "14 1 2 0\n",
+    "9 2 2 1\n",
+    "10 3 2 0\n",
+    "16 0 3 3\n",
+    "4 1 3 0\n",
+    "2 2 3 3\n",
+    "6 3 3 0\n",
+);
+
+/// A 3x3 puzzle with 3 border and 3 inner colours.
+///
+/// With a single inner tile, this is mostly a puzzle about the frame.
+pub fn toy_3x3() -> BoardSpec<u8> {
+    example(3, TOY_3X3_TILES, TOY_3X3_SOLUTION)
+}
+
+/// A 4x4 puzzle with 3 border and 4 inner colours.
+pub fn toy_4x4() -> BoardSpec<u8> {
+    example(4, TOY_4X4_TILES, TOY_4X4_SOLUTION)
+}
+
+fn example(side: usize, tiles_txt: &str, solution_txt: &str) -> BoardSpec<u8> {
+    let tiles = parse_tiles::<u8, { North }, { East }, { South }, { West }>(tiles_txt)
+        .expect("example tile data is valid");
+    let mut spec = BoardSpec {
+        dimensions: BoardShape {
+            columns: side,
+            rows: side,
+        },
+        tiles,
+        clue_sets: Vec::new(),
+    };
+    let solution = spec
+        .parse_clues(solution_txt, false)
+        .expect("example solution data is valid");
+    spec.add_clue_set(SOLUTION, solution)
+        .expect("example solutions fit their boards");
+    spec
+}
@@ -0,0 +1,28 @@
+//! Export boards for other tools.
+//!
+//! These write boards out in formats that game engines and level editors can load directly,
+//! with the tile artwork rendered alongside.
+//!
+//! An [Atlas] holds the distinct tiles of a board in a texture atlas, with their placements.
+//! It can be written out as a JSON manifest for game engines, or as a Tiled map and tileset
+//! for the [Tiled](https://www.mapeditor.org) editor.
+//!
+//! With the `timelapse` feature, a `Timelapse` encodes a run of rendered boards as a video
+//! through `ffmpeg`.
+
+use std::path::Path;
+
+mod atlas;
+pub use atlas::*;
+mod tiled;
+#[cfg(feature = "timelapse")]
+mod timelapse;
+#[cfg(feature = "timelapse")]
+pub use timelapse::*;
+
+/// The file name of a path, for files that refer to each other from the same directory.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
@@ -0,0 +1,230 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use image::{GenericImage, ImageBuffer, RgbaImage};
+
+use crate::error::{Error, IoError, RenderError};
+use crate::images::EdgeArt;
+use crate::model::{Board, BoardShape, Indx, Rotation, TileId, TileSet};
+
+use super::file_name;
+
+/// A texture atlas of the distinct tiles on a board, with a manifest of where they are placed.
+///
+/// The atlas image holds each tile used on the board once, unrotated, in a grid of
+/// [Atlas::tile_size] cells filled in row-major order.
+/// Game engines can draw the board from the atlas and the placements alone, using
+/// [Atlas::manifest_json] to hand over the layout.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+/// use e2rs::export::Atlas;
+/// use e2rs::images::EdgeArt;
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+///
+/// let art = EdgeArt::e2().unwrap();
+/// let atlas = Atlas::new(&board, &E2_BOARD_SPEC.tiles, &art).unwrap();
+/// assert_eq!(atlas.tiles.len(), 5);
+/// assert_eq!(atlas.placements.len(), 5);
+///
+/// let json = atlas.manifest_json("clues.png");
+/// assert!(json.contains("\"image\": \"clues.png\""));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Atlas {
+    /// The shape of the board.
+    pub shape: BoardShape,
+    /// The atlas image.
+    pub image: RgbaImage,
+    /// The width and height of each tile in the atlas, in pixels.
+    pub tile_size: u32,
+    /// Number of tiles across the atlas image.
+    pub columns: u32,
+    /// The tiles in the atlas, in atlas order.
+    pub tiles: Vec<TileId>,
+    /// The placed tiles, in row-major cell order.
+    pub placements: Vec<AtlasPlacement>,
+}
+
+/// A tile placed on the board, as recorded in an [Atlas].
+#[derive(Clone, Copy, Debug)]
+pub struct AtlasPlacement {
+    /// The cell holding the tile.
+    pub at: Indx,
+    /// The position of the tile within [Atlas::tiles].
+    pub index: usize,
+    /// How the atlas image of the tile is turned to place it.
+    ///
+    /// This is the rotation of [Clue::placed_tile](crate::model::Clue::placed_tile), which turns
+    /// images clockwise by a quarter turn per step.
+    pub rotation: Rotation,
+}
+
+impl Atlas {
+    /// Build an atlas of the tiles on a board, drawn with the given art.
+    ///
+    /// Every placed tile must be in the tileset, so that duplicates can be recognised in any
+    /// rotation.
+    pub fn new<E: Copy + PartialEq + Into<u8>>(
+        board: &Board<E>,
+        tiles: &TileSet<E>,
+        art: &EdgeArt,
+    ) -> Result<Self, RenderError> {
+        let mut used: Vec<TileId> = Vec::new();
+        let mut placements = Vec::new();
+        for row in 0..board.rows {
+            for col in 0..board.columns {
+                let at = Indx { col, row };
+                let Some(placed) = &board[at] else {
+                    continue;
+                };
+                let (id, rotation) = tiles
+                    .identify(placed)
+                    .ok_or(RenderError::UnidentifiedTile(at))?;
+                let index = match used.iter().position(|u| *u == id) {
+                    Some(index) => index,
+                    None => {
+                        used.push(id);
+                        used.len() - 1
+                    }
+                };
+                placements.push(AtlasPlacement {
+                    at,
+                    index,
+                    rotation,
+                });
+            }
+        }
+
+        let tile_size = art.tile_size();
+        let columns = (used.len() as f64).sqrt().ceil().max(1.0) as u32;
+        let rows = (used.len() as u32).div_ceil(columns).max(1);
+        let too_large = || RenderError::TooLarge {
+            columns: columns as usize,
+            rows: rows as usize,
+        };
+        let width = columns.checked_mul(tile_size).ok_or_else(too_large)?;
+        let height = rows.checked_mul(tile_size).ok_or_else(too_large)?;
+
+        let mut image = ImageBuffer::new(width, height);
+        for (i, id) in used.iter().enumerate() {
+            let tile = &tiles[..][id.index()];
+            let (x, y) = atlas_position(i, columns, tile_size);
+            let mut cell = image.sub_image(x, y, tile_size, tile_size);
+            art.draw_tile(&mut *cell, tile)?;
+        }
+
+        Ok(Atlas {
+            shape: board.shape(),
+            image,
+            tile_size,
+            columns,
+            tiles: used,
+            placements,
+        })
+    }
+
+    /// The pixel position of the top left corner of a tile in the atlas image.
+    pub fn position(&self, index: usize) -> (u32, u32) {
+        atlas_position(index, self.columns, self.tile_size)
+    }
+
+    /// The placement manifest as JSON, naming the file the atlas image is saved to.
+    ///
+    /// The manifest lists the atlas tiles with their tile numbers and pixel positions,
+    /// and the placed cells with the atlas tile they show and its clockwise quarter turns:
+    ///
+    /// ```json
+    /// {
+    ///   "image": "board.png",
+    ///   "tile_size": 64,
+    ///   "board": { "columns": 16, "rows": 16 },
+    ///   "tiles": [
+    ///     { "index": 0, "tile": 139, "x": 0, "y": 0 }
+    ///   ],
+    ///   "cells": [
+    ///     { "col": 7, "row": 8, "tile": 0, "rotation": 2 }
+    ///   ]
+    /// }
+    /// ```
+    pub fn manifest_json(&self, image: &str) -> String {
+        let mut json = String::new();
+
+        json.push_str("{\n");
+        let _ = writeln!(json, "  \"image\": {},", json_string(image));
+        let _ = writeln!(json, "  \"tile_size\": {},", self.tile_size);
+        let _ = writeln!(
+            json,
+            "  \"board\": {{ \"columns\": {}, \"rows\": {} }},",
+            self.shape.columns, self.shape.rows
+        );
+
+        json.push_str("  \"tiles\": [");
+        for (i, id) in self.tiles.iter().enumerate() {
+            let (x, y) = self.position(i);
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            let _ = write!(
+                json,
+                "{}    {{ \"index\": {}, \"tile\": {}, \"x\": {}, \"y\": {} }}",
+                sep,
+                i,
+                id.number(),
+                x,
+                y
+            );
+        }
+        json.push_str("\n  ],\n");
+
+        json.push_str("  \"cells\": [");
+        for (i, p) in self.placements.iter().enumerate() {
+            let sep = if i == 0 { "\n" } else { ",\n" };
+            let _ = write!(
+                json,
+                "{}    {{ \"col\": {}, \"row\": {}, \"tile\": {}, \"rotation\": {} }}",
+                sep, p.at.col, p.at.row, p.index, p.rotation as usize
+            );
+        }
+        json.push_str("\n  ]\n}\n");
+
+        json
+    }
+
+    /// Save the atlas image and its manifest.
+    ///
+    /// The manifest names the image by its file name, so the two should be kept side by side.
+    pub fn save<P: AsRef<Path>, Q: AsRef<Path>>(&self, image: P, manifest: Q) -> Result<(), Error> {
+        let image = image.as_ref();
+        self.image.save(image).map_err(RenderError::from)?;
+
+        std::fs::write(&manifest, self.manifest_json(&file_name(image)))
+            .map_err(|e| IoError::new(&manifest, e))?;
+        Ok(())
+    }
+}
+
+fn atlas_position(index: usize, columns: u32, tile_size: u32) -> (u32, u32) {
+    let index = index as u32;
+    ((index % columns) * tile_size, (index / columns) * tile_size)
+}
+
+/// Quote a string for JSON.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
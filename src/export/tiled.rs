@@ -0,0 +1,153 @@
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::error::{Error, IoError, RenderError};
+use crate::model::Rotation;
+
+use super::{file_name, Atlas};
+
+/// Tiled flags a tile as flipped horizontally with this bit of its global ID.
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+/// Tiled flags a tile as flipped vertically with this bit of its global ID.
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+/// Tiled flags a tile as flipped across its diagonal with this bit of its global ID.
+const FLIPPED_DIAGONALLY: u32 = 0x2000_0000;
+
+/// The flip flags Tiled uses to show a tile turned clockwise by the given rotation.
+fn rotation_flags(rotation: Rotation) -> u32 {
+    match rotation {
+        Rotation::Rot0 => 0,
+        Rotation::Rot90 => FLIPPED_DIAGONALLY | FLIPPED_HORIZONTALLY,
+        Rotation::Rot180 => FLIPPED_HORIZONTALLY | FLIPPED_VERTICALLY,
+        Rotation::Rot270 => FLIPPED_DIAGONALLY | FLIPPED_VERTICALLY,
+    }
+}
+
+impl Atlas {
+    /// The atlas as a Tiled tileset (TSX), naming the file the atlas image is saved to.
+    ///
+    /// Each tile in the tileset carries a `tile` property with its puzzle tile number.
+    pub fn tiled_tileset(&self, image: &str) -> String {
+        let (width, height) = self.image.dimensions();
+        let mut tsx = String::new();
+        tsx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            tsx,
+            "<tileset version=\"1.10\" name=\"e2rs\" tilewidth=\"{0}\" tileheight=\"{0}\" tilecount=\"{1}\" columns=\"{2}\">",
+            self.tile_size,
+            self.tiles.len(),
+            self.columns
+        );
+        let _ = writeln!(
+            tsx,
+            " <image source=\"{}\" width=\"{}\" height=\"{}\"/>",
+            xml_escape(image),
+            width,
+            height
+        );
+        for (i, id) in self.tiles.iter().enumerate() {
+            let _ = writeln!(
+                tsx,
+                " <tile id=\"{}\">\n  <properties>\n   <property name=\"tile\" type=\"int\" value=\"{}\"/>\n  </properties>\n </tile>",
+                i,
+                id.number()
+            );
+        }
+        tsx.push_str("</tileset>\n");
+        tsx
+    }
+
+    /// The board as a Tiled map (TMX), naming the file its tileset is saved to.
+    ///
+    /// The board is a single tile layer, with rotations given by Tiled's flip flags.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+    /// use e2rs::export::Atlas;
+    /// use e2rs::images::EdgeArt;
+    ///
+    /// let mut board = new_e2board();
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    ///
+    /// let atlas = Atlas::new(&board, &E2_BOARD_SPEC.tiles, &EdgeArt::e2().unwrap()).unwrap();
+    /// let tmx = atlas.tiled_map("clues.tsx");
+    /// assert!(tmx.contains("<tileset firstgid=\"1\" source=\"clues.tsx\"/>"));
+    /// assert!(tmx.contains("width=\"16\" height=\"16\""));
+    /// ```
+    pub fn tiled_map(&self, tileset: &str) -> String {
+        let columns = self.shape.columns;
+        let rows = self.shape.rows;
+        let mut gids = vec![0u32; columns * rows];
+        for p in &self.placements {
+            let gid = p.index as u32 + 1;
+            gids[p.at.col + p.at.row * columns] = gid | rotation_flags(p.rotation);
+        }
+
+        let mut tmx = String::new();
+        tmx.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            tmx,
+            "<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{2}\" tileheight=\"{2}\" infinite=\"0\" nextlayerid=\"2\" nextobjectid=\"1\">",
+            columns, rows, self.tile_size
+        );
+        let _ = writeln!(
+            tmx,
+            " <tileset firstgid=\"1\" source=\"{}\"/>",
+            xml_escape(tileset)
+        );
+        let _ = writeln!(
+            tmx,
+            " <layer id=\"1\" name=\"board\" width=\"{}\" height=\"{}\">",
+            columns, rows
+        );
+        tmx.push_str("  <data encoding=\"csv\">\n");
+        for (r, row) in gids.chunks(columns.max(1)).enumerate() {
+            let line: Vec<_> = row.iter().map(|g| g.to_string()).collect();
+            tmx.push_str(&line.join(","));
+            if r + 1 < rows {
+                tmx.push(',');
+            }
+            tmx.push('\n');
+        }
+        tmx.push_str("</data>\n </layer>\n</map>\n");
+        tmx
+    }
+
+    /// Save the atlas image, a Tiled tileset and a Tiled map of the board.
+    ///
+    /// Each file names the next by its file name, so the three should be kept side by side.
+    pub fn save_tiled<P, Q, R>(&self, image: P, tileset: Q, map: R) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+        R: AsRef<Path>,
+    {
+        let image = image.as_ref();
+        let tileset = tileset.as_ref();
+        self.image.save(image).map_err(RenderError::from)?;
+
+        std::fs::write(tileset, self.tiled_tileset(&file_name(image)))
+            .map_err(|e| IoError::new(tileset, e))?;
+        std::fs::write(&map, self.tiled_map(&file_name(tileset)))
+            .map_err(|e| IoError::new(&map, e))?;
+        Ok(())
+    }
+}
+
+/// Escape a string for use in an XML attribute.
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
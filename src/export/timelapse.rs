@@ -0,0 +1,121 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use image::{Rgba, RgbaImage};
+
+use crate::error::{Error, IoError, RenderError};
+
+/// The height of the score bar drawn by [draw_score_bar], as a fraction of the image height.
+const SCORE_BAR_FRACTION: u32 = 40;
+
+/// A video being encoded by `ffmpeg`, one rendered board at a time.
+///
+/// Frames are piped to an `ffmpeg` process as raw pixels, so `ffmpeg` must be on the path.
+/// The container and codec follow from the file extension of the output, so `.mp4` and
+/// `.webm` both work.
+/// Every frame must be the same size, and for most codecs the width and height must be even.
+///
+/// ```no_run
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::export::{draw_score_bar, Timelapse};
+/// use e2rs::images::board_image;
+///
+/// let mut board = new_e2board();
+/// let mut video: Option<Timelapse> = None;
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+///     let mut frame = board_image(&board).unwrap();
+///     draw_score_bar(&mut frame, board.score(), 480);
+///     let video = match &mut video {
+///         Some(video) => video,
+///         None => video.insert(Timelapse::start("clues.mp4", frame.dimensions(), 2).unwrap()),
+///     };
+///     video.push(&frame).unwrap();
+/// }
+/// video.unwrap().finish().unwrap();
+/// ```
+pub struct Timelapse {
+    path: PathBuf,
+    size: (u32, u32),
+    ffmpeg: Child,
+    input: Option<ChildStdin>,
+}
+
+impl Timelapse {
+    /// Start encoding a video of frames of the given size, shown at `fps` frames per second.
+    ///
+    /// Any existing file at `path` is overwritten.
+    pub fn start<P: AsRef<Path>>(path: P, size: (u32, u32), fps: u32) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut ffmpeg = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgba"])
+            .args(["-s", &format!("{}x{}", size.0, size.1)])
+            .args(["-r", &fps.max(1).to_string()])
+            .args(["-i", "-", "-pix_fmt", "yuv420p"])
+            .arg(&path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| IoError::new("ffmpeg", e))?;
+        let input = ffmpeg.stdin.take();
+        Ok(Timelapse {
+            path,
+            size,
+            ffmpeg,
+            input,
+        })
+    }
+
+    /// Add a frame to the video.
+    pub fn push(&mut self, frame: &RgbaImage) -> Result<(), Error> {
+        if frame.dimensions() != self.size {
+            return Err(RenderError::SizeMismatch {
+                expected: self.size,
+                found: frame.dimensions(),
+            }
+            .into());
+        }
+        let input = self.input.as_mut().expect("input is open until the video is finished");
+        input
+            .write_all(frame.as_raw())
+            .map_err(|e| IoError::new(&self.path, e))?;
+        Ok(())
+    }
+
+    /// Finish the video, waiting for `ffmpeg` to write it out.
+    pub fn finish(mut self) -> Result<(), Error> {
+        drop(self.input.take());
+        let status = self
+            .ffmpeg
+            .wait()
+            .map_err(|e| IoError::new("ffmpeg", e))?;
+        if !status.success() {
+            return Err(RenderError::Encoder(format!("ffmpeg exited with {}", status)).into());
+        }
+        Ok(())
+    }
+}
+
+/// Draw a bar along the bottom of a rendered board showing its score out of `max_score`, as
+/// an overlay for timelapse frames.
+///
+/// The filled part of the bar is green, and the rest dark grey.
+pub fn draw_score_bar(img: &mut RgbaImage, score: usize, max_score: usize) {
+    let (width, height) = img.dimensions();
+    let bar = (height / SCORE_BAR_FRACTION).max(1).min(height);
+    let filled = if max_score == 0 {
+        width
+    } else {
+        (width as u64 * score.min(max_score) as u64 / max_score as u64) as u32
+    };
+    for y in height - bar..height {
+        for x in 0..width {
+            let colour = if x < filled {
+                Rgba([40, 200, 80, 255])
+            } else {
+                Rgba([60, 60, 60, 255])
+            };
+            img.put_pixel(x, y, colour);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Generation of random puzzles with known solutions.
+//!
+//! Puzzles are generated by colouring every edge of a board at random, then cutting it into tiles.
+//! The tiles are shuffled and given random rotations, and the placements that put them back
+//! together are kept as the solution.
+//!
+//! Generated puzzles can be much larger than the Eternity 2 Puzzle, for stress-testing.
+//! Puzzles with more than 256 tiles can be written out to files, but can't yet be held in a
+//! [TileSet].
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::error::Error;
+use crate::model::{BoardShape, BoardSpec, Indx, Rotate, Rotation, Side, Tile, TileSet, ROTATIONS};
+
+/// Settings for the puzzle generator.
+#[derive(Clone, Copy, Debug)]
+pub struct GeneratorConfig {
+    /// The shape of the board to generate.
+    pub shape: BoardShape,
+    /// Number of colours used for the edges between border tiles.
+    pub border_colours: u8,
+    /// Number of colours used for all other edges between tiles.
+    pub inner_colours: u8,
+}
+
+impl GeneratorConfig {
+    /// Settings matching the Eternity 2 Puzzle: a 16x16 board with 5 border and 17 inner colours.
+    pub const E2_LIKE: GeneratorConfig = GeneratorConfig {
+        shape: BoardShape {
+            columns: 16,
+            rows: 16,
+        },
+        border_colours: 5,
+        inner_colours: 17,
+    };
+}
+
+/// The placement of a generated tile in the solution.
+#[derive(Clone, Copy, Debug)]
+pub struct Placement {
+    /// The tile number, counting from 1.
+    pub tile: usize,
+    /// Where the tile goes.
+    pub at: Indx,
+    /// How the tile is rotated, anti-clockwise.
+    pub rotation: Rotation,
+}
+
+/// A generated puzzle, with its solution.
+///
+/// Edges are numbered, with 0 as the outside, border colours numbered next and then inner colours.
+#[derive(Clone, Debug)]
+pub struct Generated {
+    /// The shape of the board.
+    pub shape: BoardShape,
+    /// The tiles, in puzzle order.
+    pub tiles: Vec<Tile<u8>>,
+    /// The placement of every tile in the solution, in row-major cell order.
+    pub solution: Vec<Placement>,
+}
+
+/// Generate a random puzzle.
+///
+/// Fails with [Error::InvalidConfig] if the board is smaller than 2x2, if either colour count
+/// is zero, or if there are too many colours for the edges to fit in a `u8`.
+///
+/// ```
+/// use e2rs::generate::{generate, GeneratorConfig};
+/// use e2rs::model::BoardShape;
+///
+/// let config = GeneratorConfig {
+///     shape: BoardShape { columns: 32, rows: 32 },
+///     border_colours: 6,
+///     inner_colours: 30,
+/// };
+/// let puzzle = generate(&config, &mut rand::thread_rng()).unwrap();
+/// assert_eq!(puzzle.tiles.len(), 1024);
+/// assert_eq!(puzzle.solution.len(), 1024);
+///
+/// let small = GeneratorConfig { shape: BoardShape { columns: 6, rows: 6 }, ..config };
+/// let puzzle = generate(&small, &mut rand::thread_rng()).unwrap();
+/// let spec = puzzle.spec().unwrap();
+/// let solution = spec.parse_clues(&puzzle.solution_file(), false).unwrap();
+/// assert!(spec.validate_clues(&solution).is_ok());
+/// ```
+pub fn generate<R: Rng>(config: &GeneratorConfig, rng: &mut R) -> Result<Generated, Error> {
+    let BoardShape { columns, rows } = config.shape;
+    if columns < 2 || rows < 2 {
+        return Err(Error::InvalidConfig("boards must be at least 2x2"));
+    }
+    if config.border_colours == 0 || config.inner_colours == 0 {
+        return Err(Error::InvalidConfig("colour counts must be at least 1"));
+    }
+    if 1 + config.border_colours as usize + config.inner_colours as usize > 256 {
+        return Err(Error::InvalidConfig("edges must fit in a u8"));
+    }
+
+    let border = |rng: &mut R| rng.gen_range(1..=config.border_colours);
+    let inner = |rng: &mut R| {
+        rng.gen_range(config.border_colours + 1..=config.border_colours + config.inner_colours)
+    };
+
+    // vertical[c + r * (columns - 1)] is the edge between (c, r) and (c + 1, r)
+    let mut vertical = Vec::with_capacity((columns - 1) * rows);
+    for row in 0..rows {
+        for _col in 0..columns - 1 {
+            let edge = if row == 0 || row + 1 == rows {
+                border(rng)
+            } else {
+                inner(rng)
+            };
+            vertical.push(edge);
+        }
+    }
+
+    // horizontal[c + r * columns] is the edge between (c, r) and (c, r + 1)
+    let mut horizontal = Vec::with_capacity(columns * (rows - 1));
+    for _row in 0..rows - 1 {
+        for col in 0..columns {
+            let edge = if col == 0 || col + 1 == columns {
+                border(rng)
+            } else {
+                inner(rng)
+            };
+            horizontal.push(edge);
+        }
+    }
+
+    let mut cells = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        for col in 0..columns {
+            let north = if row == 0 {
+                0
+            } else {
+                horizontal[col + (row - 1) * columns]
+            };
+            let south = if row + 1 == rows {
+                0
+            } else {
+                horizontal[col + row * columns]
+            };
+            let west = if col == 0 {
+                0
+            } else {
+                vertical[col - 1 + row * (columns - 1)]
+            };
+            let east = if col + 1 == columns {
+                0
+            } else {
+                vertical[col + row * (columns - 1)]
+            };
+            cells.push((Indx { col, row }, Tile::new(north, east, south, west)));
+        }
+    }
+
+    let mut order: Vec<usize> = (0..cells.len()).collect();
+    order.shuffle(rng);
+
+    let mut tiles = vec![Tile::default(); cells.len()];
+    let mut solution = Vec::with_capacity(cells.len());
+    for (cell, &slot) in cells.iter().zip(&order) {
+        let (at, solved) = *cell;
+        let rotation = *ROTATIONS.choose(rng).unwrap();
+        // rotating the stored tile by `rotation` must give back the solved tile
+        tiles[slot] = solved.rotate(rotation.reverse()).apply();
+        solution.push(Placement {
+            tile: slot + 1,
+            at,
+            rotation,
+        });
+    }
+
+    Ok(Generated {
+        shape: config.shape,
+        tiles,
+        solution,
+    })
+}
+
+impl Generated {
+    /// The puzzle as a board spec, if it has few enough tiles to fit in a [TileSet].
+    pub fn spec(&self) -> Option<BoardSpec<u8>> {
+        (self.tiles.len() <= 256).then(|| BoardSpec {
+            dimensions: self.shape,
+            tiles: TileSet::new(self.tiles.clone()),
+            clue_sets: Vec::new(),
+        })
+    }
+
+    /// The tiles in the tiles file format, with the edges in the given side order.
+    pub fn tiles_file(&self, order: [Side; 4]) -> String {
+        let mut txt = String::new();
+        for tile in &self.tiles {
+            let edges: Vec<_> = order.iter().map(|s| tile[*s].to_string()).collect();
+            txt.push_str(&edges.join(" "));
+            txt.push('\n');
+        }
+        txt
+    }
+
+    /// The solution in the clues file format, with anti-clockwise rotations.
+    pub fn solution_file(&self) -> String {
+        let mut txt = String::new();
+        for p in &self.solution {
+            txt.push_str(&format!(
+                "{} {} {} {}\n",
+                p.tile, p.at.col, p.at.row, p.rotation as usize
+            ));
+        }
+        txt
+    }
+}
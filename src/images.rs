@@ -1,120 +1,214 @@
 //! Work with image representations of boards.
 //!
+//! Boards are drawn with [EdgeArt]: either the embedded Eternity 2 Puzzle edge images, or edge
+//! patterns drawn procedurally for puzzles with more edges than that art covers.
+//! Alternatively, a [SpriteSheet] cut from a picture of the real pieces draws boards with the
+//! actual piece artwork.
+//!
+//! Every function here returns a [RenderError] rather than panicking, including when the
+//! embedded edge images cannot be decoded.
+//!
+//! This module is only available with the `images` feature, and the Eternity 2 Puzzle art only
+//! with the `e2-data` feature as well.
+
+use std::borrow::Cow;
+
 use image::imageops::{rotate180, rotate270, rotate90};
-use image::{
-    self, imageops::overlay, load_from_memory, DynamicImage, GenericImageView, ImageBuffer,
-};
+use image::{self, imageops::overlay, DynamicImage, ImageBuffer, RgbaImage};
 use image::{GenericImage, Rgba};
-use lazy_static::lazy_static;
 
-use crate::e2::E2Edge;
+use crate::error::RenderError;
 use crate::model::Board;
-use crate::{
-    model::{Side::*, Tile},
-    e2::E2_EDGE_COUNT,
-};
-
-lazy_static! {
-    /// Edge images.
-    pub static ref IMAGES: [DynamicImage; E2_EDGE_COUNT] = [
-        load_from_memory(
-            include_bytes!("../data/edge_images/0.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/1.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/2.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/3.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/4.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/5.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/6.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/7.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/8.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/9.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/10.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/11.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/12.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/13.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/14.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/15.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/16.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/17.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/18.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/19.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/20.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/21.png"))
-            .expect("Failed to load image resource"),
-        load_from_memory(
-            include_bytes!("../data/edge_images/22.png"))
-            .expect("Failed to load image resource"),
-            ];
-}
+use crate::model::{Side::*, Tile};
+
+#[cfg(feature = "e2-data")]
+mod embedded;
+#[cfg(feature = "e2-data")]
+pub use embedded::*;
+
+mod pattern;
+pub use pattern::*;
+mod sprites;
+pub use sprites::*;
+mod png;
+pub use png::*;
+mod thumbnails;
+pub use thumbnails::*;
+mod hash;
+pub use hash::*;
+mod highlight;
+pub use highlight::*;
+mod heatmap;
+pub use heatmap::*;
+
+/// The size of procedurally drawn tiles, in pixels, unless another size is asked for.
+pub const PATTERN_TILE_SIZE: u32 = 128;
 
-/// Render a tile as an image.
-pub fn edge_image<I: GenericImage<Pixel = Rgba<u8>>>(img: &mut I, tile: &Tile<E2Edge>) {
-    overlay(img, &IMAGES[tile[North] as usize], 0, 0);
-    overlay(img, &rotate90(&IMAGES[tile[East] as usize]), 0, 0);
-    overlay(img, &rotate180(&IMAGES[tile[South] as usize]), 0, 0);
-    overlay(img, &rotate270(&IMAGES[tile[West] as usize]), 0, 0);
+/// A set of images to draw edges with, indexed by edge number.
+///
+/// Each image shows one edge as a triangle pointing down from the top of a square tile.
+/// The triangle is rotated into place for the other sides.
+#[derive(Clone, Debug)]
+pub struct EdgeArt {
+    images: Cow<'static, [DynamicImage]>,
 }
 
-/// Render a board as an image.
-pub fn board_image(board: &Board<E2Edge>) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
-    let (tile_w, tile_h) = IMAGES[0].dimensions();
-    let board_w = tile_w * board.columns as u32;
-    let board_h = tile_h * board.rows as u32;
-
-    let mut img = ImageBuffer::new(board_w, board_h);
-
-    for r in 0..board.rows {
-        for c in 0..board.columns {
-            if let Some(t) = &board[(c, r)] {
-                let c = c as u32;
-                let r = r as u32;
-                let mut sub_image = img.sub_image(c * tile_w, r * tile_h, tile_w, tile_h);
-                edge_image(&mut *sub_image, t);
+impl EdgeArt {
+    /// Procedurally drawn art for the given number of edges, as made by [edge_pattern].
+    pub fn procedural(edge_count: usize, size: u32) -> Self {
+        let images = (0..edge_count)
+            .map(|edge| DynamicImage::ImageRgba8(edge_pattern(edge, size)))
+            .collect();
+        EdgeArt {
+            images: Cow::Owned(images),
+        }
+    }
+
+    /// Art for a puzzle with the given number of edges.
+    ///
+    /// This is the Eternity 2 Puzzle art if it has enough edges and the `e2-data` feature is on,
+    /// and procedural art of [PATTERN_TILE_SIZE] otherwise.
+    ///
+    /// ```
+    /// use e2rs::images::{EdgeArt, PATTERN_TILE_SIZE};
+    ///
+    /// let art = EdgeArt::for_edges(40).unwrap();
+    /// assert_eq!(art.len(), 40);
+    /// assert_eq!(art.tile_size(), PATTERN_TILE_SIZE);
+    /// ```
+    pub fn for_edges(edge_count: usize) -> Result<Self, RenderError> {
+        #[cfg(feature = "e2-data")]
+        if edge_count <= crate::e2::E2_EDGE_COUNT {
+            return EdgeArt::e2();
+        }
+        Ok(EdgeArt::procedural(edge_count, PATTERN_TILE_SIZE))
+    }
+
+    /// The number of edges this art can draw.
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    /// True if this art can't draw any edges.
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// The width and height of a tile drawn with this art, in pixels.
+    pub fn tile_size(&self) -> u32 {
+        self.images.first().map_or(0, |img| img.width())
+    }
+
+    /// Draw a tile into an image, which must be exactly [EdgeArt::tile_size] pixels square.
+    pub fn draw_tile<I, E>(&self, img: &mut I, tile: &Tile<E>) -> Result<(), RenderError>
+    where
+        I: GenericImage<Pixel = Rgba<u8>>,
+        E: Copy + Into<u8>,
+    {
+        let size = self.tile_size();
+        if img.dimensions() != (size, size) {
+            return Err(RenderError::SizeMismatch {
+                expected: (size, size),
+                found: img.dimensions(),
+            });
+        }
+
+        let edge = |side| {
+            let edge: u8 = tile[side].into();
+            let edge = edge as usize;
+            self.images.get(edge).ok_or(RenderError::UnknownEdge(edge))
+        };
+        let (north, east, south, west) = (edge(North)?, edge(East)?, edge(South)?, edge(West)?);
+
+        overlay(img, north, 0, 0);
+        overlay(img, &rotate90(east), 0, 0);
+        overlay(img, &rotate180(south), 0, 0);
+        overlay(img, &rotate270(west), 0, 0);
+        Ok(())
+    }
+
+    /// Draw a board as an image.
+    ///
+    /// ```
+    /// use e2rs::generate::{generate, GeneratorConfig};
+    /// use e2rs::images::EdgeArt;
+    /// use e2rs::model::BoardShape;
+    ///
+    /// let config = GeneratorConfig {
+    ///     shape: BoardShape { columns: 4, rows: 3 },
+    ///     border_colours: 10,
+    ///     inner_colours: 30,
+    /// };
+    /// let puzzle = generate(&config, &mut rand::thread_rng()).unwrap();
+    /// let spec = puzzle.spec().unwrap();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in spec.load_clues(&puzzle.solution_file(), false).unwrap() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    ///
+    /// let art = EdgeArt::for_edges(41).unwrap();
+    /// let img = art.draw_board(&board).unwrap();
+    /// assert_eq!(img.dimensions(), (4 * art.tile_size(), 3 * art.tile_size()));
+    /// ```
+    pub fn draw_board<E: Copy + Into<u8>>(
+        &self,
+        board: &Board<E>,
+    ) -> Result<RgbaImage, RenderError> {
+        let mut img = ImageBuffer::new(0, 0);
+        self.draw_board_into(board, &mut img)?;
+        Ok(img)
+    }
+
+    /// Draw a board into an existing image, reusing its buffer.
+    ///
+    /// The image is cleared, and resized to fit the board if it is the wrong size, so drawing a
+    /// board of the same size again doesn't allocate.
+    pub fn draw_board_into<E: Copy + Into<u8>>(
+        &self,
+        board: &Board<E>,
+        img: &mut RgbaImage,
+    ) -> Result<(), RenderError> {
+        let tile = self.tile_size();
+        let (board_w, board_h) = board_pixels(board, tile)?;
+        if img.dimensions() == (board_w, board_h) {
+            img.iter_mut().for_each(|b| *b = 0);
+        } else {
+            *img = ImageBuffer::new(board_w, board_h);
+        }
+
+        for r in 0..board.rows {
+            for c in 0..board.columns {
+                if let Some(t) = &board[(c, r)] {
+                    let c = c as u32;
+                    let r = r as u32;
+                    let mut sub_image = img.sub_image(c * tile, r * tile, tile, tile);
+                    self.draw_tile(&mut *sub_image, t)?;
+                }
             }
         }
+
+        Ok(())
     }
+}
 
-    img
+/// The size in pixels of an image of a board, checking that it can be allocated.
+fn board_pixels<E>(board: &Board<E>, tile: u32) -> Result<(u32, u32), RenderError> {
+    let too_large = || RenderError::TooLarge {
+        columns: board.columns,
+        rows: board.rows,
+    };
+    let pixels = |cells: usize| {
+        u32::try_from(cells)
+            .ok()
+            .and_then(|cells| cells.checked_mul(tile))
+            .ok_or_else(too_large)
+    };
+    let board_w = pixels(board.columns)?;
+    let board_h = pixels(board.rows)?;
+    // the image buffer holds 4 bytes per pixel, and must be addressable
+    (board_w as usize)
+        .checked_mul(board_h as usize)
+        .and_then(|p| p.checked_mul(4))
+        .ok_or_else(too_large)?;
+    Ok((board_w, board_h))
 }
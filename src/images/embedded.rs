@@ -0,0 +1,164 @@
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::{LazyLock, OnceLock};
+
+use image::{load_from_memory, DynamicImage, GenericImage, GenericImageView, Rgba, RgbaImage};
+
+use crate::e2::{E2Edge, E2_EDGE_COUNT};
+use crate::error::{Error, IoError, RenderError};
+use crate::model::{Board, Tile};
+
+use super::EdgeArt;
+
+/// The embedded edge image files, indexed by edge number.
+static EDGE_IMAGE_DATA: [&[u8]; E2_EDGE_COUNT] = [
+    include_bytes!("../../data/edge_images/0.png"),
+    include_bytes!("../../data/edge_images/1.png"),
+    include_bytes!("../../data/edge_images/2.png"),
+    include_bytes!("../../data/edge_images/3.png"),
+    include_bytes!("../../data/edge_images/4.png"),
+    include_bytes!("../../data/edge_images/5.png"),
+    include_bytes!("../../data/edge_images/6.png"),
+    include_bytes!("../../data/edge_images/7.png"),
+    include_bytes!("../../data/edge_images/8.png"),
+    include_bytes!("../../data/edge_images/9.png"),
+    include_bytes!("../../data/edge_images/10.png"),
+    include_bytes!("../../data/edge_images/11.png"),
+    include_bytes!("../../data/edge_images/12.png"),
+    include_bytes!("../../data/edge_images/13.png"),
+    include_bytes!("../../data/edge_images/14.png"),
+    include_bytes!("../../data/edge_images/15.png"),
+    include_bytes!("../../data/edge_images/16.png"),
+    include_bytes!("../../data/edge_images/17.png"),
+    include_bytes!("../../data/edge_images/18.png"),
+    include_bytes!("../../data/edge_images/19.png"),
+    include_bytes!("../../data/edge_images/20.png"),
+    include_bytes!("../../data/edge_images/21.png"),
+    include_bytes!("../../data/edge_images/22.png"),
+];
+
+/// The edge image files in use, fixed the first time they are needed or by [set_edge_images].
+static EDGE_DATA: OnceLock<Vec<Cow<'static, [u8]>>> = OnceLock::new();
+
+/// Edge images, decoded and checked once on first use.
+static IMAGES: LazyLock<Result<Vec<DynamicImage>, (usize, String)>> = LazyLock::new(|| {
+    let data = EDGE_DATA.get_or_init(|| EDGE_IMAGE_DATA.map(Cow::Borrowed).to_vec());
+    load_edge_images(data)
+});
+
+/// Decode the edge images, checking that they are all square and the same size.
+fn load_edge_images<D: AsRef<[u8]>>(data: &[D]) -> Result<Vec<DynamicImage>, (usize, String)> {
+    if data.is_empty() {
+        return Err((0, "there are no edge images".to_string()));
+    }
+    let mut images = Vec::with_capacity(data.len());
+    for (edge, data) in data.iter().enumerate() {
+        let img = load_from_memory(data.as_ref()).map_err(|e| (edge, e.to_string()))?;
+        let (w, h) = img.dimensions();
+        if w != h {
+            return Err((edge, format!("image is {}x{}, but must be square", w, h)));
+        }
+        if let Some(first) = images.first() {
+            let size = GenericImageView::width(first);
+            if w != size {
+                let reason = format!("image is {}x{}, but edge 0 is {}x{}", w, h, size, size);
+                return Err((edge, reason));
+            }
+        }
+        images.push(img);
+    }
+    Ok(images)
+}
+
+/// Use other edge images for the Eternity 2 Puzzle art in place of the embedded ones.
+///
+/// The images are image file contents, indexed by edge number, and must all be square and the
+/// same size.
+/// They are checked before anything changes.
+///
+/// This must be called before the edge art is first used, as the images are fixed from then on;
+/// later calls fail with [Error::InvalidConfig].
+///
+/// ```
+/// use e2rs::images::{set_edge_images, tile_size};
+///
+/// let edge = std::fs::read("data/edge_images/0.png").unwrap();
+/// assert!(set_edge_images(vec![edge.clone(), b"not an image".to_vec()]).is_err());
+///
+/// set_edge_images(vec![edge.clone(); 23]).unwrap();
+/// assert!(tile_size().is_ok());
+/// assert!(set_edge_images(vec![edge]).is_err());
+/// ```
+pub fn set_edge_images(images: Vec<Vec<u8>>) -> Result<(), Error> {
+    if let Err((edge, reason)) = load_edge_images(&images) {
+        return Err(RenderError::Resource { edge, reason }.into());
+    }
+    EDGE_DATA
+        .set(images.into_iter().map(Cow::Owned).collect())
+        .map_err(|_| Error::InvalidConfig("the edge images are already in use"))
+}
+
+/// Read edge image files, indexed by edge number, and use them as by [set_edge_images].
+pub fn set_edge_image_files<P: AsRef<Path>>(paths: &[P]) -> Result<(), Error> {
+    let mut images = Vec::with_capacity(paths.len());
+    for path in paths {
+        images.push(std::fs::read(path).map_err(|e| IoError::new(path, e))?);
+    }
+    set_edge_images(images)
+}
+
+/// The edge images, indexed by edge number.
+///
+/// The images are decoded the first time this is called, and the result is kept,
+/// so a broken image resource is reported by every call.
+pub fn edge_images() -> Result<&'static [DynamicImage], RenderError> {
+    match &*IMAGES {
+        Ok(images) => Ok(images),
+        Err((edge, reason)) => Err(RenderError::Resource {
+            edge: *edge,
+            reason: reason.clone(),
+        }),
+    }
+}
+
+/// The width and height of a rendered tile, in pixels.
+pub fn tile_size() -> Result<u32, RenderError> {
+    Ok(edge_images()?[0].width())
+}
+
+impl EdgeArt {
+    /// The embedded Eternity 2 Puzzle edge art.
+    pub fn e2() -> Result<Self, RenderError> {
+        Ok(EdgeArt {
+            images: Cow::Borrowed(edge_images()?),
+        })
+    }
+}
+
+/// Render a tile as an image, with the Eternity 2 Puzzle art.
+///
+/// The image must be exactly [tile_size] pixels square.
+pub fn edge_image<I: GenericImage<Pixel = Rgba<u8>>>(
+    img: &mut I,
+    tile: &Tile<E2Edge>,
+) -> Result<(), RenderError> {
+    EdgeArt::e2()?.draw_tile(img, tile)
+}
+
+/// Render a board as an image, with the Eternity 2 Puzzle art.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::{board_image, tile_size};
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+///
+/// let img = board_image(&board).unwrap();
+/// assert_eq!(img.width(), 16 * tile_size().unwrap());
+/// ```
+pub fn board_image(board: &Board<E2Edge>) -> Result<RgbaImage, RenderError> {
+    EdgeArt::e2()?.draw_board(board)
+}
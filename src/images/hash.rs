@@ -0,0 +1,96 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, RgbaImage};
+
+use crate::model::{Board, Indx, SIDES};
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A content hash of a board: its shape and the edges of every placed tile.
+///
+/// Boards that look the same hash the same, however they were filled, and the hash is stable
+/// across runs, platforms and versions of the crate, so it can key galleries and be written
+/// into tests.
+/// It is a 64 bit FNV-1a hash, not a cryptographic one.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::board_hash;
+///
+/// let mut board = new_e2board();
+/// let empty = board_hash(&board);
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+/// assert_ne!(board_hash(&board), empty);
+/// assert_eq!(board_hash(&board), 0x96b6_1ed5_b92d_07a8);
+/// ```
+pub fn board_hash<E: Copy + Into<u8>>(board: &Board<E>) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let mut feed = |byte: u8| {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+    };
+
+    for dimension in [board.columns, board.rows] {
+        for byte in (dimension as u64).to_le_bytes() {
+            feed(byte);
+        }
+    }
+    for row in 0..board.rows {
+        for col in 0..board.columns {
+            match &board[Indx { col, row }] {
+                None => feed(0),
+                Some(tile) => {
+                    feed(1);
+                    for side in SIDES {
+                        feed(tile[side].into());
+                    }
+                }
+            }
+        }
+    }
+
+    hash
+}
+
+/// A perceptual hash of an image, for spotting renders that look alike.
+///
+/// This is a difference hash: the image is shrunk to 9x8 grey pixels, and each bit records
+/// whether a pixel is brighter than its neighbour to the right.
+/// Images that look alike, such as renders of the same board at different sizes or with
+/// different PNG settings, have hashes differing in few bits, as counted by
+/// [perceptual_distance].
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::{board_image, perceptual_distance, perceptual_hash, thumbnail};
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+/// let full = board_image(&board).unwrap();
+/// let small = thumbnail(&full, 128);
+/// assert!(perceptual_distance(perceptual_hash(&full), perceptual_hash(&small)) <= 4);
+/// ```
+pub fn perceptual_hash(img: &RgbaImage) -> u64 {
+    let small = imageops::resize(img, 9, 8, FilterType::Triangle);
+    let grey = DynamicImage::ImageRgba8(small).into_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = grey.get_pixel(x, y)[0];
+            let right = grey.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | (left > right) as u64;
+        }
+    }
+    hash
+}
+
+/// The number of bits in which two perceptual hashes differ, from 0 for images that look the
+/// same to 64.
+pub fn perceptual_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
@@ -0,0 +1,50 @@
+use image::{Rgba, RgbaImage};
+
+use crate::analysis::TileMarginals;
+use crate::model::Indx;
+
+/// Render how strongly a population of boards agrees on each cell, as gathered by
+/// [tile_marginals](crate::analysis::tile_marginals).
+///
+/// Each cell is a square of `cell_size` pixels, shaded from red where the boards disagree, or
+/// leave the cell empty, through yellow to green where they all hold the same placement.
+///
+/// ```
+/// use e2rs::analysis::tile_marginals;
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+/// use e2rs::images::agreement_heatmap;
+///
+/// let mut board = new_e2board();
+/// E2_CLUES[0].apply(&mut board).unwrap();
+/// let marginals = tile_marginals(&E2_BOARD_SPEC.tiles, [&board]).unwrap();
+///
+/// let img = agreement_heatmap(&marginals, 8);
+/// assert_eq!(img.dimensions(), (16 * 8, 16 * 8));
+/// ```
+pub fn agreement_heatmap(marginals: &TileMarginals, cell_size: u32) -> RgbaImage {
+    let shape = marginals.shape();
+    let mut img = RgbaImage::new(
+        shape.columns as u32 * cell_size,
+        shape.rows as u32 * cell_size,
+    );
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let colour = shade(marginals.agreement(Indx { col, row }));
+            let (x0, y0) = (col as u32 * cell_size, row as u32 * cell_size);
+            for y in y0..y0 + cell_size {
+                for x in x0..x0 + cell_size {
+                    img.put_pixel(x, y, colour);
+                }
+            }
+        }
+    }
+    img
+}
+
+/// The colour for an agreement from 0 to 1, from red through yellow to green.
+fn shade(agreement: f64) -> Rgba<u8> {
+    let a = agreement.clamp(0.0, 1.0);
+    let red = if a < 0.5 { 1.0 } else { 2.0 * (1.0 - a) };
+    let green = if a < 0.5 { 2.0 * a } else { 1.0 };
+    Rgba([(red * 255.0) as u8, (green * 255.0) as u8, 0, 255])
+}
@@ -0,0 +1,53 @@
+use image::{Rgba, RgbaImage};
+
+use crate::model::Indx;
+
+/// The colour [outline_cells] is usually drawn in: an opaque red.
+pub const HIGHLIGHT: Rgba<u8> = Rgba([255, 0, 0, 255]);
+
+/// Draw an outline around each of the given cells of a rendered board, such as the cells that
+/// changed between two checkpoints.
+///
+/// `tile_size` is the width and height of each cell in pixels, and `thickness` the width of the
+/// outline, drawn inside the cell.
+/// Parts of cells beyond the image are skipped.
+///
+/// ```
+/// use e2rs::analysis::board_diff;
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::{board_image, outline_cells, tile_size, HIGHLIGHT};
+///
+/// let before = new_e2board();
+/// let mut after = new_e2board();
+/// E2_CLUES[0].apply(&mut after).unwrap();
+///
+/// let changed: Vec<_> = board_diff(&before, &after).iter().map(|d| d.at).collect();
+/// let mut img = board_image(&after).unwrap();
+/// let size = tile_size().unwrap();
+/// outline_cells(&mut img, size, &changed, 4, HIGHLIGHT);
+///
+/// let at = E2_CLUES[0].at;
+/// assert_eq!(*img.get_pixel(at.col as u32 * size, at.row as u32 * size), HIGHLIGHT);
+/// ```
+pub fn outline_cells(
+    img: &mut RgbaImage,
+    tile_size: u32,
+    cells: &[Indx],
+    thickness: u32,
+    colour: Rgba<u8>,
+) {
+    let thickness = thickness.min(tile_size);
+    let (width, height) = img.dimensions();
+    for at in cells {
+        let (x0, y0) = (at.col as u32 * tile_size, at.row as u32 * tile_size);
+        for y in y0..(y0 + tile_size).min(height) {
+            for x in x0..(x0 + tile_size).min(width) {
+                let (dx, dy) = (x - x0, y - y0);
+                let edge = tile_size - thickness;
+                if dx < thickness || dy < thickness || dx >= edge || dy >= edge {
+                    img.put_pixel(x, y, colour);
+                }
+            }
+        }
+    }
+}
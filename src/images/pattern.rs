@@ -0,0 +1,111 @@
+use image::{Rgba, RgbaImage};
+
+/// The glyphs drawn on procedural edges, cycled through by edge number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Glyph {
+    Disc,
+    Square,
+    Diamond,
+    Ring,
+    Cross,
+}
+
+const GLYPHS: [Glyph; 5] = [
+    Glyph::Disc,
+    Glyph::Square,
+    Glyph::Diamond,
+    Glyph::Ring,
+    Glyph::Cross,
+];
+
+/// The colour of the outside edge.
+const OUTSIDE: Rgba<u8> = Rgba([128, 128, 128, 255]);
+
+/// Draw an edge image procedurally, in the layout of the Eternity 2 Puzzle edge art.
+///
+/// The edge is a triangle pointing down from the top of a `size` square image to its centre.
+/// Edge 0, the outside, is plain grey.
+/// Every other edge gets a background colour and a glyph in a contrasting colour, both chosen
+/// deterministically from the edge number so that neighbouring numbers look quite different.
+/// This gives art for any number of edges without needing external assets.
+///
+/// ```
+/// use e2rs::images::edge_pattern;
+///
+/// let a = edge_pattern(30, 64);
+/// assert_eq!(a.dimensions(), (64, 64));
+/// assert_eq!(a, edge_pattern(30, 64));
+/// assert_ne!(a, edge_pattern(31, 64));
+///
+/// // the bottom half is left transparent, for the other sides of the tile
+/// assert_eq!(a.get_pixel(32, 60).0[3], 0);
+/// ```
+pub fn edge_pattern(edge: usize, size: u32) -> RgbaImage {
+    let s = size as f32;
+    let in_triangle = |x: f32, y: f32| y <= x && y <= s - x;
+
+    if edge == 0 {
+        return RgbaImage::from_fn(size, size, |x, y| {
+            let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+            if in_triangle(x, y) {
+                OUTSIDE
+            } else {
+                Rgba([0, 0, 0, 0])
+            }
+        });
+    }
+
+    let k = edge - 1;
+    // the golden ratio spreads successive hues evenly around the colour wheel
+    let hue = (k as f32 * 0.618_034).fract();
+    let dark = (k / GLYPHS.len()) % 2 == 1;
+    let background = hsv(hue, 0.75, if dark { 0.55 } else { 0.85 });
+    let foreground = hsv((hue + 0.5).fract(), 0.9, if dark { 0.95 } else { 0.45 });
+    let glyph = GLYPHS[k % GLYPHS.len()];
+
+    let (cx, cy, r) = (s / 2.0, s * 0.2, s * 0.12);
+    RgbaImage::from_fn(size, size, |x, y| {
+        let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+        if !in_triangle(x, y) {
+            Rgba([0, 0, 0, 0])
+        } else if in_glyph(glyph, x - cx, y - cy, r) {
+            foreground
+        } else {
+            background
+        }
+    })
+}
+
+/// Check if a point, relative to the glyph centre, falls within a glyph of radius `r`.
+fn in_glyph(glyph: Glyph, dx: f32, dy: f32, r: f32) -> bool {
+    match glyph {
+        Glyph::Disc => dx * dx + dy * dy <= r * r,
+        Glyph::Square => dx.abs() <= r * 0.85 && dy.abs() <= r * 0.85,
+        Glyph::Diamond => dx.abs() + dy.abs() <= r * 1.2,
+        Glyph::Ring => {
+            let d = dx * dx + dy * dy;
+            d <= r * r && d >= r * r * 0.3
+        }
+        Glyph::Cross => {
+            (dx.abs() <= r * 0.35 && dy.abs() <= r) || (dy.abs() <= r * 0.35 && dx.abs() <= r)
+        }
+    }
+}
+
+/// Convert a colour from hue, saturation and value, each in `0..=1`, to opaque RGBA.
+fn hsv(h: f32, s: f32, v: f32) -> Rgba<u8> {
+    let h = h * 6.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let byte = |f: f32| ((f + m) * 255.0).round() as u8;
+    Rgba([byte(r), byte(g), byte(b), 255])
+}
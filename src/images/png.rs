@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{ColorType, ImageEncoder, RgbaImage};
+
+use crate::error::{Error, IoError, RenderError};
+
+/// How hard a [PngWriter] works to make files small.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PngCompression {
+    /// Fast compression with no filtering, for frequent snapshots where encoding time matters
+    /// more than file size.
+    Fastest,
+    /// Fast compression with adaptive filtering, which is what the image library uses by default.
+    #[default]
+    Fast,
+    /// The best compression, for images that are kept.
+    Small,
+}
+
+impl PngCompression {
+    fn settings(self) -> (CompressionType, FilterType) {
+        match self {
+            PngCompression::Fastest => (CompressionType::Fast, FilterType::NoFilter),
+            PngCompression::Fast => (CompressionType::Fast, FilterType::Adaptive),
+            PngCompression::Small => (CompressionType::Best, FilterType::Adaptive),
+        }
+    }
+}
+
+/// A PNG encoder for saving many images in a row, such as batch renders or snapshots of the
+/// best board so far.
+///
+/// The encoded bytes are kept in a buffer that is reused from one image to the next, so saving
+/// doesn't allocate once the buffer has grown to fit.
+/// Pair it with [EdgeArt::draw_board_into](super::EdgeArt::draw_board_into) to reuse the image
+/// buffer as well.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::{EdgeArt, PngCompression, PngWriter};
+///
+/// let art = EdgeArt::e2().unwrap();
+/// let mut board = new_e2board();
+/// let mut img = image::RgbaImage::new(0, 0);
+/// let mut png = PngWriter::new(PngCompression::Fastest);
+///
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+///     art.draw_board_into(&board, &mut img).unwrap();
+///     let bytes = png.encode(&img).unwrap();
+///     assert_eq!(&bytes[1..4], b"PNG");
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PngWriter {
+    compression: PngCompression,
+    bytes: Vec<u8>,
+}
+
+impl PngWriter {
+    /// A writer with the given compression.
+    pub fn new(compression: PngCompression) -> Self {
+        PngWriter {
+            compression,
+            bytes: Vec::new(),
+        }
+    }
+
+    /// The compression this writer uses.
+    pub fn compression(&self) -> PngCompression {
+        self.compression
+    }
+
+    /// Encode an image, returning the PNG file contents.
+    ///
+    /// The contents are only valid until the next image is encoded.
+    pub fn encode(&mut self, img: &RgbaImage) -> Result<&[u8], RenderError> {
+        let (compression, filter) = self.compression.settings();
+        self.bytes.clear();
+        PngEncoder::new_with_quality(&mut self.bytes, compression, filter).write_image(
+            img.as_raw(),
+            img.width(),
+            img.height(),
+            ColorType::Rgba8,
+        )?;
+        Ok(&self.bytes)
+    }
+
+    /// Encode an image and save it to a file.
+    pub fn save<P: AsRef<Path>>(&mut self, img: &RgbaImage, path: P) -> Result<(), Error> {
+        self.encode(img)?;
+        std::fs::write(&path, &self.bytes).map_err(|e| IoError::new(&path, e))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use std::path::Path;
+
+use image::imageops::{self, rotate180, rotate270, rotate90, FilterType};
+use image::{DynamicImage, GenericImage, GenericImageView, ImageBuffer, RgbaImage};
+
+use crate::error::RenderError;
+use crate::model::{Board, Indx, Rotation, TileSet};
+
+use super::board_pixels;
+
+/// Where the pieces lie on a sprite sheet.
+///
+/// Pieces are laid out in a grid of equally sized cells, numbered in row-major order from tile 1
+/// at the top left.
+/// Each piece must be pictured unrotated, that is with its north edge at the top,
+/// as it is listed in the tiles file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SheetGrid {
+    /// Number of pieces across the sheet.
+    pub columns: u32,
+    /// Number of pieces down the sheet.
+    pub rows: u32,
+    /// The pixel position of the top left corner of the first piece.
+    pub origin: (u32, u32),
+    /// The width and height of each piece, in pixels.
+    pub cell: (u32, u32),
+    /// The gap between neighbouring pieces, across and down, in pixels.
+    pub spacing: (u32, u32),
+}
+
+impl SheetGrid {
+    /// A grid dividing a whole sheet evenly into pieces, with no margins or gaps.
+    pub fn uniform(sheet: (u32, u32), columns: u32, rows: u32) -> Self {
+        SheetGrid {
+            columns,
+            rows,
+            origin: (0, 0),
+            cell: (sheet.0 / columns.max(1), sheet.1 / rows.max(1)),
+            spacing: (0, 0),
+        }
+    }
+
+    /// The size of sheet this grid covers, or `None` if it does not fit in a `u32`.
+    fn extent(&self) -> Option<(u32, u32)> {
+        let extent = |origin: u32, count: u32, cell: u32, spacing: u32| {
+            let gaps = count.checked_sub(1)?.checked_mul(spacing)?;
+            origin.checked_add(count.checked_mul(cell)?)?.checked_add(gaps)
+        };
+        Some((
+            extent(self.origin.0, self.columns, self.cell.0, self.spacing.0)?,
+            extent(self.origin.1, self.rows, self.cell.1, self.spacing.1)?,
+        ))
+    }
+}
+
+/// Images of the individual pieces of a puzzle, cut from a photo, scan or sprite sheet.
+///
+/// Boards drawn from a sprite sheet show the real piece artwork in place of the schematic edge
+/// triangles of [EdgeArt](super::EdgeArt).
+/// As boards only hold edges, each placed tile is looked up in the tileset with
+/// [TileSet::identify] to find its sprite.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+/// use e2rs::images::{SheetGrid, SpriteSheet};
+///
+/// // a stand-in for a scan of the pieces, with one 8 pixel cell per tile
+/// let sheet = image::DynamicImage::new_rgba8(16 * 8, 16 * 8);
+/// let grid = SheetGrid::uniform((128, 128), 16, 16);
+/// let sprites = SpriteSheet::cut(&sheet, &grid, 32).unwrap();
+/// assert_eq!(sprites.len(), 256);
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+/// let img = sprites.draw_board(&E2_BOARD_SPEC.tiles, &board).unwrap();
+/// assert_eq!(img.dimensions(), (16 * 32, 16 * 32));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    sprites: Vec<RgbaImage>,
+    tile_size: u32,
+}
+
+impl SpriteSheet {
+    /// Cut a sheet into square sprites of `tile_size` pixels, along the given grid.
+    ///
+    /// Pieces that are not square, or are not `tile_size` across, are resized to fit.
+    pub fn cut(
+        sheet: &DynamicImage,
+        grid: &SheetGrid,
+        tile_size: u32,
+    ) -> Result<Self, RenderError> {
+        let found = sheet.dimensions();
+        let needed = grid.extent().ok_or(RenderError::SheetTooSmall {
+            needed: (u32::MAX, u32::MAX),
+            found,
+        })?;
+        if needed.0 > found.0 || needed.1 > found.1 || grid.cell.0 == 0 || grid.cell.1 == 0 {
+            return Err(RenderError::SheetTooSmall { needed, found });
+        }
+
+        let mut sprites = Vec::with_capacity((grid.columns * grid.rows) as usize);
+        for row in 0..grid.rows {
+            for col in 0..grid.columns {
+                let x = grid.origin.0 + col * (grid.cell.0 + grid.spacing.0);
+                let y = grid.origin.1 + row * (grid.cell.1 + grid.spacing.1);
+                let piece = sheet.view(x, y, grid.cell.0, grid.cell.1).to_image();
+                let sprite = if piece.dimensions() == (tile_size, tile_size) {
+                    piece
+                } else {
+                    imageops::resize(&piece, tile_size, tile_size, FilterType::Triangle)
+                };
+                sprites.push(sprite);
+            }
+        }
+
+        Ok(SpriteSheet { sprites, tile_size })
+    }
+
+    /// Load a sheet from an image file and cut it, as for [SpriteSheet::cut].
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        grid: &SheetGrid,
+        tile_size: u32,
+    ) -> Result<Self, RenderError> {
+        SpriteSheet::cut(&image::open(path)?, grid, tile_size)
+    }
+
+    /// The number of sprites on the sheet.
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// True if the sheet has no sprites.
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// The width and height of each sprite, in pixels.
+    pub fn tile_size(&self) -> u32 {
+        self.tile_size
+    }
+
+    /// The sprite for a tile, by its number counting from 1.
+    pub fn sprite(&self, number: usize) -> Option<&RgbaImage> {
+        number.checked_sub(1).and_then(|i| self.sprites.get(i))
+    }
+
+    /// Draw a board with the piece sprites.
+    ///
+    /// Every placed tile must be in the tileset, and the tileset must be in the same order as
+    /// the sheet.
+    pub fn draw_board<E: Copy + PartialEq>(
+        &self,
+        tiles: &TileSet<E>,
+        board: &Board<E>,
+    ) -> Result<RgbaImage, RenderError> {
+        let tile = self.tile_size;
+        let (board_w, board_h) = board_pixels(board, tile)?;
+        let mut img = ImageBuffer::new(board_w, board_h);
+
+        for row in 0..board.rows {
+            for col in 0..board.columns {
+                let at = Indx { col, row };
+                let Some(placed) = &board[at] else {
+                    continue;
+                };
+                let (id, rotation) = tiles
+                    .identify(placed)
+                    .ok_or(RenderError::UnidentifiedTile(at))?;
+                let sprite = self
+                    .sprite(id.number())
+                    .ok_or(RenderError::MissingSprite(id.number()))?;
+                // tile rotations turn the north edge towards the east, which is clockwise
+                let sprite = match rotation {
+                    Rotation::Rot0 => sprite.clone(),
+                    Rotation::Rot90 => rotate90(sprite),
+                    Rotation::Rot180 => rotate180(sprite),
+                    Rotation::Rot270 => rotate270(sprite),
+                };
+                img.copy_from(&sprite, col as u32 * tile, row as u32 * tile)?;
+            }
+        }
+
+        Ok(img)
+    }
+}
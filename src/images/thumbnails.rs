@@ -0,0 +1,55 @@
+use image::imageops::{self, FilterType};
+use image::RgbaImage;
+
+/// The longest side of a thumbnail, in pixels, unless another size is asked for.
+pub const THUMBNAIL_SIZE: u32 = 256;
+
+/// The longest side of a web-sized image, in pixels, unless another size is asked for.
+pub const WEB_SIZE: u32 = 1024;
+
+/// Scale an image down to fit within `max_side` pixels across and down, keeping its shape.
+///
+/// Images that already fit are copied unchanged, rather than scaled up.
+pub fn thumbnail(img: &RgbaImage, max_side: u32) -> RgbaImage {
+    let (w, h) = img.dimensions();
+    let longest = w.max(h);
+    if longest <= max_side {
+        return img.clone();
+    }
+    let scale = |side: u32| ((side as u64 * max_side as u64) / longest as u64).max(1) as u32;
+    imageops::resize(img, scale(w), scale(h), FilterType::Triangle)
+}
+
+/// Scale an image down to several sizes in one pass, as for [thumbnail].
+///
+/// The images are returned in the order of `max_sides`.
+/// Each is scaled from the next larger one rather than from the full image, which is much
+/// quicker when making a thumbnail alongside a mid-size version of a large board.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::images::{board_image, renditions, THUMBNAIL_SIZE, WEB_SIZE};
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+/// let full = board_image(&board).unwrap();
+///
+/// let sizes = renditions(&full, &[THUMBNAIL_SIZE, WEB_SIZE]);
+/// assert_eq!(sizes[0].dimensions(), (THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+/// assert_eq!(sizes[1].dimensions(), (WEB_SIZE, WEB_SIZE));
+/// ```
+pub fn renditions(img: &RgbaImage, max_sides: &[u32]) -> Vec<RgbaImage> {
+    let mut order: Vec<usize> = (0..max_sides.len()).collect();
+    order.sort_by_key(|i| std::cmp::Reverse(max_sides[*i]));
+
+    let mut scaled: Vec<Option<RgbaImage>> = vec![None; max_sides.len()];
+    let mut previous: Option<usize> = None;
+    for i in order {
+        let source = previous.and_then(|p| scaled[p].as_ref()).unwrap_or(img);
+        scaled[i] = Some(thumbnail(source, max_sides[i]));
+        previous = Some(i);
+    }
+    scaled.into_iter().flatten().collect()
+}
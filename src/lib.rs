@@ -1,4 +1,8 @@
 //! Eternity 2-family puzzles.
+//!
+//! The model, analyses and solvers are always available.
+//! The Eternity 2 Puzzle data in [e2] needs the `e2-data` feature, and rendering in [images] and
+//! [export] needs the `images` feature; both are on by default.
 
 #![allow(incomplete_features)]
 #![feature(const_for)]
@@ -8,5 +12,21 @@
 #![warn(missing_docs)]
 
 pub mod model;
+pub mod error;
+#[cfg(feature = "e2-data")]
 pub mod e2;
+#[cfg(feature = "images")]
 pub mod images;
+pub mod convert;
+pub mod analysis;
+pub mod solver;
+pub mod generate;
+pub mod examples;
+pub mod bench;
+pub mod transform;
+#[cfg(feature = "images")]
+pub mod export;
+#[cfg(feature = "archive")]
+pub mod archive;
+#[cfg(feature = "bevy")]
+pub mod bevy;
@@ -15,12 +15,67 @@ mod tileset;
 pub use tileset::*;
 
 /// Shared operations on things that are edges.
-pub trait Edge {
+///
+/// Edges are numbered, as they are in data files, so every edge converts to its number; boards
+/// key their [Board::hash64] on these numbers.
+pub trait Edge: Into<u8> {
     /// Check if the edge is a border, that must be placed to the outside of the puzzle.
     fn is_border(&self) -> bool;
+
+    /// How much a pair of facing edges scores, for puzzles where some mismatches are better
+    /// than others.
+    ///
+    /// By default, matching edges score 1 and all others 0, which is the usual edge count;
+    /// border facing border scores 0, as it does in [Board::score].
+    /// Near-miss colour schemes and weighted variants override this, and are scored with
+    /// [Board::weighted_score] and by the local search solvers.
+    fn match_weight(&self, other: &Self) -> u32
+    where
+        Self: PartialEq,
+    {
+        (self == other && !self.is_border()) as u32
+    }
+}
+
+/// Plain numbered edges, as read from data files, where edge 0 is the outside.
+impl Edge for u8 {
+    fn is_border(&self) -> bool {
+        *self == 0
+    }
 }
 
 
 
 mod board;
-pub use board::*;
\ No newline at end of file
+pub use board::*;
+
+mod score;
+pub use score::*;
+
+mod zobrist;
+pub use zobrist::*;
+
+mod symmetry;
+
+mod code;
+
+mod verify;
+pub use verify::*;
+
+mod clue_set;
+pub use clue_set::*;
+
+mod clue_reader;
+pub use clue_reader::*;
+
+mod clue_spec;
+pub use clue_spec::*;
+
+mod entry;
+pub use entry::*;
+
+mod persistent;
+pub use persistent::*;
+
+mod edge_board;
+pub use edge_board::*;
@@ -1,17 +1,45 @@
 use std::ops::IndexMut;
 
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::ops::Index;
 
 use super::ROTATIONS;
 use super::Rotate;
 use super::Rotation;
+use super::Edge;
+use super::Side;
+use super::SIDES;
+use super::ClueSet;
+use super::ClueSpec;
 use super::Tile;
 use super::TileSet;
+use super::parse_tile_lines;
+use super::zobrist_key;
+use crate::error::{Error, ParseError, PlacementError};
 
 /// A (partially filled) board.
 ///
 /// Each cell is empty, or contains a tile with the specified edge type.
-#[derive(Debug)]
+/// Boards are equal when they have the same shape and the same tiles, facing the same way, in
+/// the same cells, so they can be kept in sets to find the distinct boards among many.
+///
+/// ```
+/// use std::collections::HashSet;
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+///
+/// let spec = toy_3x3();
+/// let empty = spec.dimensions.new_board();
+/// let mut solved = empty.clone();
+/// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+///     clue.apply(&mut solved).unwrap();
+/// }
+/// let boards: HashSet<_> = [empty.clone(), solved.clone(), empty].into_iter().collect();
+/// assert_eq!(boards.len(), 2);
+/// assert!(boards.contains(&solved));
+/// ```
+#[derive(Clone, Debug)]
 pub struct Board<E> {
     /// Number of columns in the board (its width).
     pub columns: usize,
@@ -21,6 +49,26 @@ pub struct Board<E> {
 
     /// The squares on the board.
     pub(crate) squares: Vec<Option<Tile<E>>>,
+
+    /// The Zobrist hash of the tiles on the board, kept up to date as tiles are placed and
+    /// removed, or none if cells have been written to directly since it was last worked out.
+    pub(crate) hash: Option<u64>,
+}
+
+impl<E: PartialEq> PartialEq for Board<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.columns == other.columns && self.rows == other.rows && self.squares == other.squares
+    }
+}
+
+impl<E: Eq> Eq for Board<E> {}
+
+impl<E: Hash> Hash for Board<E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.columns.hash(state);
+        self.rows.hash(state);
+        self.squares.hash(state);
+    }
 }
 
 impl<E: Clone> Board<E> {
@@ -30,11 +78,249 @@ impl<E: Clone> Board<E> {
             columns,
             rows,
             squares: vec![None; columns * rows],
+            hash: Some(0),
+        }
+    }
+
+    /// Copy the cells of a region out into a board of their own, with the region's origin at
+    /// its top-left.
+    ///
+    /// Fails if the region doesn't lie within the board.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::model::{BoardShape, Indx};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// let strip = board.crop(Indx { col: 1, row: 2 }, BoardShape { columns: 3, rows: 2 }).unwrap();
+    /// assert_eq!(strip.shape(), BoardShape { columns: 3, rows: 2 });
+    /// assert_eq!(strip[Indx { col: 0, row: 0 }], board[Indx { col: 1, row: 2 }]);
+    /// assert_eq!(strip.score(), 7);
+    /// assert!(board.crop(Indx { col: 2, row: 2 }, BoardShape { columns: 3, rows: 2 }).is_err());
+    /// ```
+    pub fn crop(&self, origin: Indx, shape: BoardShape) -> Result<Board<E>, PlacementError> {
+        let region = Region { origin, shape };
+        self.check_region(region)?;
+        let mut cropped = shape.new_board();
+        for at in region.cells() {
+            let local = Indx {
+                col: at.col - origin.col,
+                row: at.row - origin.row,
+            };
+            cropped[local] = self[at].clone();
+        }
+        Ok(cropped)
+    }
+
+    /// Copy the tiles of another board onto this one, with the other board's top-left at the
+    /// origin.
+    ///
+    /// Only the tiles are copied: where the other board is empty, this board is left as it is,
+    /// so boards built in parts can be pasted together.
+    /// Fails, leaving this board as it was, if the other board doesn't lie within this one.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::model::{BoardShape, Indx};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut solved = spec.dimensions.new_board();
+    /// solved.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// // stitch the solution back together from two halves
+    /// let half = BoardShape { columns: 4, rows: 2 };
+    /// let top = solved.crop(Indx { col: 0, row: 0 }, half).unwrap();
+    /// let bottom = solved.crop(Indx { col: 0, row: 2 }, half).unwrap();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.paste(&bottom, Indx { col: 0, row: 2 }).unwrap();
+    /// board.paste(&top, Indx { col: 0, row: 0 }).unwrap();
+    /// assert_eq!(board, solved);
+    /// assert!(board.paste(&top, Indx { col: 1, row: 0 }).is_err());
+    /// ```
+    pub fn paste(&mut self, sub: &Board<E>, origin: Indx) -> Result<(), PlacementError> {
+        let region = Region {
+            origin,
+            shape: sub.shape(),
+        };
+        self.check_region(region)?;
+        for (at, cell) in sub.cells() {
+            if let Some(tile) = cell {
+                let at = Indx {
+                    col: origin.col + at.col,
+                    row: origin.row + at.row,
+                };
+                self[at] = Some(tile.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Fail with the first cell of the region that isn't on the board, if any.
+    fn check_region(&self, region: Region) -> Result<(), PlacementError> {
+        let shape = self.shape();
+        match region.cells().find(|at| !shape.contains(*at)) {
+            Some(at) => Err(PlacementError::OutOfBounds {
+                at,
+                columns: self.columns,
+                rows: self.rows,
+            }),
+            None => Ok(()),
         }
     }
 }
 
 impl<E> Board<E> {
+    /// The shape of this board.
+    pub fn shape(&self) -> BoardShape {
+        BoardShape {
+            columns: self.columns,
+            rows: self.rows,
+        }
+    }
+
+    /// Iterate mutably over the cells of the board in row-major order, with their locations.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_CLUES};
+    ///
+    /// let mut board = new_e2board();
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    ///
+    /// // clear everything outside the top half
+    /// for (at, cell) in board.iter_mut() {
+    ///     if at.row >= 8 {
+    ///         *cell = None;
+    ///     }
+    /// }
+    /// assert_eq!(board.into_iter().filter(|(_, t)| t.is_some()).count(), 2);
+    /// ```
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Indx, &mut Option<Tile<E>>)> {
+        self.cells_mut()
+    }
+
+    /// Iterate over the cells of the board in row-major order, with their locations.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_CLUES};
+    /// use e2rs::model::Indx;
+    ///
+    /// let mut board = new_e2board();
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// let filled: Vec<Indx> = board
+    ///     .cells()
+    ///     .filter(|(_, cell)| cell.is_some())
+    ///     .map(|(at, _)| at)
+    ///     .collect();
+    /// assert_eq!(filled.len(), E2_CLUES.len());
+    /// assert!(filled.windows(2).all(|w| (w[0].row, w[0].col) < (w[1].row, w[1].col)));
+    /// ```
+    pub fn cells(&self) -> impl Iterator<Item = (Indx, &Option<Tile<E>>)> {
+        let columns = self.columns;
+        self.squares
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (Indx::from_position(i, columns), cell))
+    }
+
+    /// Iterate mutably over the cells of the board in row-major order, with their locations, as
+    /// [Board::iter_mut] does.
+    pub fn cells_mut(&mut self) -> impl Iterator<Item = (Indx, &mut Option<Tile<E>>)> {
+        self.hash = None;
+        let columns = self.columns;
+        self.squares
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (Indx::from_position(i, columns), cell))
+    }
+
+    /// The locations of the empty cells, in row-major order.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// assert_eq!(board.empty_cells().count(), 9);
+    /// let clues = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// board.apply_clues(&clues[..8]).unwrap();
+    /// assert_eq!(board.empty_cells().collect::<Vec<_>>(), [clues[8].at]);
+    /// ```
+    pub fn empty_cells(&self) -> impl Iterator<Item = Indx> + '_ {
+        self.cells()
+            .filter(|(_, cell)| cell.is_none())
+            .map(|(at, _)| at)
+    }
+
+    /// The locations of the empty cells, in the given order.
+    ///
+    /// ```
+    /// use e2rs::model::{BoardShape, CellOrder, Indx};
+    ///
+    /// let shape = BoardShape { columns: 4, rows: 4 };
+    /// let mut board = shape.new_board::<u8>();
+    /// let first = |board: &e2rs::model::Board<u8>| board.empty_cells_in(CellOrder::Spiral).next();
+    /// assert_eq!(first(&board), Some(Indx { col: 0, row: 0 }));
+    /// board[Indx { col: 0, row: 0 }] = Some(e2rs::model::Tile::new(0, 1, 1, 0));
+    /// assert_eq!(first(&board), Some(Indx { col: 1, row: 0 }));
+    /// let inner = board.empty_cells_in(CellOrder::BorderFirst).nth(11);
+    /// assert_eq!(inner, Some(Indx { col: 1, row: 1 }));
+    /// ```
+    pub fn empty_cells_in(&self, order: CellOrder) -> impl Iterator<Item = Indx> + '_ {
+        self.shape()
+            .cells_in(order)
+            .into_iter()
+            .filter(|at| self[*at].is_none())
+    }
+
+    /// Check if every cell of the board holds a tile.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// assert!(!board.is_complete());
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    /// assert!(board.is_complete());
+    /// ```
+    pub fn is_complete(&self) -> bool {
+        self.squares.iter().all(Option::is_some)
+    }
+
+    /// The cell across the given side of a cell, if there is one on the board.
+    ///
+    /// This is none for the sides on the outside of the board, and for cells that aren't on it.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Side};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// let corner = Indx { col: 0, row: 0 };
+    /// assert_eq!(board.neighbour(corner, Side::North), None);
+    /// assert_eq!(board.neighbour(corner, Side::East), Some(&None));
+    ///
+    /// let clue = spec.clue_set(SOLUTION).unwrap().clues[1];
+    /// assert_eq!(clue.at, Indx { col: 1, row: 0 });
+    /// clue.apply(&mut board).unwrap();
+    /// assert_eq!(board.neighbour(corner, Side::East), Some(&Some(clue.placed_tile())));
+    /// ```
+    pub fn neighbour(&self, at: Indx, side: Side) -> Option<&Option<Tile<E>>> {
+        let shape = self.shape();
+        if !shape.contains(at) {
+            return None;
+        }
+        shape.neighbour(at, side).map(|next| &self[next])
+    }
+
     pub(crate) fn indx(&self, c: usize, r: usize) -> usize {
         debug_assert!(c < self.columns);
         debug_assert!(r < self.rows);
@@ -45,6 +331,246 @@ impl<E> Board<E> {
     }
 }
 
+impl<E: Copy + Into<u8>> Board<E> {
+    /// Lift the tile off a cell, leaving it empty.
+    ///
+    /// This gives the tile that was there, or none if the cell was empty or isn't on the board.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::Indx;
+    ///
+    /// let spec = toy_3x3();
+    /// let clue = spec.clue_set(SOLUTION).unwrap().clues[0];
+    /// let mut board = spec.dimensions.new_board();
+    /// clue.apply(&mut board).unwrap();
+    ///
+    /// assert_eq!(board.remove(clue.at), Some(clue.placed_tile()));
+    /// assert_eq!(board[clue.at], None);
+    /// assert_eq!(board.remove(clue.at), None);
+    /// assert_eq!(board.remove(Indx { col: 3, row: 0 }), None);
+    /// ```
+    pub fn remove(&mut self, at: Indx) -> Option<Tile<E>> {
+        if !self.shape().contains(at) {
+            return None;
+        }
+        self.set(at, None)
+    }
+
+    /// Put a tile in a cell, giving back the tile that was there, if any.
+    ///
+    /// Fails if the cell isn't on the board.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let clues = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let (first, second) = (clues[0].placed_tile(), clues[1].placed_tile());
+    /// let mut board = spec.dimensions.new_board();
+    ///
+    /// assert_eq!(board.replace(clues[0].at, first).unwrap(), None);
+    /// assert_eq!(board.replace(clues[0].at, second).unwrap(), Some(first));
+    /// assert_eq!(board[clues[0].at], Some(second));
+    /// ```
+    pub fn replace(&mut self, at: Indx, tile: Tile<E>) -> Result<Option<Tile<E>>, PlacementError> {
+        if !self.shape().contains(at) {
+            return Err(PlacementError::OutOfBounds {
+                at,
+                columns: self.columns,
+                rows: self.rows,
+            });
+        }
+        Ok(self.set(at, Some(tile)))
+    }
+
+    /// Set a cell that is on the board, keeping the hash up to date, and give back what was
+    /// there.
+    pub(crate) fn set(&mut self, at: Indx, cell: Option<Tile<E>>) -> Option<Tile<E>> {
+        let mut hash = self.hash64();
+        let i = self.indx(at.col, at.row);
+        let old = std::mem::replace(&mut self.squares[i], cell);
+        for tile in old.iter().chain(&self.squares[i]) {
+            hash ^= zobrist_key(at, tile);
+        }
+        self.hash = Some(hash);
+        old
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> Board<E> {
+    /// True if the tile, as it would lie, can be placed in a cell: the cell is on the board and
+    /// empty, its sides on the outside of the board are border and the others aren't, and it
+    /// matches every tile already placed around it.
+    ///
+    /// The board is left as it is.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Rotate, Rotation};
+    ///
+    /// let spec = toy_3x3();
+    /// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let corner = solution.iter().find(|c| c.at == Indx { col: 0, row: 0 }).unwrap();
+    /// let mut board = spec.dimensions.new_board();
+    /// assert!(board.can_place(corner.at, &corner.placed_tile()));
+    ///
+    /// // turned, it faces its border inwards
+    /// let turned = corner.placed_tile().rotate(Rotation::Rot90).apply();
+    /// assert!(!board.can_place(corner.at, &turned));
+    ///
+    /// // and once placed, the cell is taken
+    /// corner.apply(&mut board).unwrap();
+    /// assert!(!board.can_place(corner.at, &corner.placed_tile()));
+    /// assert!(!board.can_place(Indx { col: 3, row: 0 }, &corner.placed_tile()));
+    /// ```
+    pub fn can_place(&self, at: Indx, tile: &Tile<E>) -> bool {
+        self.shape().contains(at) && self[at].is_none() && self.fits(at, tile)
+    }
+
+    /// Place every clue, or report every problem with them and leave the board as it is.
+    ///
+    /// A clue can't be placed outside the board, in a cell that already holds a tile, or in the
+    /// same cell as another clue, and no tile can be placed twice, in any rotation, whether by
+    /// two clues or by a clue and the board.
+    /// Clues are placed whether or not they match their neighbours; [Board::conflicts] finds
+    /// those that don't.
+    ///
+    /// The board doesn't know the tileset, so tiles are told apart by their edges alone: a clue
+    /// placing a copy of a tile that the tileset holds more than once is reported as placing
+    /// the same tile as the other copy.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Clue, ClueConflict, Indx};
+    ///
+    /// let spec = toy_3x3();
+    /// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&solution[..2]).unwrap();
+    ///
+    /// let mut moved = solution[3];
+    /// moved.at = solution[2].at;
+    /// let outside = Clue { at: Indx { col: 3, row: 3 }, ..solution[4] };
+    /// let clues = [solution[0], solution[2], moved, outside];
+    /// let conflicts = board.apply_clues(&clues).unwrap_err();
+    /// assert_eq!(
+    ///     conflicts,
+    ///     [
+    ///         ClueConflict::Occupied { clue: 0 },
+    ///         ClueConflict::OnBoard { clue: 0, at: solution[0].at },
+    ///         ClueConflict::SameCell { first: 1, second: 2 },
+    ///         ClueConflict::OutOfBounds { clue: 3 },
+    ///     ]
+    /// );
+    ///
+    /// // the board is unchanged, and a tile can't be placed twice
+    /// assert_eq!(board.conflicts().count(), 0);
+    /// let again = board.apply_clues(&[solution[2], Clue { at: solution[5].at, ..solution[2] }]);
+    /// assert_eq!(again, Err(vec![ClueConflict::SameTile { first: 0, second: 1 }]));
+    ///
+    /// // nor can a tile already on the board, in another cell
+    /// let moved = Clue { at: solution[5].at, ..solution[1] };
+    /// assert_eq!(
+    ///     board.apply_clues(&[moved]),
+    ///     Err(vec![ClueConflict::OnBoard { clue: 0, at: solution[1].at }])
+    /// );
+    /// ```
+    pub fn apply_clues(&mut self, clues: &[Clue<E>]) -> Result<(), Vec<ClueConflict>>
+    where
+        E: Into<u8>,
+    {
+        let shape = self.shape();
+        let mut conflicts = Vec::new();
+        let mut cells: HashMap<(usize, usize), usize> = HashMap::new();
+        for (i, clue) in clues.iter().enumerate() {
+            if !shape.contains(clue.at) {
+                conflicts.push(ClueConflict::OutOfBounds { clue: i });
+                continue;
+            }
+            if self[clue.at].is_some() {
+                conflicts.push(ClueConflict::Occupied { clue: i });
+            } else if let Some(&first) = cells.get(&(clue.at.col, clue.at.row)) {
+                conflicts.push(ClueConflict::SameCell { first, second: i });
+            } else {
+                cells.insert((clue.at.col, clue.at.row), i);
+            }
+            let placed = clue.placed_tile();
+            let same = clues[..i].iter().position(|other| {
+                ROTATIONS
+                    .iter()
+                    .any(|r| other.placed_tile().rotate(*r).apply() == placed)
+            });
+            if let Some(first) = same {
+                conflicts.push(ClueConflict::SameTile { first, second: i });
+            }
+            let on_board = self.cells().find(|(_, cell)| {
+                cell.is_some_and(|tile| ROTATIONS.iter().any(|r| tile.rotate(*r).apply() == placed))
+            });
+            if let Some((at, _)) = on_board {
+                conflicts.push(ClueConflict::OnBoard { clue: i, at });
+            }
+        }
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        for clue in clues {
+            self.set(clue.at, Some(clue.placed_tile()));
+        }
+        Ok(())
+    }
+
+    /// Check a tile against the border and any placed neighbours of a cell, whatever is in the
+    /// cell itself.
+    pub(crate) fn fits(&self, at: Indx, tile: &Tile<E>) -> bool {
+        let shape = self.shape();
+        SIDES.iter().all(|side| {
+            let edge = tile[*side];
+            match shape.neighbour(at, *side) {
+                None => edge.is_border(),
+                Some(n) => {
+                    !edge.is_border() && self[n].as_ref().is_none_or(|t| t[side.flip()] == edge)
+                }
+            }
+        })
+    }
+}
+
+/// Consume a board, yielding its cells in row-major order with their locations.
+impl<E> IntoIterator for Board<E> {
+    type Item = (Indx, Option<Tile<E>>);
+    type IntoIter = IntoCells<E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoCells {
+            columns: self.columns,
+            cells: self.squares.into_iter().enumerate(),
+        }
+    }
+}
+
+/// The consuming iterator over the cells of a [Board].
+pub struct IntoCells<E> {
+    columns: usize,
+    cells: std::iter::Enumerate<std::vec::IntoIter<Option<Tile<E>>>>,
+}
+
+impl<E> Iterator for IntoCells<E> {
+    type Item = (Indx, Option<Tile<E>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cells
+            .next()
+            .map(|(i, cell)| (Indx::from_position(i, self.columns), cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cells.size_hint()
+    }
+}
+
+impl<E> ExactSizeIterator for IntoCells<E> {}
+
 impl<E> Index<(usize, usize)> for Board<E> {
     type Output = Option<Tile<E>>;
 
@@ -65,6 +591,7 @@ impl<E> Index<Indx> for Board<E> {
 
 impl<E> IndexMut<(usize, usize)> for Board<E> {
     fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        self.hash = None;
         let (c, r) = index;
         let index = self.indx(c, r);
         unsafe { self.squares.get_unchecked_mut(index) }
@@ -79,16 +606,88 @@ impl<E> IndexMut<Indx> for Board<E> {
 
 /// A board specification.
 ///
-/// This is composed from an optional board dimensions, and a tileset.
+/// This is composed from an optional board dimensions, a tileset, and any named clue sets.
+#[derive(Debug)]
 pub struct BoardSpec<E> {
     /// The specified dimensions of boards.
     pub dimensions: BoardShape,
 
     /// The tileset to fill boards with.
     pub tiles: TileSet<E>,
+
+    /// Named sets of clues for the board, such as the official clues and hint hypotheses.
+    pub clue_sets: Vec<ClueSet<E>>,
 }
 
 
+impl<E: Copy + Into<u8>> BoardSpec<E> {
+    /// Write this board specification out in the board spec file format.
+    ///
+    /// This is the board shape line, as `<columns> <rows>`, followed by the tiles as written by
+    /// [TileSet::write] in the given side order.
+    /// The output can be read back with [BoardSpec::parse] using the same side order:
+    ///
+    /// ```
+    /// use e2rs::model::{BoardSpec, Side::*};
+    /// use e2rs::examples::toy_4x4;
+    ///
+    /// let spec = toy_4x4();
+    /// let txt = spec.write([North, East, South, West]);
+    /// let read = BoardSpec::<u8>::parse(&txt, [North, East, South, West]).unwrap();
+    /// assert_eq!(read.dimensions, spec.dimensions);
+    /// assert_eq!(read.tiles.len(), spec.tiles.len());
+    /// assert_eq!(read.write([North, East, South, West]), txt);
+    /// ```
+    pub fn write(&self, order: [Side; 4]) -> String {
+        format!(
+            "{} {}\n{}",
+            self.dimensions.columns,
+            self.dimensions.rows,
+            self.tiles.write(order)
+        )
+    }
+}
+
+impl<E: From<u8> + Copy + Default> BoardSpec<E> {
+    /// Parse a board specification in the format written by [BoardSpec::write].
+    ///
+    /// The first line gives the board shape, as `<columns> <rows>` or as a single `<size>` for a
+    /// square board, and every line after it is a tile, with its edges in the given side order.
+    /// The specification has no clue sets.
+    pub fn parse(txt: &str, order: [Side; 4]) -> Result<BoardSpec<E>, ParseError> {
+        let mut lines = txt.lines().enumerate().map(|(i, line)| (i + 1, line));
+        let header = lines.next().map_or("", |(_, line)| line);
+        let sizes = header
+            .split_whitespace()
+            .map(|size| {
+                size.parse::<usize>().map_err(|_| ParseError::BadNumber {
+                    line: 1,
+                    text: size.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let dimensions = match sizes[..] {
+            [size] => BoardShape {
+                columns: size,
+                rows: size,
+            },
+            [columns, rows] => BoardShape { columns, rows },
+            _ => {
+                return Err(ParseError::FieldCount {
+                    line: 1,
+                    expected: 2,
+                    found: sizes.len(),
+                })
+            }
+        };
+        Ok(BoardSpec {
+            dimensions,
+            tiles: parse_tile_lines(lines, order)?,
+            clue_sets: Vec::new(),
+        })
+    }
+}
+
 impl<E: Copy> BoardSpec<E> {
     /// Parse a clues file.
     ///
@@ -96,33 +695,371 @@ impl<E: Copy> BoardSpec<E> {
     ///
     /// If the clockwise flag is true, the rotation is taken to be clockwise.
     /// If false, anti-clockwise.
-    pub fn parse_clues(&self, txt: &str, clockwise: bool) -> Vec<Clue<E>> {
-        let mut clues: Vec<_> = Vec::new();
+    pub fn parse_clues(&self, txt: &str, clockwise: bool) -> Result<Vec<Clue<E>>, ParseError> {
+        txt.lines()
+            .enumerate()
+            .map(|(i, line)| self.parse_clue_line(i + 1, line, clockwise))
+            .collect()
+    }
+
+    /// Parse a single clue line, numbered from 1.
+    pub(crate) fn parse_clue_line(
+        &self,
+        line_no: usize,
+        line: &str,
+        clockwise: bool,
+    ) -> Result<Clue<E>, ParseError> {
+        match self.parse_clue_spec_line(line_no, line, clockwise)? {
+            ClueSpec::Fixed(clue) => Ok(clue),
+            ClueSpec::AnyRotation { .. } => Err(ParseError::OpenRotation { line: line_no }),
+        }
+    }
+
+    /// Parse a single clue line, numbered from 1, allowing `?` or `-1` for the rotation.
+    pub(crate) fn parse_clue_spec_line(
+        &self,
+        line_no: usize,
+        line: &str,
+        clockwise: bool,
+    ) -> Result<ClueSpec<E>, ParseError> {
+        let digits = line
+            .split_whitespace()
+            .enumerate()
+            .map(|(i, d)| match d {
+                "?" | "-1" if i == 3 => Ok(None),
+                _ => d.parse::<usize>().map(Some).map_err(|_| ParseError::BadNumber {
+                    line: line_no,
+                    text: d.to_string(),
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() != 4 {
+            return Err(ParseError::FieldCount {
+                line: line_no,
+                expected: 4,
+                found: digits.len(),
+            });
+        }
+        let number = |d: Option<usize>| d.expect("only the rotation may be open");
 
-        for line in txt.lines() {
-            let digits: Vec<_> = line
-                .trim()
-                .split(" ")
-                .map(|d| d.parse::<usize>().unwrap())
-                .collect();
-            let tile = self.tiles[self.tiles.id(digits[0])];
-            let col = digits[1];
-            let row = digits[2];
-            let at = Indx { col, row };
-            let mut rotation = ROTATIONS[digits[3]];
-            if clockwise {
-                rotation = rotation.reverse();
+        let tile = *self
+            .tiles
+            .try_get(number(digits[0]))
+            .ok_or(ParseError::UnknownTile {
+                line: line_no,
+                tile: number(digits[0]),
+            })?;
+        let col = number(digits[1]);
+        let row = number(digits[2]);
+        let at = Indx { col, row };
+        let Some(rotation) = digits[3] else {
+            return Ok(ClueSpec::AnyRotation { tile, at });
+        };
+        let mut rotation = *ROTATIONS.get(rotation).ok_or(ParseError::BadRotation {
+            line: line_no,
+            rotation,
+        })?;
+        if clockwise {
+            rotation = rotation.reverse();
+        }
+
+        Ok(ClueSpec::Fixed(Clue { tile, rotation, at }))
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> BoardSpec<E> {
+    /// Check clues against the board they are to be placed on.
+    ///
+    /// Each clue must be placed within the board, and once rotated must have its outside edges
+    /// facing exactly the sides of its cell that lie on the board border.
+    /// No two clues may be placed in the same cell, and clues placed next to each other must
+    /// agree on the edge between them.
+    ///
+    /// All problems found are reported, in clue order.
+    /// A clues file read with the wrong rotation convention will typically fail this check.
+    ///
+    /// ```
+    /// use e2rs::model::{ClueError, Indx};
+    /// use e2rs::e2::{E2_BOARD_SPEC, E2_CLUES};
+    ///
+    /// assert!(E2_BOARD_SPEC.validate_clues(&E2_CLUES).is_ok());
+    ///
+    /// let mut cornered = E2_CLUES[0];
+    /// cornered.at = Indx { col: 0, row: 0 };
+    /// let errors = E2_BOARD_SPEC.validate_clues(&[cornered]).unwrap_err();
+    /// assert!(matches!(errors[0], ClueError::Border { clue: 0, .. }));
+    ///
+    /// // the same clue twice is wrong on its own each time, then for sharing the cell
+    /// let errors = E2_BOARD_SPEC.validate_clues(&[cornered, cornered]).unwrap_err();
+    /// let half = (errors.len() - 1) / 2;
+    /// assert!(errors[..half].iter().all(|e| matches!(e, ClueError::Border { clue: 0, .. })));
+    /// assert!(errors[half..errors.len() - 1]
+    ///     .iter()
+    ///     .all(|e| matches!(e, ClueError::Border { clue: 1, .. })));
+    /// assert_eq!(errors.last(), Some(&ClueError::SameCell { first: 0, second: 1 }));
+    /// ```
+    pub fn validate_clues(&self, clues: &[Clue<E>]) -> Result<(), Vec<ClueError>> {
+        let shape = self.dimensions;
+        let mut errors = Vec::new();
+        let mut placed: HashMap<(usize, usize), (usize, Tile<E>)> = HashMap::new();
+
+        for (i, clue) in clues.iter().enumerate() {
+            if !shape.contains(clue.at) {
+                errors.push(ClueError::OutOfBounds { clue: i });
+                continue;
+            }
+
+            let tile = clue.placed_tile();
+            for side in SIDES {
+                if tile[side].is_border() != shape.on_border(clue.at, side) {
+                    errors.push(ClueError::Border { clue: i, side });
+                }
+            }
+
+            match placed.get(&(clue.at.col, clue.at.row)) {
+                Some(&(first, _)) => errors.push(ClueError::SameCell { first, second: i }),
+                None => {
+                    placed.insert((clue.at.col, clue.at.row), (i, tile));
+                }
+            }
+        }
+
+        for (i, clue) in clues.iter().enumerate() {
+            let Some(&(owner, tile)) = placed.get(&(clue.at.col, clue.at.row)) else {
+                continue;
+            };
+            if owner != i {
+                continue;
+            }
+
+            for side in [Side::East, Side::South] {
+                let Some(next) = shape.neighbour(clue.at, side) else {
+                    continue;
+                };
+                if let Some(&(other, other_tile)) = placed.get(&(next.col, next.row)) {
+                    if tile[side] != other_tile[side.flip()] {
+                        errors.push(ClueError::EdgeMismatch {
+                            first: i,
+                            second: other,
+                            side,
+                        });
+                    }
+                }
             }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parse a clues file, and check the clues with [BoardSpec::validate_clues].
+    pub fn load_clues(&self, txt: &str, clockwise: bool) -> Result<Vec<Clue<E>>, Error> {
+        let clues = self.parse_clues(txt, clockwise)?;
+        self.validate_clues(&clues).map_err(PlacementError::Clues)?;
+        Ok(clues)
+    }
+
+    /// Write clues out in the clues file format, with anti-clockwise rotations.
+    ///
+    /// The output reads back with [BoardSpec::load_clues].
+    /// Tiles are identified in the tileset as by [TileSet::identify_unused], in the order of the
+    /// clues, so copies of a tile that the tileset holds more than once get numbers of their own.
+    /// A clue whose tile isn't in the tileset, in any rotation, or is in more clues than it is in
+    /// the tileset, is reported by its cell.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    ///
+    /// let spec = toy_4x4();
+    /// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let txt = spec.write_clues(solution).unwrap();
+    /// let read = spec.load_clues(&txt, false).unwrap();
+    /// assert_eq!(read.len(), solution.len());
+    /// assert_eq!(spec.write_clues(&read).unwrap(), txt);
+    /// ```
+    ///
+    /// ```
+    /// use e2rs::model::{BoardShape, BoardSpec, Clue, Indx, Rotation, Tile, TileSet};
+    ///
+    /// let tile = Tile::new(0, 1, 1, 0);
+    /// let spec = BoardSpec {
+    ///     dimensions: BoardShape { columns: 2, rows: 1 },
+    ///     tiles: TileSet::new(vec![tile, tile]),
+    ///     clue_sets: Vec::new(),
+    /// };
+    /// let clue = |col| Clue { tile, rotation: Rotation::Rot0, at: Indx { col, row: 0 } };
+    /// assert_eq!(spec.write_clues(&[clue(0), clue(1)]).unwrap(), "1 0 0 0\n2 1 0 0\n");
+    /// assert!(spec.write_clues(&[clue(0), clue(1), clue(0)]).is_err());
+    /// ```
+    pub fn write_clues(&self, clues: &[Clue<E>]) -> Result<String, PlacementError> {
+        let mut txt = String::new();
+        let mut used = vec![false; self.tiles.len()];
+        for clue in clues {
+            let (id, rotation) = self
+                .tiles
+                .identify_unused(&clue.placed_tile(), &used)
+                .ok_or(PlacementError::UnknownTile(clue.at))?;
+            used[id.index()] = true;
+            txt.push_str(&format!(
+                "{} {} {} {}\n",
+                id.number(),
+                clue.at.col,
+                clue.at.row,
+                rotation as usize
+            ));
+        }
+        Ok(txt)
+    }
+}
+
+/// A problem with a clue, found by [BoardSpec::validate_clues].
+///
+/// Clues are identified by their position in the clue list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClueError {
+    /// The clue is placed outside the board.
+    OutOfBounds {
+        /// The misplaced clue.
+        clue: usize,
+    },
+    /// The side of the rotated clue tile has an outside edge facing into the board,
+    /// or an inside edge facing the border.
+    Border {
+        /// The misplaced clue.
+        clue: usize,
+        /// The side with the wrong kind of edge.
+        side: Side,
+    },
+    /// Two clues are placed in the same cell.
+    SameCell {
+        /// The clue placed first.
+        first: usize,
+        /// The clue placed on top of it.
+        second: usize,
+    },
+    /// Two neighbouring clues disagree about the edge between them.
+    EdgeMismatch {
+        /// One of the clues.
+        first: usize,
+        /// Its neighbour.
+        second: usize,
+        /// The side of the first clue that faces the second.
+        side: Side,
+    },
+}
 
-            clues.push(Clue { tile, rotation, at })
+impl fmt::Display for ClueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // clue lines are numbered from 1, as they would be in an editor
+        match self {
+            ClueError::OutOfBounds { clue } => write!(f, "clue {} is outside the board", clue + 1),
+            ClueError::Border { clue, side } => write!(
+                f,
+                "clue {} has the wrong kind of edge on its {:?} side",
+                clue + 1,
+                side
+            ),
+            ClueError::SameCell { first, second } => write!(
+                f,
+                "clues {} and {} are in the same cell",
+                first + 1,
+                second + 1
+            ),
+            ClueError::EdgeMismatch {
+                first,
+                second,
+                side,
+            } => write!(
+                f,
+                "clue {} does not match clue {} on its {:?} side",
+                first + 1,
+                second + 1,
+                side
+            ),
         }
+    }
+}
 
-        clues
+impl std::error::Error for ClueError {}
+
+/// A problem applying a clue to a board, found by [Board::apply_clues].
+///
+/// Clues are identified by their position in the clue list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClueConflict {
+    /// The clue is placed outside the board.
+    OutOfBounds {
+        /// The misplaced clue.
+        clue: usize,
+    },
+    /// The clue is placed in a cell that already holds a tile.
+    Occupied {
+        /// The misplaced clue.
+        clue: usize,
+    },
+    /// Two clues are placed in the same cell.
+    SameCell {
+        /// The clue placed first.
+        first: usize,
+        /// The clue placed on top of it.
+        second: usize,
+    },
+    /// Two clues place tiles with the same edges, in some rotation.
+    SameTile {
+        /// The clue placing the tile first.
+        first: usize,
+        /// The clue placing it again.
+        second: usize,
+    },
+    /// A clue places a tile with the same edges, in some rotation, as a tile on the board.
+    OnBoard {
+        /// The clue placing the tile again.
+        clue: usize,
+        /// Where the tile is on the board.
+        at: Indx,
+    },
+}
+
+impl fmt::Display for ClueConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // clue lines are numbered from 1, as they would be in an editor
+        match self {
+            ClueConflict::OutOfBounds { clue } => {
+                write!(f, "clue {} is outside the board", clue + 1)
+            }
+            ClueConflict::Occupied { clue } => {
+                write!(f, "clue {} is in a cell that is already filled", clue + 1)
+            }
+            ClueConflict::SameCell { first, second } => write!(
+                f,
+                "clues {} and {} are in the same cell",
+                first + 1,
+                second + 1
+            ),
+            ClueConflict::SameTile { first, second } => write!(
+                f,
+                "clues {} and {} place the same tile",
+                first + 1,
+                second + 1
+            ),
+            ClueConflict::OnBoard { clue, at } => write!(
+                f,
+                "clue {} places the tile already at {},{}",
+                clue + 1,
+                at.col,
+                at.row
+            ),
+        }
     }
 }
 
+impl std::error::Error for ClueConflict {}
+
 /// A location within a board.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Indx {
     /// Column position.
     pub col: usize,
@@ -130,7 +1067,47 @@ pub struct Indx {
     pub row: usize,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Indx {
+    /// The location of the cell at a position in the row-major order of a board.
+    pub(crate) fn from_position(position: usize, columns: usize) -> Indx {
+        Indx {
+            col: position % columns,
+            row: position / columns,
+        }
+    }
+}
+
+/// A rectangular region within a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// The top-left cell of the region.
+    pub origin: Indx,
+    /// The size of the region.
+    pub shape: BoardShape,
+}
+
+impl Region {
+    /// Check if a location falls within the region.
+    pub fn contains(&self, at: Indx) -> bool {
+        at.col >= self.origin.col
+            && at.row >= self.origin.row
+            && at.col < self.origin.col + self.shape.columns
+            && at.row < self.origin.row + self.shape.rows
+    }
+
+    /// The locations within the region, in row-major order.
+    pub fn cells(&self) -> impl Iterator<Item = Indx> {
+        let Region { origin, shape } = *self;
+        (0..shape.rows).flat_map(move |r| {
+            (0..shape.columns).map(move |c| Indx {
+                col: origin.col + c,
+                row: origin.row + r,
+            })
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 /// The shape of a board.
 pub struct BoardShape {
     /// Column count
@@ -144,11 +1121,107 @@ impl BoardShape {
     pub fn new_board<E: Clone>(&self) -> Board<E> {
         Board::new(self.columns, self.rows)
     }
+
+    /// Check if a location lies within this shape.
+    pub fn contains(&self, at: Indx) -> bool {
+        at.col < self.columns && at.row < self.rows
+    }
+
+    /// Check if the given side of a cell lies on the border.
+    pub fn on_border(&self, at: Indx, side: Side) -> bool {
+        match side {
+            Side::North => at.row == 0,
+            Side::East => at.col + 1 == self.columns,
+            Side::South => at.row + 1 == self.rows,
+            Side::West => at.col == 0,
+        }
+    }
+
+    /// The location across the given side of a cell, if that is within this shape.
+    pub fn neighbour(&self, at: Indx, side: Side) -> Option<Indx> {
+        if self.on_border(at, side) {
+            return None;
+        }
+
+        let Indx { col, row } = at;
+        Some(match side {
+            Side::North => Indx { col, row: row - 1 },
+            Side::East => Indx { col: col + 1, row },
+            Side::South => Indx { col, row: row + 1 },
+            Side::West => Indx { col: col - 1, row },
+        })
+    }
+
+    /// Every location within this shape, in the given order.
+    ///
+    /// ```
+    /// use e2rs::model::{BoardShape, CellOrder, Indx};
+    ///
+    /// let shape = BoardShape { columns: 3, rows: 3 };
+    /// let at = |col, row| Indx { col, row };
+    /// let spiral = shape.cells_in(CellOrder::Spiral);
+    /// assert_eq!(spiral[..4], [at(0, 0), at(1, 0), at(2, 0), at(2, 1)]);
+    /// assert_eq!(spiral[8], at(1, 1));
+    /// let border_first = shape.cells_in(CellOrder::BorderFirst);
+    /// assert_eq!(border_first[7..], [at(0, 1), at(1, 1)]);
+    /// ```
+    pub fn cells_in(&self, order: CellOrder) -> Vec<Indx> {
+        let whole = Region {
+            origin: Indx { col: 0, row: 0 },
+            shape: *self,
+        };
+        match order {
+            CellOrder::RowMajor => whole.cells().collect(),
+            CellOrder::Spiral => {
+                let depth = self.columns.min(self.rows).div_ceil(2);
+                (0..depth).flat_map(|d| self.ring(d)).collect()
+            }
+            CellOrder::BorderFirst => {
+                let inside = |at: &Indx| SIDES.iter().all(|side| !self.on_border(*at, *side));
+                let mut cells = self.ring(0);
+                cells.extend(whole.cells().filter(inside));
+                cells
+            }
+        }
+    }
+
+    /// The cells `depth` in from the border, clockwise from the top-left.
+    pub(crate) fn ring(&self, depth: usize) -> Vec<Indx> {
+        let (left, top) = (depth, depth);
+        if self.columns <= 2 * depth || self.rows <= 2 * depth {
+            return Vec::new();
+        }
+        let (right, bottom) = (self.columns - 1 - depth, self.rows - 1 - depth);
+
+        let mut cells: Vec<Indx> = (left..=right).map(|col| Indx { col, row: top }).collect();
+        cells.extend((top + 1..=bottom).map(|row| Indx { col: right, row }));
+        if bottom > top {
+            cells.extend((left..right).rev().map(|col| Indx { col, row: bottom }));
+        }
+        if right > left {
+            cells.extend((top + 1..bottom).rev().map(|row| Indx { col: left, row }));
+        }
+        cells
+    }
+}
+
+/// An order to visit the cells of a board in, as by [BoardShape::cells_in].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellOrder {
+    /// Left to right along each row, starting from the top.
+    #[default]
+    RowMajor,
+    /// Clockwise around the border from the top-left corner, then around each ring inside it in
+    /// turn, ending in the middle.
+    Spiral,
+    /// Clockwise around the border from the top-left corner, then the cells off the border in
+    /// row-major order.
+    BorderFirst,
 }
 
 
 /// A clue, giving the tile, its rotation and its position within the puzzle.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Clue<E> {
     /// The clue tile.
     pub tile: Tile<E>,
@@ -158,11 +1231,19 @@ pub struct Clue<E> {
     pub at: Indx,
 }
 
-impl<E: Copy + std::fmt::Debug> Clue<E> {
+impl<E: Copy> Clue<E> {
+    /// The clue tile, as it is placed on the board once rotated.
+    pub fn placed_tile(&self) -> Tile<E> {
+        self.tile.rotate(self.rotation).apply()
+    }
+}
+
+impl<E: Copy + Into<u8> + std::fmt::Debug> Clue<E> {
     /// Apply a clue to a board.
-    pub fn apply(&self, board: &mut Board<E>) {
-        let rott = self.tile.rotate(self.rotation);
-        // println!("Writing rotated tile {:?} at {:?}", rott, self.at);
-        board[self.at] = Some(rott.apply());
+    ///
+    /// Fails if the clue lies outside the board.
+    pub fn apply(&self, board: &mut Board<E>) -> Result<(), PlacementError> {
+        board.replace(self.at, self.placed_tile())?;
+        Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,136 @@
+use std::io::BufRead;
+
+use super::{BoardSpec, Clue, ClueError, Edge, Indx, Tile, SIDES};
+use crate::error::ReadError;
+
+impl<E: Copy + Edge + PartialEq> BoardSpec<E> {
+    /// Stream the clues of a clues file, checking each one as it is read.
+    ///
+    /// Each clue is parsed and then checked against the board and the clues before it, as
+    /// [BoardSpec::validate_clues] would, so a large file is never held in memory and the first
+    /// problem stops the stream.
+    /// Problems are reported with the line and byte offset where they were found.
+    ///
+    /// ```
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    /// use e2rs::error::ReadError;
+    ///
+    /// let clues = E2_BOARD_SPEC.read_clues(&b"139 7 8 2\n249 13 13 0\n"[..], false);
+    /// assert_eq!(clues.collect::<Result<Vec<_>, _>>().unwrap().len(), 2);
+    ///
+    /// // the second clue lands on the first
+    /// let mut clues = E2_BOARD_SPEC.read_clues(&b"139 7 8 2\n249 7 8 0\n255 1 1 0\n"[..], false);
+    /// assert!(clues.next().unwrap().is_ok());
+    /// let err = clues.next().unwrap().unwrap_err();
+    /// assert!(matches!(err, ReadError::Clue { line: 2, byte: 10, .. }));
+    /// assert!(clues.next().is_none());
+    /// ```
+    pub fn read_clues<R: BufRead>(&self, reader: R, clockwise: bool) -> ClueReader<'_, E, R> {
+        let shape = self.dimensions;
+        ClueReader {
+            spec: self,
+            reader,
+            clockwise,
+            line: 0,
+            byte: 0,
+            buf: String::new(),
+            cells: vec![None; shape.columns * shape.rows],
+            failed: false,
+        }
+    }
+}
+
+/// A stream of checked clues read from a clues file, as made by [BoardSpec::read_clues].
+///
+/// The stream ends after the first error.
+pub struct ClueReader<'a, E, R> {
+    spec: &'a BoardSpec<E>,
+    reader: R,
+    clockwise: bool,
+    line: usize,
+    byte: u64,
+    buf: String,
+    /// The clue in each cell so far, by clue number, in row-major order.
+    cells: Vec<Option<(usize, Tile<E>)>>,
+    failed: bool,
+}
+
+impl<'a, E: Copy + Edge + PartialEq, R: BufRead> ClueReader<'a, E, R> {
+    /// Check a clue against the board and the clues already read.
+    fn check(&self, i: usize, clue: &Clue<E>) -> Result<(), ClueError> {
+        let shape = self.spec.dimensions;
+        if !shape.contains(clue.at) {
+            return Err(ClueError::OutOfBounds { clue: i });
+        }
+
+        let tile = clue.placed_tile();
+        for side in SIDES {
+            if tile[side].is_border() != shape.on_border(clue.at, side) {
+                return Err(ClueError::Border { clue: i, side });
+            }
+        }
+
+        let cell = |at: Indx| at.col + at.row * shape.columns;
+        if let Some((first, _)) = self.cells[cell(clue.at)] {
+            return Err(ClueError::SameCell { first, second: i });
+        }
+        for side in SIDES {
+            let Some(next) = shape.neighbour(clue.at, side) else {
+                continue;
+            };
+            if let Some((other, other_tile)) = self.cells[cell(next)] {
+                if tile[side] != other_tile[side.flip()] {
+                    return Err(ClueError::EdgeMismatch {
+                        first: i,
+                        second: other,
+                        side,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn next_clue(&mut self) -> Option<Result<Clue<E>, ReadError>> {
+        self.buf.clear();
+        let byte = self.byte;
+        let read = match self.reader.read_line(&mut self.buf) {
+            Ok(0) => return None,
+            Ok(read) => read,
+            Err(source) => return Some(Err(ReadError::Io { byte, source })),
+        };
+        self.byte += read as u64;
+        self.line += 1;
+        let i = self.line - 1;
+
+        let line = self.buf.trim_end_matches(['\n', '\r']);
+        let clue = match self.spec.parse_clue_line(self.line, line, self.clockwise) {
+            Ok(clue) => clue,
+            Err(source) => return Some(Err(ReadError::Parse { byte, source })),
+        };
+        if let Err(source) = self.check(i, &clue) {
+            return Some(Err(ReadError::Clue {
+                line: self.line,
+                byte,
+                source,
+            }));
+        }
+
+        let at = clue.at.col + clue.at.row * self.spec.dimensions.columns;
+        self.cells[at] = Some((i, clue.placed_tile()));
+        Some(Ok(clue))
+    }
+}
+
+impl<'a, E: Copy + Edge + PartialEq, R: BufRead> Iterator for ClueReader<'a, E, R> {
+    type Item = Result<Clue<E>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_clue();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
@@ -0,0 +1,146 @@
+use super::{BoardSpec, Clue, Edge};
+use crate::error::{Error, ParseError, PlacementError};
+
+/// A named set of clues for a board, such as the official clues or a hint hypothesis.
+#[derive(Clone, Debug)]
+pub struct ClueSet<E> {
+    /// The name the set is selected by.
+    pub name: String,
+    /// The clues.
+    pub clues: Vec<Clue<E>>,
+}
+
+/// The name given to clues in a clue sets file that come before any section header.
+pub const DEFAULT_CLUE_SET: &str = "default";
+
+impl<E: Copy> BoardSpec<E> {
+    /// Parse a clue sets file.
+    ///
+    /// This is a clues file split into named sections, each starting with a `[name]` header
+    /// line.
+    /// Clues before the first header form a set named [DEFAULT_CLUE_SET], so a plain clues file
+    /// is read as a single set.
+    /// Blank lines are ignored.
+    ///
+    /// ```
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    ///
+    /// let txt = "[official]\n139 7 8 2\n\n[corner]\n1 0 0 1\n";
+    /// let sets = E2_BOARD_SPEC.parse_clue_sets(txt, false).unwrap();
+    /// assert_eq!(sets.len(), 2);
+    /// assert_eq!(sets[1].name, "corner");
+    /// assert_eq!(sets[1].clues.len(), 1);
+    /// ```
+    pub fn parse_clue_sets(
+        &self,
+        txt: &str,
+        clockwise: bool,
+    ) -> Result<Vec<ClueSet<E>>, ParseError> {
+        let mut sets: Vec<ClueSet<E>> = Vec::new();
+
+        for (i, line) in txt.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                sets.push(ClueSet {
+                    name: name.trim().to_string(),
+                    clues: Vec::new(),
+                });
+                continue;
+            }
+
+            let clue = self.parse_clue_line(i + 1, line, clockwise)?;
+            match sets.last_mut() {
+                Some(set) => set.clues.push(clue),
+                None => sets.push(ClueSet {
+                    name: DEFAULT_CLUE_SET.to_string(),
+                    clues: vec![clue],
+                }),
+            }
+        }
+
+        Ok(sets)
+    }
+
+    /// The clue set with the given name, if the spec carries one.
+    pub fn clue_set(&self, name: &str) -> Option<&ClueSet<E>> {
+        self.clue_sets.iter().find(|s| s.name == name)
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> BoardSpec<E> {
+    /// Add a named clue set to the spec, replacing any set with the same name.
+    ///
+    /// The clues are checked with [BoardSpec::validate_clues], and the set is not added if
+    /// they don't fit.
+    pub fn add_clue_set<S: Into<String>>(
+        &mut self,
+        name: S,
+        clues: Vec<Clue<E>>,
+    ) -> Result<(), PlacementError> {
+        self.validate_clues(&clues).map_err(PlacementError::Clues)?;
+        let set = ClueSet {
+            name: name.into(),
+            clues,
+        };
+        match self.clue_sets.iter_mut().find(|s| s.name == set.name) {
+            Some(existing) => *existing = set,
+            None => self.clue_sets.push(set),
+        }
+        Ok(())
+    }
+
+    /// Parse a clue sets file, as for [BoardSpec::parse_clue_sets], and add each set to the spec.
+    pub fn load_clue_sets(&mut self, txt: &str, clockwise: bool) -> Result<(), Error> {
+        for set in self.parse_clue_sets(txt, clockwise)? {
+            self.add_clue_set(set.name, set.clues)?;
+        }
+        Ok(())
+    }
+
+    /// Combine the named clue sets into one list of clues.
+    ///
+    /// A clue given by more than one set is only listed once.
+    /// The combined clues are checked with [BoardSpec::validate_clues], so sets that place
+    /// different tiles in the same cell, or tiles that don't fit together, can't be combined.
+    ///
+    /// ```
+    /// use e2rs::e2::board_spec;
+    /// use e2rs::error::PlacementError;
+    ///
+    /// let mut spec = board_spec();
+    /// spec.load_clue_sets("[official]\n139 7 8 2\n[a]\n139 7 8 2\n1 0 0 1\n[b]\n2 0 0 1\n", false)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(spec.combine_clue_sets(&["official", "a"]).unwrap().len(), 2);
+    /// assert!(matches!(spec.combine_clue_sets(&["a", "b"]), Err(PlacementError::Clues(_))));
+    /// assert!(matches!(
+    ///     spec.combine_clue_sets(&["c"]),
+    ///     Err(PlacementError::UnknownClueSet(name)) if name == "c"
+    /// ));
+    /// ```
+    pub fn combine_clue_sets<S: AsRef<str>>(
+        &self,
+        names: &[S],
+    ) -> Result<Vec<Clue<E>>, PlacementError> {
+        let mut clues: Vec<Clue<E>> = Vec::new();
+        for name in names {
+            let name = name.as_ref();
+            let set = self
+                .clue_set(name)
+                .ok_or_else(|| PlacementError::UnknownClueSet(name.to_string()))?;
+            for clue in &set.clues {
+                let repeated = clues
+                    .iter()
+                    .any(|c| c.at == clue.at && c.placed_tile() == clue.placed_tile());
+                if !repeated {
+                    clues.push(*clue);
+                }
+            }
+        }
+        self.validate_clues(&clues).map_err(PlacementError::Clues)?;
+        Ok(clues)
+    }
+}
@@ -0,0 +1,172 @@
+use super::{BoardSpec, Clue, Edge, Indx, Tile, ROTATIONS, SIDES};
+use crate::error::{ParseError, PlacementError};
+
+/// A line of a clues file, which may pin a tile to a cell without fixing its rotation.
+///
+/// Clue files mark an open rotation with `?` or `-1` in the rotation column.
+/// The rotation is then left to [BoardSpec::resolve_clues], or to a solver, to find.
+#[derive(Clone, Copy, Debug)]
+pub enum ClueSpec<E> {
+    /// A clue with a known rotation.
+    Fixed(Clue<E>),
+    /// A tile that must be placed in a cell, in whichever rotation fits.
+    AnyRotation {
+        /// The clue tile.
+        tile: Tile<E>,
+        /// Where to place the clue tile.
+        at: Indx,
+    },
+}
+
+impl<E: Copy> ClueSpec<E> {
+    /// The cell the clue tile is placed in.
+    pub fn at(&self) -> Indx {
+        match self {
+            ClueSpec::Fixed(clue) => clue.at,
+            ClueSpec::AnyRotation { at, .. } => *at,
+        }
+    }
+
+    /// The clue tile, unrotated.
+    pub fn tile(&self) -> Tile<E> {
+        match self {
+            ClueSpec::Fixed(clue) => clue.tile,
+            ClueSpec::AnyRotation { tile, .. } => *tile,
+        }
+    }
+
+    /// The clue, if its rotation is known.
+    pub fn fixed(&self) -> Option<Clue<E>> {
+        match self {
+            ClueSpec::Fixed(clue) => Some(*clue),
+            ClueSpec::AnyRotation { .. } => None,
+        }
+    }
+}
+
+impl<E: Copy> BoardSpec<E> {
+    /// Parse a clues file in which rotations may be left open.
+    ///
+    /// This reads the same format as [BoardSpec::parse_clues], but also accepts `?` or `-1` as
+    /// the rotation of a clue, which is then read as [ClueSpec::AnyRotation].
+    pub fn parse_clue_specs(
+        &self,
+        txt: &str,
+        clockwise: bool,
+    ) -> Result<Vec<ClueSpec<E>>, ParseError> {
+        txt.lines()
+            .enumerate()
+            .map(|(i, line)| self.parse_clue_spec_line(i + 1, line, clockwise))
+            .collect()
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> BoardSpec<E> {
+    /// Choose rotations for the clues with open rotations, so that every clue fits the board
+    /// and its neighbouring clues.
+    ///
+    /// Clues are tried in order, each open one in the rotations that put its border edges on
+    /// the outside of the board, backtracking when the clues around a cell disagree.
+    /// Where several choices fit, the first found is given, trying rotations in order from
+    /// [Rotation::Rot0](super::Rotation::Rot0).
+    /// Clues with fixed rotations are kept as they are, and if they don't fit the board, that
+    /// is reported as by [BoardSpec::validate_clues].
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let txt = "1 0 0 ?\n6 1 0 ?\n3 2 0 ?\n2 0 1 -1\n7 1 1 ?\n5 2 1 ?\n4 0 2 ?\n8 1 2 ?\n9 2 2 ?\n";
+    /// let open = spec.parse_clue_specs(txt, false).unwrap();
+    /// assert!(spec.parse_clues(txt, false).is_err());
+    ///
+    /// let resolved = spec.resolve_clues(&open).unwrap();
+    /// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// for (found, expected) in resolved.iter().zip(solution) {
+    ///     assert_eq!(found.rotation, expected.rotation);
+    /// }
+    /// ```
+    pub fn resolve_clues(&self, specs: &[ClueSpec<E>]) -> Result<Vec<Clue<E>>, PlacementError> {
+        let shape = self.dimensions;
+        let mut candidates = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let at = spec.at();
+            if !shape.contains(at) {
+                return Err(PlacementError::OutOfBounds {
+                    at,
+                    columns: shape.columns,
+                    rows: shape.rows,
+                });
+            }
+            let fits: Vec<Clue<E>> = match spec {
+                ClueSpec::Fixed(clue) => vec![*clue],
+                ClueSpec::AnyRotation { tile, at } => ROTATIONS
+                    .iter()
+                    .map(|rotation| Clue {
+                        tile: *tile,
+                        rotation: *rotation,
+                        at: *at,
+                    })
+                    .filter(|clue| {
+                        let placed = clue.placed_tile();
+                        SIDES
+                            .iter()
+                            .all(|s| placed[*s].is_border() == shape.on_border(clue.at, *s))
+                    })
+                    .collect(),
+            };
+            candidates.push(fits);
+        }
+
+        let mut cells = vec![None; shape.columns * shape.rows];
+        let mut chosen = Vec::with_capacity(specs.len());
+        if self.resolve_from(&candidates, &mut cells, &mut chosen) {
+            return Ok(chosen);
+        }
+
+        let fixed: Vec<Clue<E>> = specs.iter().filter_map(ClueSpec::fixed).collect();
+        match self.validate_clues(&fixed) {
+            Err(errors) => Err(PlacementError::Clues(errors)),
+            Ok(()) => Err(PlacementError::UnresolvedRotations),
+        }
+    }
+
+    /// Extend the chosen clues with one candidate for each remaining clue, if possible.
+    fn resolve_from(
+        &self,
+        candidates: &[Vec<Clue<E>>],
+        cells: &mut [Option<Tile<E>>],
+        chosen: &mut Vec<Clue<E>>,
+    ) -> bool {
+        let shape = self.dimensions;
+        let Some(options) = candidates.get(chosen.len()) else {
+            return true;
+        };
+        let cell = |at: Indx| at.col + at.row * shape.columns;
+
+        for clue in options {
+            if cells[cell(clue.at)].is_some() {
+                continue;
+            }
+            let placed = clue.placed_tile();
+            let fits = SIDES.iter().all(|side| {
+                shape
+                    .neighbour(clue.at, *side)
+                    .and_then(|next| cells[cell(next)])
+                    .is_none_or(|other| placed[*side] == other[side.flip()])
+            });
+            if !fits {
+                continue;
+            }
+
+            cells[cell(clue.at)] = Some(placed);
+            chosen.push(*clue);
+            if self.resolve_from(candidates, cells, chosen) {
+                return true;
+            }
+            chosen.pop();
+            cells[cell(clue.at)] = None;
+        }
+        false
+    }
+}
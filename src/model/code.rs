@@ -0,0 +1,147 @@
+use super::{Board, BoardShape, Indx, Rotate, TileSet, ROTATIONS};
+use crate::error::{ParseError, PlacementError};
+
+impl<E: Copy + PartialEq> Board<E> {
+    /// Write the board as a one-line board code, for pasting into chats, issue reports and
+    /// URLs, the way chess positions are shared as FEN.
+    ///
+    /// The code gives the board shape as `COLUMNSxROWS`, then each row in turn after a `/`.
+    /// A row is a comma-separated list of cells, each a placed tile as `NUMBER.ROTATION`, with
+    /// tiles numbered from 1 and rotations anti-clockwise as in clue files, or a run of empty
+    /// cells as `_COUNT`.
+    /// Tiles are identified in the tileset as by [TileSet::identify_unused], in row-major
+    /// order, so copies of a tile that the tileset holds more than once get numbers of their own.
+    /// Fails if a tile isn't in the tileset, or is on the board more often than it is in the
+    /// tileset.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+    /// use e2rs::model::Board;
+    ///
+    /// let mut board = new_e2board();
+    /// E2_CLUES[0].apply(&mut board).unwrap();
+    /// let code = board.to_code(&E2_BOARD_SPEC.tiles).unwrap();
+    /// assert!(code.starts_with("16x16/_16/_16/_16/_16/_16/_16/_16/_16/_7,139.2,_8/"));
+    ///
+    /// let decoded = Board::from_code(&code, &E2_BOARD_SPEC.tiles).unwrap();
+    /// assert_eq!(decoded.to_code(&E2_BOARD_SPEC.tiles).unwrap(), code);
+    /// ```
+    ///
+    /// ```
+    /// use e2rs::model::{BoardShape, Indx, Tile, TileSet};
+    ///
+    /// let tiles = TileSet::new(vec![Tile::new(0, 1, 1, 0), Tile::new(0, 1, 1, 0)]);
+    /// let mut board = BoardShape { columns: 2, rows: 1 }.new_board();
+    /// board[Indx { col: 0, row: 0 }] = Some(tiles[1]);
+    /// board[Indx { col: 1, row: 0 }] = Some(tiles[1]);
+    /// assert_eq!(board.to_code(&tiles).unwrap(), "2x1/1.0,2.0");
+    /// ```
+    pub fn to_code(&self, tiles: &TileSet<E>) -> Result<String, PlacementError> {
+        let mut code = format!("{}x{}", self.columns, self.rows);
+        let mut used = vec![false; tiles.len()];
+        for row in 0..self.rows {
+            let mut cells = Vec::new();
+            let mut empty = 0;
+            for col in 0..self.columns {
+                let at = Indx { col, row };
+                let Some(tile) = &self[at] else {
+                    empty += 1;
+                    continue;
+                };
+                if empty > 0 {
+                    cells.push(format!("_{}", empty));
+                    empty = 0;
+                }
+                let (id, rotation) = tiles
+                    .identify_unused(tile, &used)
+                    .ok_or(PlacementError::UnknownTile(at))?;
+                used[id.index()] = true;
+                cells.push(format!("{}.{}", id.number(), rotation as usize));
+            }
+            if empty > 0 {
+                cells.push(format!("_{}", empty));
+            }
+            code.push('/');
+            code.push_str(&cells.join(","));
+        }
+        Ok(code)
+    }
+
+    /// Read a board back from a board code, as written by [Board::to_code].
+    ///
+    /// A board can't have more cells than the tileset has tiles, so a code giving a larger
+    /// board, or a run of empty cells too long to count, is reported as a bad code rather than
+    /// being made.
+    ///
+    /// ```
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    /// use e2rs::error::ParseError;
+    /// use e2rs::model::Board;
+    ///
+    /// let tiles = &E2_BOARD_SPEC.tiles;
+    /// let huge = Board::from_code("99999999x99999999/", tiles);
+    /// assert!(matches!(huge, Err(ParseError::BadCode(_))));
+    /// let long = Board::from_code("2x1/_18446744073709551615,_1", tiles);
+    /// assert!(matches!(long, Err(ParseError::BadCode(_))));
+    /// ```
+    pub fn from_code(code: &str, tiles: &TileSet<E>) -> Result<Board<E>, ParseError> {
+        let bad = |reason| ParseError::BadCode(reason);
+        let number = |text: &str| text.parse::<usize>().map_err(|_| bad("expected a number"));
+
+        let mut rows_txt = code.trim().split('/');
+        let shape_txt = rows_txt.next().unwrap_or_default();
+        let (columns, rows) = shape_txt.split_once('x').ok_or(bad(
+            "the code must start with the board shape, as COLUMNSxROWS",
+        ))?;
+        let shape = BoardShape {
+            columns: number(columns)?,
+            rows: number(rows)?,
+        };
+        match shape.columns.checked_mul(shape.rows) {
+            Some(cells) if cells <= tiles.len() => {}
+            _ => return Err(bad("the board has more cells than the tileset has tiles")),
+        }
+
+        let mut board = shape.new_board();
+        let mut row = 0;
+        for row_txt in rows_txt {
+            if row == shape.rows {
+                return Err(bad("there are more rows than the board shape gives"));
+            }
+            let mut col: usize = 0;
+            for cell in row_txt.split(',').filter(|c| !c.is_empty()) {
+                if let Some(run) = cell.strip_prefix('_') {
+                    let run = if run.is_empty() { 1 } else { number(run)? };
+                    col = col
+                        .checked_add(run)
+                        .ok_or(bad("a row is longer than the board shape gives"))?;
+                } else {
+                    let (tile, rotation) = cell
+                        .split_once('.')
+                        .ok_or(bad("cells must be NUMBER.ROTATION"))?;
+                    let tile = *tiles
+                        .try_get(number(tile)?)
+                        .ok_or(bad("a tile number is not in the tileset"))?;
+                    let rotation = *ROTATIONS
+                        .get(number(rotation)?)
+                        .ok_or(bad("rotations must be 0 to 3"))?;
+                    if col < shape.columns {
+                        board[Indx { col, row }] = Some(tile.rotate(rotation).apply());
+                    }
+                    col += 1;
+                }
+                if col > shape.columns {
+                    return Err(bad("a row is longer than the board shape gives"));
+                }
+            }
+            if col != shape.columns {
+                return Err(bad("a row is shorter than the board shape gives"));
+            }
+            row += 1;
+        }
+        if row != shape.rows {
+            return Err(bad("there are fewer rows than the board shape gives"));
+        }
+        Ok(board)
+    }
+}
@@ -0,0 +1,184 @@
+use super::{Board, BoardShape, Edge, Indx, Side, Tile, SIDES};
+
+/// A board stored as one array of edges per side, with a bitset of occupied cells.
+///
+/// [Board] keeps each cell as an optional tile, which is the natural layout for reading and
+/// writing whole tiles.
+/// Solvers instead spend their time comparing one edge of a cell with the facing edge of its
+/// neighbour, such as "my south against the north of the cell below", and this layout keeps
+/// all the edges one such comparison reads close together in memory.
+/// The `board_layout` benchmark compares the two.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC, E2_CLUES};
+/// use e2rs::model::{EdgeBoard, Side};
+///
+/// let mut board = new_e2board();
+/// for clue in E2_CLUES.iter() {
+///     clue.apply(&mut board).unwrap();
+/// }
+///
+/// let edges = EdgeBoard::from(&board);
+/// let clue = E2_CLUES[0];
+/// assert_eq!(edges.get(clue.at), board[clue.at]);
+/// assert_eq!(edges.edge(clue.at, Side::South), Some(clue.placed_tile()[Side::South]));
+/// assert!(!edges.fits(clue.at, &E2_BOARD_SPEC.tiles[1]));
+/// assert_eq!(edges.to_board().into_iter().filter(|(_, t)| t.is_some()).count(), 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdgeBoard<E> {
+    shape: BoardShape,
+    /// The edges of placed tiles, one array per side in [SIDES] order, in row-major order.
+    edges: [Vec<E>; 4],
+    /// One bit per cell, set where a tile is placed, in row-major order.
+    occupied: Vec<u64>,
+}
+
+impl<E: Copy + Default> EdgeBoard<E> {
+    /// Make a new, empty board of the given shape.
+    pub fn new(shape: BoardShape) -> Self {
+        let cells = shape.columns * shape.rows;
+        EdgeBoard {
+            shape,
+            edges: std::array::from_fn(|_| vec![E::default(); cells]),
+            occupied: vec![0; cells.div_ceil(64)],
+        }
+    }
+
+    /// Put a tile in a cell, or clear it.
+    ///
+    /// This panics if the cell is outside the board.
+    pub fn set(&mut self, at: Indx, tile: Option<Tile<E>>) {
+        let cell = self.cell(at);
+        let bit = 1 << (cell % 64);
+        match tile {
+            Some(tile) => {
+                for side in SIDES {
+                    self.edges[side as usize][cell] = tile[side];
+                }
+                self.occupied[cell / 64] |= bit;
+            }
+            None => self.occupied[cell / 64] &= !bit,
+        }
+    }
+
+    /// Copy the cells out into a plain board.
+    pub fn to_board(&self) -> Board<E> {
+        let mut board = self.shape.new_board();
+        for row in 0..self.shape.rows {
+            for col in 0..self.shape.columns {
+                let at = Indx { col, row };
+                board[at] = self.get(at);
+            }
+        }
+        board
+    }
+}
+
+impl<E: Copy> EdgeBoard<E> {
+    /// The shape of this board.
+    pub fn shape(&self) -> BoardShape {
+        self.shape
+    }
+
+    /// Check if a cell holds a tile.
+    pub fn is_occupied(&self, at: Indx) -> bool {
+        self.occupied_cell(self.cell(at))
+    }
+
+    /// The edge on one side of the tile in a cell, if the cell holds one.
+    pub fn edge(&self, at: Indx, side: Side) -> Option<E> {
+        self.edge_of_cell(self.cell(at), side)
+    }
+
+    /// The tile in a cell, if the cell holds one.
+    pub fn get(&self, at: Indx) -> Option<Tile<E>> {
+        let cell = self.cell(at);
+        self.occupied_cell(cell).then(|| {
+            let [n, e, s, w] = &self.edges;
+            Tile::new(n[cell], e[cell], s[cell], w[cell])
+        })
+    }
+
+    fn occupied_cell(&self, cell: usize) -> bool {
+        self.occupied[cell / 64] & (1 << (cell % 64)) != 0
+    }
+
+    fn edge_of_cell(&self, cell: usize, side: Side) -> Option<E> {
+        self.occupied_cell(cell)
+            .then(|| self.edges[side as usize][cell])
+    }
+
+    fn cell(&self, at: Indx) -> usize {
+        assert!(self.shape.contains(at), "cell is outside the board");
+        at.col + at.row * self.shape.columns
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> EdgeBoard<E> {
+    /// Check if a tile, placed as it lies, fits a cell: border edges face the outside of the
+    /// board and only there, and every edge matches any tile next to it.
+    ///
+    /// The cell itself may be occupied; only its neighbours are looked at.
+    pub fn fits(&self, at: Indx, tile: &Tile<E>) -> bool {
+        let columns = self.shape.columns;
+        let cell = self.cell(at);
+        SIDES.iter().all(|side| {
+            let edge = tile[*side];
+            if self.shape.on_border(at, *side) {
+                return edge.is_border();
+            }
+            let next = match side {
+                Side::North => cell - columns,
+                Side::East => cell + 1,
+                Side::South => cell + columns,
+                Side::West => cell - 1,
+            };
+            !edge.is_border()
+                && self
+                    .edge_of_cell(next, side.flip())
+                    .is_none_or(|e| e == edge)
+        })
+    }
+
+    /// The number of edges between neighbouring tiles whose colours match, as for
+    /// [Board::score].
+    pub fn score(&self) -> usize {
+        let BoardShape { columns, rows } = self.shape;
+        let [north, east, south, west] = &self.edges;
+        let mut score = 0;
+        for row in 0..rows {
+            for col in 0..columns {
+                let cell = col + row * columns;
+                if !self.occupied_cell(cell) {
+                    continue;
+                }
+                let right = cell + 1;
+                if col + 1 < columns && self.occupied_cell(right) {
+                    score += (east[cell] == west[right]) as usize;
+                }
+                let below = cell + columns;
+                if row + 1 < rows && self.occupied_cell(below) {
+                    score += (south[cell] == north[below]) as usize;
+                }
+            }
+        }
+        score
+    }
+}
+
+impl<E: Copy + Default> From<&Board<E>> for EdgeBoard<E> {
+    fn from(board: &Board<E>) -> Self {
+        let mut edges = EdgeBoard::new(board.shape());
+        for (i, cell) in board.squares.iter().enumerate() {
+            edges.set(Indx::from_position(i, board.columns), *cell);
+        }
+        edges
+    }
+}
+
+impl<E: Copy + Default> From<EdgeBoard<E>> for Board<E> {
+    fn from(board: EdgeBoard<E>) -> Self {
+        board.to_board()
+    }
+}
@@ -0,0 +1,141 @@
+use super::{zobrist_key, Board, Indx, Tile};
+
+/// A view into a single cell of a board, which may be occupied or vacant.
+///
+/// This is made by [Board::entry], and mirrors the entry API of the standard maps.
+/// It allows conditional placement without looking up the cell twice.
+/// Tiles are only handed out by shared reference, so that every change to the cell goes
+/// through the entry and keeps [Board::hash64] up to date.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC};
+/// use e2rs::model::{Entry, Indx};
+///
+/// let mut board = new_e2board();
+/// let at = Indx { col: 0, row: 0 };
+///
+/// board.entry(at).or_place(E2_BOARD_SPEC.tiles[1]);
+/// board.entry(at).or_place(E2_BOARD_SPEC.tiles[2]);
+///
+/// match board.entry(at) {
+///     Entry::Occupied(o) => {
+///         let removed = o.remove();
+///         assert_eq!(removed.is_corner(), E2_BOARD_SPEC.tiles[1].is_corner());
+///     }
+///     Entry::Vacant(_) => unreachable!(),
+/// }
+/// assert!(board[at].is_none());
+/// assert_eq!(board.hash64(), 0);
+/// ```
+pub enum Entry<'a, E> {
+    /// The cell holds a tile.
+    Occupied(OccupiedEntry<'a, E>),
+    /// The cell is empty.
+    Vacant(VacantEntry<'a, E>),
+}
+
+/// A view into a cell that holds a tile.
+pub struct OccupiedEntry<'a, E> {
+    at: Indx,
+    cell: &'a mut Option<Tile<E>>,
+    hash: &'a mut u64,
+}
+
+/// A view into an empty cell.
+pub struct VacantEntry<'a, E> {
+    at: Indx,
+    cell: &'a mut Option<Tile<E>>,
+    hash: &'a mut u64,
+}
+
+impl<E: Copy + Into<u8>> Board<E> {
+    /// Get the entry for a cell, for in-place inspection and placement.
+    pub fn entry(&mut self, at: Indx) -> Entry<'_, E> {
+        let current = self.hash64();
+        let i = self.indx(at.col, at.row);
+        let hash = self.hash.insert(current);
+        let cell = &mut self.squares[i];
+        if cell.is_some() {
+            Entry::Occupied(OccupiedEntry { at, cell, hash })
+        } else {
+            Entry::Vacant(VacantEntry { at, cell, hash })
+        }
+    }
+}
+
+impl<'a, E> Entry<'a, E> {
+    /// The location of the cell.
+    pub fn at(&self) -> Indx {
+        match self {
+            Entry::Occupied(o) => o.at,
+            Entry::Vacant(v) => v.at,
+        }
+    }
+}
+
+impl<'a, E: Copy + Into<u8>> Entry<'a, E> {
+    /// Place the tile if the cell is empty, and return the tile now in the cell.
+    pub fn or_place(self, tile: Tile<E>) -> &'a Tile<E> {
+        self.or_place_with(|| tile)
+    }
+
+    /// Place the tile made by `make` if the cell is empty, and return the tile now in the cell.
+    pub fn or_place_with<F: FnOnce() -> Tile<E>>(self, make: F) -> &'a Tile<E> {
+        match self {
+            Entry::Occupied(o) => o.into_ref(),
+            Entry::Vacant(v) => v.place(make()),
+        }
+    }
+}
+
+impl<'a, E> OccupiedEntry<'a, E> {
+    /// The location of the cell.
+    pub fn at(&self) -> Indx {
+        self.at
+    }
+
+    /// The tile in the cell.
+    pub fn get(&self) -> &Tile<E> {
+        self.cell.as_ref().expect("occupied entries hold a tile")
+    }
+
+    /// Convert the entry into a reference to the tile in the cell, bound to the board's lifetime.
+    pub fn into_ref(self) -> &'a Tile<E> {
+        self.cell.as_ref().expect("occupied entries hold a tile")
+    }
+}
+
+impl<'a, E: Copy + Into<u8>> OccupiedEntry<'a, E> {
+    /// Put a new tile in the cell, returning the tile it replaces.
+    pub fn replace(&mut self, tile: Tile<E>) -> Tile<E> {
+        *self.hash ^= zobrist_key(self.at, &tile);
+        let old = self
+            .cell
+            .replace(tile)
+            .expect("occupied entries hold a tile");
+        *self.hash ^= zobrist_key(self.at, &old);
+        old
+    }
+
+    /// Lift the tile off the board, leaving the cell empty.
+    pub fn remove(self) -> Tile<E> {
+        let old = self.cell.take().expect("occupied entries hold a tile");
+        *self.hash ^= zobrist_key(self.at, &old);
+        old
+    }
+}
+
+impl<'a, E> VacantEntry<'a, E> {
+    /// The location of the cell.
+    pub fn at(&self) -> Indx {
+        self.at
+    }
+}
+
+impl<'a, E: Copy + Into<u8>> VacantEntry<'a, E> {
+    /// Place a tile in the cell, returning a reference to it.
+    pub fn place(self, tile: Tile<E>) -> &'a Tile<E> {
+        *self.hash ^= zobrist_key(self.at, &tile);
+        self.cell.insert(tile)
+    }
+}
@@ -0,0 +1,128 @@
+use std::ops::Index;
+use std::sync::Arc;
+
+use super::{Board, BoardShape, Indx, Tile};
+
+/// The number of cells in each shared chunk of a [PersistentBoard].
+const CHUNK_CELLS: usize = 16;
+
+/// A board whose copies share storage, for keeping many snapshots of a search cheaply.
+///
+/// The cells are held in fixed-size chunks that are shared between a board and its clones, and
+/// a chunk is only copied when a cell in it is changed, so a snapshot costs a handful of
+/// pointers rather than a copy of every cell.
+/// This suits solvers and servers that keep thousands of historical boards which each differ
+/// from the one before by a few placements.
+///
+/// ```
+/// use e2rs::e2::{new_e2board, E2_CLUES};
+/// use e2rs::model::PersistentBoard;
+///
+/// let mut board = PersistentBoard::from(&new_e2board());
+/// let mut history = vec![board.clone()];
+/// for clue in E2_CLUES.iter() {
+///     board.set(clue.at, Some(clue.placed_tile()));
+///     history.push(board.clone());
+/// }
+///
+/// assert!(history[0][E2_CLUES[0].at].is_none());
+/// assert!(history[1][E2_CLUES[0].at].is_some());
+/// assert_eq!(history[5].shared_chunks(&history[4]), 15);
+/// assert_eq!(history[5].to_board().into_iter().filter(|(_, t)| t.is_some()).count(), 5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PersistentBoard<E> {
+    shape: BoardShape,
+    chunks: Vec<Arc<Vec<Option<Tile<E>>>>>,
+}
+
+impl<E: Clone> PersistentBoard<E> {
+    /// Make a new, empty board of the given shape.
+    pub fn new(shape: BoardShape) -> Self {
+        let cells = shape.columns * shape.rows;
+        let chunks = (0..cells)
+            .step_by(CHUNK_CELLS)
+            .map(|start| Arc::new(vec![None; CHUNK_CELLS.min(cells - start)]))
+            .collect();
+        PersistentBoard { shape, chunks }
+    }
+
+    /// Put a tile in a cell, or clear it, returning what was there before.
+    ///
+    /// Only the chunk holding the cell is copied, and only if it is shared with another board.
+    /// This panics if the cell is outside the board.
+    pub fn set(&mut self, at: Indx, tile: Option<Tile<E>>) -> Option<Tile<E>> {
+        let (chunk, offset) = self.locate(at);
+        std::mem::replace(&mut Arc::make_mut(&mut self.chunks[chunk])[offset], tile)
+    }
+
+    /// Copy the cells out into a plain board.
+    pub fn to_board(&self) -> Board<E> {
+        let mut board = self.shape.new_board();
+        board.squares = self.chunks.iter().flat_map(|c| c.iter().cloned()).collect();
+        board.hash = None;
+        board
+    }
+}
+
+impl<E> PersistentBoard<E> {
+    /// The shape of this board.
+    pub fn shape(&self) -> BoardShape {
+        self.shape
+    }
+
+    /// The tile in a cell, if the cell is on the board and holds one.
+    pub fn get(&self, at: Indx) -> Option<&Tile<E>> {
+        if !self.shape.contains(at) {
+            return None;
+        }
+        let (chunk, offset) = self.locate(at);
+        self.chunks[chunk][offset].as_ref()
+    }
+
+    /// The number of chunks of storage this board shares with another.
+    ///
+    /// This is a measure of how cheaply one was derived from the other.
+    pub fn shared_chunks(&self, other: &PersistentBoard<E>) -> usize {
+        self.chunks
+            .iter()
+            .zip(&other.chunks)
+            .filter(|(a, b)| Arc::ptr_eq(a, b))
+            .count()
+    }
+
+    fn locate(&self, at: Indx) -> (usize, usize) {
+        assert!(self.shape.contains(at), "cell is outside the board");
+        let position = at.col + at.row * self.shape.columns;
+        (position / CHUNK_CELLS, position % CHUNK_CELLS)
+    }
+}
+
+impl<E> Index<Indx> for PersistentBoard<E> {
+    type Output = Option<Tile<E>>;
+
+    fn index(&self, at: Indx) -> &Self::Output {
+        let (chunk, offset) = self.locate(at);
+        &self.chunks[chunk][offset]
+    }
+}
+
+impl<E: Clone> From<&Board<E>> for PersistentBoard<E> {
+    fn from(board: &Board<E>) -> Self {
+        let chunks = board
+            .squares
+            .chunks(CHUNK_CELLS)
+            .map(|c| Arc::new(c.to_vec()))
+            .collect();
+        PersistentBoard {
+            shape: board.shape(),
+            chunks,
+        }
+    }
+}
+
+impl<E: Clone> From<PersistentBoard<E>> for Board<E> {
+    fn from(board: PersistentBoard<E>) -> Self {
+        board.to_board()
+    }
+}
@@ -4,7 +4,7 @@ use std::mem::transmute;
 ///
 /// When a tile is rotated, the edges shift around in a cycle, conter-clockwise.
 /// For example, Rot90 will make the new north the old east, the new east the old south and so on.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(usize)]
 pub enum Rotation {
     /// No rotation
@@ -0,0 +1,322 @@
+use std::fmt;
+
+use super::{Board, BoardShape, Edge, Indx, Region, Side, SIDES};
+
+impl<E: Copy + Edge + PartialEq> Board<E> {
+    /// The score of the board: the number of edges between neighbouring tiles whose colours match.
+    ///
+    /// Edges with an empty cell on either side don't score, and nor do the sides of the board;
+    /// [Board::matched_edges] counts those too.
+    /// Border edges facing each other don't score either, as border belongs on the outside.
+    /// A solved Eternity 2 Puzzle scores 480.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{BoardShape, Indx, Tile};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// assert_eq!(board.score(), 0);
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// assert_eq!(board.score(), 12);
+    ///
+    /// // every edge either matches or conflicts, and border facing border conflicts
+    /// let mut blank = BoardShape { columns: 2, rows: 1 }.new_board::<u8>();
+    /// for col in 0..2 {
+    ///     blank[Indx { col, row: 0 }] = Some(Tile::new(0, 0, 0, 0));
+    /// }
+    /// assert_eq!(blank.score(), 0);
+    /// assert_eq!(blank.matched_edges() + blank.conflicts().count(), 7);
+    /// ```
+    pub fn score(&self) -> usize {
+        let whole = Region {
+            origin: Indx { col: 0, row: 0 },
+            shape: self.shape(),
+        };
+        self.score_region(whole).matched
+    }
+
+    /// The score of the board cut into a grid of regions, `grid.0` across and `grid.1` down, to
+    /// show which parts of a partial solution are weakest.
+    ///
+    /// Each cell scores the edges to its east and south neighbours, so every edge is counted
+    /// in exactly one region and the region scores add up to [Board::score].
+    /// The grid is clamped to between one region and one region per cell in each direction,
+    /// and regions differ in size by at most one cell where the board doesn't divide evenly.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_CLUES};
+    ///
+    /// let mut board = new_e2board();
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// let quadrants = board.score_by_region((2, 2));
+    /// assert_eq!(quadrants.regions.len(), 4);
+    /// assert_eq!(quadrants.regions[0].edges, 8 * 8 * 2);
+    /// assert_eq!(quadrants.matched(), board.score());
+    /// println!("{}", quadrants);
+    /// ```
+    pub fn score_by_region(&self, grid: (usize, usize)) -> RegionScores {
+        let across = grid.0.clamp(1, self.columns.max(1));
+        let down = grid.1.clamp(1, self.rows.max(1));
+        let cut = |i: usize, parts: usize, size: usize| i * size / parts;
+
+        let mut regions = Vec::with_capacity(across * down);
+        for r in 0..down {
+            for c in 0..across {
+                let col = cut(c, across, self.columns);
+                let row = cut(r, down, self.rows);
+                let region = Region {
+                    origin: Indx { col, row },
+                    shape: BoardShape {
+                        columns: cut(c + 1, across, self.columns) - col,
+                        rows: cut(r + 1, down, self.rows) - row,
+                    },
+                };
+                regions.push(self.score_region(region));
+            }
+        }
+        RegionScores {
+            grid: (across, down),
+            regions,
+        }
+    }
+
+    fn score_region(&self, region: Region) -> RegionScore {
+        let shape = self.shape();
+        let mut matched = 0;
+        let mut edges = 0;
+        for at in region.cells() {
+            for side in [Side::East, Side::South] {
+                let Some(next) = shape.neighbour(at, side) else {
+                    continue;
+                };
+                edges += 1;
+                if let (Some(a), Some(b)) = (&self[at], &self[next]) {
+                    if a[side] == b[side.flip()] && !a[side].is_border() {
+                        matched += 1;
+                    }
+                }
+            }
+        }
+        RegionScore {
+            region,
+            matched,
+            edges,
+        }
+    }
+}
+
+/// The score of one region of a board, as found by [Board::score_by_region].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegionScore {
+    /// The cells scored.
+    pub region: Region,
+    /// The number of matching edges scored by the region.
+    pub matched: usize,
+    /// The number of edges scored by the region, which is its score when solved.
+    pub edges: usize,
+}
+
+impl RegionScore {
+    /// The fraction of the region's edges that match, from 0 to 1.
+    ///
+    /// A region with no edges to score counts as fully matched.
+    pub fn fraction(&self) -> f64 {
+        if self.edges == 0 {
+            1.0
+        } else {
+            self.matched as f64 / self.edges as f64
+        }
+    }
+}
+
+/// The scores of a board cut into a grid of regions, as found by [Board::score_by_region].
+///
+/// This displays as a grid of `matched/edges` scores, laid out as the regions are on the board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionScores {
+    /// The number of regions across and down.
+    pub grid: (usize, usize),
+    /// The region scores, in row-major order.
+    pub regions: Vec<RegionScore>,
+}
+
+impl RegionScores {
+    /// The total score over all regions.
+    pub fn matched(&self) -> usize {
+        self.regions.iter().map(|r| r.matched).sum()
+    }
+
+    /// The region with the smallest fraction of matching edges, or the first of them if
+    /// several tie.
+    pub fn weakest(&self) -> Option<&RegionScore> {
+        self.regions
+            .iter()
+            .reduce(|a, b| if b.fraction() < a.fraction() { b } else { a })
+    }
+}
+
+impl fmt::Display for RegionScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<String> = self
+            .regions
+            .iter()
+            .map(|r| format!("{}/{}", r.matched, r.edges))
+            .collect();
+        let width = cells.iter().map(String::len).max().unwrap_or(0);
+        for row in cells.chunks(self.grid.0.max(1)) {
+            let line: Vec<String> = row.iter().map(|c| format!("{:>width$}", c)).collect();
+            writeln!(f, "{}", line.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> Board<E> {
+    /// The edges of the board that are as they would be in a solution: the edges between
+    /// neighbouring tiles whose colours match, as counted by [Board::score], and the sides of
+    /// tiles on the outside of the board that are border.
+    ///
+    /// Empty cells don't count, so a solved board counts every edge: the Eternity 2 Puzzle has
+    /// 480 inside and 64 outside.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Rotate, Rotation};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// assert_eq!(board.matched_edges(), 12 + 12);
+    ///
+    /// // a turned corner faces its border inwards
+    /// let corner = Indx { col: 0, row: 0 };
+    /// let turned = board[corner].unwrap().rotate(Rotation::Rot180).apply();
+    /// board[corner] = Some(turned);
+    /// assert_eq!(board.matched_edges(), 24 - 4);
+    /// ```
+    pub fn matched_edges(&self) -> usize {
+        let shape = self.shape();
+        let mut outside = 0;
+        for (at, cell) in self.cells() {
+            let Some(tile) = cell else {
+                continue;
+            };
+            outside += SIDES
+                .iter()
+                .filter(|side| shape.on_border(at, **side) && tile[**side].is_border())
+                .count();
+        }
+        self.score() + outside
+    }
+
+    /// Every place where the board is wrong, as the cell and side on each side of the edge.
+    ///
+    /// These are the neighbouring tiles whose facing edges don't match or are border, and the
+    /// sides of tiles on the outside of the board that aren't border, which have no cell across
+    /// them and so are given as the same cell and side twice.
+    /// Each edge is given once, those between cells from the cell to the west or north.
+    /// Empty cells have no conflicts.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Rotate, Rotation, Side};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// assert_eq!(board.conflicts().count(), 0);
+    ///
+    /// // turning a corner puts its border against its neighbours
+    /// let corner = Indx { col: 0, row: 0 };
+    /// board[corner] = Some(board[corner].unwrap().rotate(Rotation::Rot180).apply());
+    /// let conflicts: Vec<_> = board.conflicts().collect();
+    /// assert_eq!(conflicts.len(), 4);
+    /// assert!(conflicts.contains(&(corner, Side::North, corner, Side::North)));
+    /// assert!(conflicts.contains(&(corner, Side::East, Indx { col: 1, row: 0 }, Side::West)));
+    /// ```
+    pub fn conflicts(&self) -> impl Iterator<Item = (Indx, Side, Indx, Side)> + '_ {
+        let shape = self.shape();
+        self.cells()
+            .filter_map(|(at, cell)| Some((at, cell.as_ref()?)))
+            .flat_map(move |(at, tile)| {
+                SIDES.into_iter().filter_map(move |side| {
+                    let edge = tile[side];
+                    let Some(next) = shape.neighbour(at, side) else {
+                        return (!edge.is_border()).then_some((at, side, at, side));
+                    };
+                    // edges between cells are taken from the cell to the west or north
+                    if matches!(side, Side::North | Side::West) {
+                        return None;
+                    }
+                    let other = self[next].as_ref()?[side.flip()];
+                    let wrong = edge != other || edge.is_border();
+                    wrong.then_some((at, side, next, side.flip()))
+                })
+            })
+    }
+
+    /// The score of the board with each pair of facing edges scored by [Edge::match_weight].
+    ///
+    /// With the default weights, this is the same as [Board::score].
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::Edge;
+    ///
+    /// // colours 1 and 2 are so alike that confusing them scores half
+    /// #[derive(Clone, Copy, PartialEq)]
+    /// struct Shade(u8);
+    /// impl Edge for Shade {
+    ///     fn is_border(&self) -> bool { self.0 == 0 }
+    ///     fn match_weight(&self, other: &Self) -> u32 {
+    ///         match (self.0, other.0) {
+    ///             (a, b) if a == b => 2,
+    ///             (1, 2) | (2, 1) => 1,
+    ///             _ => 0,
+    ///         }
+    ///     }
+    /// }
+    /// impl From<Shade> for u8 {
+    ///     fn from(shade: Shade) -> u8 { shade.0 }
+    /// }
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// assert_eq!(board.weighted_score(), board.score() as u64);
+    ///
+    /// let shaded = spec.tiles.remap_edges(Shade);
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     let (id, _) = spec.tiles.identify(&clue.tile).unwrap();
+    ///     let tile = shaded[id.number()];
+    ///     board[clue.at] = Some(e2rs::model::Rotate::rotate(&tile, clue.rotation).apply());
+    /// }
+    /// assert_eq!(board.weighted_score(), 2 * 12);
+    /// ```
+    pub fn weighted_score(&self) -> u64 {
+        let mut score = 0;
+        for (at, cell) in self.cells() {
+            let Some(tile) = cell else {
+                continue;
+            };
+            for side in [Side::East, Side::South] {
+                if let Some(Some(other)) = self.neighbour(at, side) {
+                    score += tile[side].match_weight(&other[side.flip()]) as u64;
+                }
+            }
+        }
+        score
+    }
+}
@@ -0,0 +1,204 @@
+use super::{Board, BoardShape, Indx, Rotate, Rotation, Tile, ROTATIONS, SIDES};
+
+impl BoardShape {
+    /// The shape of a board of this shape turned as a tile is by the rotation.
+    pub(crate) fn turned(&self, turn: Rotation) -> BoardShape {
+        match turn {
+            Rotation::Rot0 | Rotation::Rot180 => *self,
+            Rotation::Rot90 | Rotation::Rot270 => BoardShape {
+                columns: self.rows,
+                rows: self.columns,
+            },
+        }
+    }
+
+    /// Where a cell goes when a board of this shape is turned as a tile is by the rotation.
+    pub(crate) fn turn_cell(&self, turn: Rotation, at: Indx) -> Indx {
+        let (columns, rows) = (self.columns - 1, self.rows - 1);
+        match turn {
+            Rotation::Rot0 => at,
+            Rotation::Rot90 => Indx {
+                col: rows - at.row,
+                row: at.col,
+            },
+            Rotation::Rot180 => Indx {
+                col: columns - at.col,
+                row: rows - at.row,
+            },
+            Rotation::Rot270 => Indx {
+                col: at.row,
+                row: columns - at.col,
+            },
+        }
+    }
+}
+
+/// Turn the whole board as a tile is turned by the rotation, so that a board turned by
+/// [Rotation::Rot90] has its west side at the top.
+///
+/// Every tile is turned with the board, so tiles that matched still match.
+/// Turning by a quarter swaps the columns and rows.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::model::{BoardShape, Indx, Rotate, Rotation};
+///
+/// let spec = toy_3x3();
+/// let mut board = BoardShape { columns: 3, rows: 2 }.new_board();
+/// let clues = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let top: Vec<_> = clues.iter().copied().filter(|clue| clue.at.row < 2).collect();
+/// board.apply_clues(&top).unwrap();
+///
+/// let turned = board.rotate(Rotation::Rot90);
+/// assert_eq!((turned.columns, turned.rows), (2, 3));
+/// assert_eq!(turned.score(), board.score());
+/// let corner = board[Indx { col: 0, row: 0 }].unwrap();
+/// let turned_corner = (&corner).rotate(Rotation::Rot90).apply();
+/// assert_eq!(turned[Indx { col: 1, row: 0 }], Some(turned_corner));
+/// assert_eq!(turned.rotate(Rotation::Rot270), board);
+/// ```
+impl<E: Copy> Rotate for &Board<E> {
+    type ROTATED = Board<E>;
+
+    fn rotate(self, rotation: Rotation) -> Self::ROTATED {
+        let shape = self.shape();
+        let mut turned = shape.turned(rotation).new_board();
+        for (at, cell) in self.cells() {
+            turned[shape.turn_cell(rotation, at)] =
+                cell.map(|tile| (&tile).rotate(rotation).apply());
+        }
+        turned
+    }
+}
+
+impl<E: Copy> Board<E> {
+    /// The board reflected left to right, with every tile reflected with it, so that each
+    /// tile's east and west edges are swapped.
+    ///
+    /// Reflected tiles are the tiles turned over, so a reflected solution is a solution to the
+    /// puzzle with every tile turned over, rather than to the same puzzle.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Side};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// let mirrored = board.mirror_horizontally();
+    /// assert_eq!(mirrored.score(), board.score());
+    /// let (left, right) = (Indx { col: 0, row: 0 }, Indx { col: 2, row: 0 });
+    /// assert_eq!(mirrored[right].unwrap()[Side::East], board[left].unwrap()[Side::West]);
+    /// assert_eq!(mirrored.mirror_horizontally(), board);
+    /// ```
+    pub fn mirror_horizontally(&self) -> Board<E> {
+        let mut mirrored = self.shape().new_board();
+        for (at, cell) in self.cells() {
+            let col = self.columns - 1 - at.col;
+            mirrored[Indx { col, ..at }] = cell.map(|tile| {
+                let [north, east, south, west] = SIDES.map(|side| tile[side]);
+                Tile::new(north, west, south, east)
+            });
+        }
+        mirrored
+    }
+
+    /// The board reflected top to bottom, with every tile reflected with it, so that each
+    /// tile's north and south edges are swapped.
+    ///
+    /// As for [Board::mirror_horizontally], this turns the tiles over.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Rotate, Rotation};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// let mirrored = board.mirror_vertically();
+    /// assert_eq!(mirrored.score(), board.score());
+    /// assert_eq!(mirrored, board.mirror_horizontally().rotate(Rotation::Rot180));
+    /// ```
+    pub fn mirror_vertically(&self) -> Board<E> {
+        let mut mirrored = self.shape().new_board();
+        for (at, cell) in self.cells() {
+            let row = self.rows - 1 - at.row;
+            mirrored[Indx { row, ..at }] = cell.map(|tile| {
+                let [north, east, south, west] = SIDES.map(|side| tile[side]);
+                Tile::new(south, east, north, west)
+            });
+        }
+        mirrored
+    }
+}
+
+impl<E: Copy + Into<u8>> Board<E> {
+    /// The board turned to its canonical representative, so that boards that are the same but
+    /// for a turn of the whole board canonicalize to the same board.
+    ///
+    /// Of the four turns of the board, this is the one that comes first when ordered by shape
+    /// and then by the cells in row-major order, with empty cells before tiles and tiles ordered
+    /// by their edges as numbers, north first.
+    /// Turns are the only symmetries of a solution, as tiles can't be turned over; use
+    /// [Board::canonicalize_mirrored] for puzzles where they can.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::model::{Rotate, Rotation};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// let turned = board.rotate(Rotation::Rot180);
+    /// assert_ne!(board, turned);
+    /// assert_eq!(board.canonicalize(), turned.canonicalize());
+    /// assert_eq!(turned.canonicalize().score(), board.score());
+    /// ```
+    pub fn canonicalize(&self) -> Board<E> {
+        Self::first(ROTATIONS.map(|turn| self.rotate(turn)))
+    }
+
+    /// The board turned or reflected to its canonical representative, so that boards that are
+    /// the same but for a turn or reflection of the whole board canonicalize to the same board.
+    ///
+    /// The representative is chosen from all eight turns and reflections as
+    /// [Board::canonicalize] chooses it from the turns.
+    /// A reflected tile has its east and west edges swapped, so this suits puzzles whose tiles
+    /// may be turned over.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// board.apply_clues(&spec.clue_set(SOLUTION).unwrap().clues).unwrap();
+    ///
+    /// let mirrored = board.mirror_horizontally();
+    /// assert_ne!(board.canonicalize(), mirrored.canonicalize());
+    /// assert_eq!(board.canonicalize_mirrored(), mirrored.canonicalize_mirrored());
+    /// ```
+    pub fn canonicalize_mirrored(&self) -> Board<E> {
+        let mirrored = self.mirror_horizontally();
+        let turns = ROTATIONS.map(|turn| self.rotate(turn));
+        let reflections = ROTATIONS.map(|turn| mirrored.rotate(turn));
+        Self::first(turns.into_iter().chain(reflections))
+    }
+
+    /// The first of the boards, as ordered by [Board::canonicalize].
+    fn first(boards: impl IntoIterator<Item = Board<E>>) -> Board<E> {
+        boards
+            .into_iter()
+            .min_by_key(|board| {
+                let cells: Vec<Option<[u8; 4]>> = board
+                    .squares
+                    .iter()
+                    .map(|cell| cell.map(|tile| SIDES.map(|side| tile[side].into())))
+                    .collect();
+                (board.columns, board.rows, cells)
+            })
+            .expect("a board has at least one turn")
+    }
+}
@@ -7,7 +7,7 @@ use super::{Edge, Rotate, Rotation};
 /// Sides are identified by their compas cardinalities.
 /// North/south point up/down in columns.
 /// East/west point left/right in rows.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, std::marker::ConstParamTy)]
 #[repr(usize)]
 pub enum Side {
     /// The North, top side.
@@ -60,7 +60,7 @@ pub const SIDES: [Side; 4] = [Side::North, Side::East, Side::South, Side::West];
 /// assert_eq!(tile[Side::South], "s");
 /// assert_eq!(tile[Side::West], "w");
 /// ```
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct Tile<E> {
     edges: [E; 4],
 }
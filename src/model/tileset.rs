@@ -4,8 +4,13 @@ use std::marker::PhantomData;
 use std::ops::Index;
 use std::ops::RangeFull;
 
+use super::Rotate;
+use super::Rotation;
 use super::Side;
 use super::Tile;
+use super::ROTATIONS;
+use super::SIDES;
+use crate::error::ParseError;
 
 /// The ID of a puzzle tile.
 /// 
@@ -20,6 +25,45 @@ impl <'a, E> From<TileID<'a, E>> for u8 {
     }
 }
 
+/// The ID of a puzzle tile, not tied to a borrow of its tileset.
+///
+/// Unlike [TileID], this can be stored in long-lived structures alongside the tileset.
+/// It is checked against a tileset when it is created with [TileSet::tile_id],
+/// and lookups with [TileSet::get] are checked again, since nothing stops it being used with
+/// another tileset.
+/// Tiles are counted from 1, not 0, so the tile number of a TileId is based-1.
+///
+/// ```
+/// use e2rs::e2::E2_BOARD_SPEC;
+///
+/// let tiles = &E2_BOARD_SPEC.tiles;
+/// let id = tiles.tile_id(139).unwrap();
+/// assert_eq!(id.number(), 139);
+/// assert!(tiles.get(id).is_some());
+/// assert!(tiles.tile_id(0).is_none());
+/// assert!(tiles.tile_id(257).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TileId(u8);
+
+impl TileId {
+    /// The tile number, counting from 1.
+    pub fn number(self) -> usize {
+        self.0 as usize + 1
+    }
+
+    /// The position of the tile within `tileset[..]`, counting from 0.
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl <'a, E> From<TileID<'a, E>> for TileId {
+    fn from(value: TileID<'a, E>) -> Self {
+        TileId(value.0)
+    }
+}
+
 /// A complete tileset for an eternity-style puzzle.
 ///
 /// Tile sets are indexed from 1 in the puzzle numbering scheme.
@@ -42,6 +86,214 @@ impl <E> TileSet<E> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    /// Make a [TileId] for a tile number, if the number is in `1..=tileset.len()`.
+    pub fn tile_id(&self, number: usize) -> Option<TileId> {
+        (1..=self.len())
+            .contains(&number)
+            .then(|| TileId((number - 1) as u8))
+    }
+
+    /// Look up a tile by its number, counting from 1, if it is within this tileset.
+    ///
+    /// This is the non-panicking form of `tileset[number]`.
+    pub fn try_get(&self, number: usize) -> Option<&Tile<E>> {
+        self.tile_id(number).and_then(|id| self.get(id))
+    }
+
+    /// Look up a tile by its ID, if it is within this tileset.
+    pub fn get(&self, id: TileId) -> Option<&Tile<E>> {
+        self.0.get(id.index())
+    }
+
+    /// The IDs of all the tiles in this tileset, in order.
+    pub fn ids(&self) -> impl Iterator<Item = TileId> {
+        (0..self.len()).map(|i| TileId(i as u8))
+    }
+}
+
+impl<E: Copy + PartialEq> TileSet<E> {
+    /// Work out which tile, in which rotation, a placed tile is.
+    ///
+    /// Boards only hold the edges of placed tiles, so this recovers the tile identity.
+    /// The rotation is the one that turns the tileset tile into the placed tile,
+    /// as for [Clue::placed_tile](super::Clue::placed_tile).
+    /// Where tiles are duplicated, the first match is given.
+    ///
+    /// ```
+    /// use e2rs::e2::{E2_BOARD_SPEC, E2_CLUES};
+    ///
+    /// let clue = E2_CLUES[0];
+    /// let (id, rotation) = E2_BOARD_SPEC.tiles.identify(&clue.placed_tile()).unwrap();
+    /// assert_eq!(id.number(), 139);
+    /// assert_eq!(rotation as usize, clue.rotation as usize);
+    /// ```
+    pub fn identify(&self, placed: &Tile<E>) -> Option<(TileId, Rotation)> {
+        self.ids().zip(&self.0).find_map(|(id, tile)| {
+            ROTATIONS
+                .into_iter()
+                .find(|r| tile.rotate(*r).apply() == *placed)
+                .map(|r| (id, r))
+        })
+    }
+
+    /// Work out which tile, in which rotation, a placed tile is, as for [TileSet::identify],
+    /// passing over the tiles already marked used.
+    ///
+    /// Where tiles are duplicated, this tells the copies apart as they are placed in turn.
+    ///
+    /// ```
+    /// use e2rs::model::{Tile, TileSet};
+    ///
+    /// let tiles = TileSet::new(vec![Tile::new(0, 1, 1, 0), Tile::new(0, 1, 1, 0)]);
+    /// let placed = Tile::new(0, 1, 1, 0);
+    /// let mut used = vec![false; tiles.len()];
+    /// let (first, _) = tiles.identify_unused(&placed, &used).unwrap();
+    /// used[first.index()] = true;
+    /// let (second, _) = tiles.identify_unused(&placed, &used).unwrap();
+    /// assert_ne!(first, second);
+    /// used[second.index()] = true;
+    /// assert_eq!(tiles.identify_unused(&placed, &used), None);
+    /// ```
+    pub fn identify_unused(&self, placed: &Tile<E>, used: &[bool]) -> Option<(TileId, Rotation)> {
+        self.ids()
+            .zip(&self.0)
+            .filter(|(id, _)| !used.get(id.index()).copied().unwrap_or(false))
+            .find_map(|(id, tile)| {
+                ROTATIONS
+                    .into_iter()
+                    .find(|r| tile.rotate(*r).apply() == *placed)
+                    .map(|r| (id, r))
+            })
+    }
+}
+
+impl <E: Copy + Into<u8>> TileSet<E> {
+    /// Write this tileset out in the tiles file format.
+    ///
+    /// Each tile is written on its own line, with its edges in the given side order.
+    /// So if `order[0]` is North, the first number on each line is the tile's north edge.
+    /// The output can be read back with [parse_tiles] using the same side order.
+    /// It has no dimensions line; [BoardSpec::write](super::BoardSpec::write) writes the tiles
+    /// after one.
+    ///
+    /// ```
+    /// use e2rs::model::{parse_tiles, Side::*};
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    ///
+    /// let txt = E2_BOARD_SPEC.tiles.write([North, South, West, East]);
+    /// let tiles = parse_tiles::<u8, { North }, { South }, { West }, { East }>(&txt).unwrap();
+    /// assert_eq!(tiles.len(), E2_BOARD_SPEC.tiles.len());
+    /// assert_eq!(tiles.write([North, South, West, East]), txt);
+    /// ```
+    pub fn write(&self, order: [Side; 4]) -> String {
+        let mut txt = String::new();
+        for tile in &self.0 {
+            let edges: Vec<_> = order
+                .iter()
+                .map(|s| Into::<u8>::into(tile[*s]).to_string())
+                .collect();
+            txt.push_str(&edges.join(" "));
+            txt.push('\n');
+        }
+        txt
+    }
+}
+
+impl<E: Copy> TileSet<E> {
+    /// Relabel the edges of every tile through a map.
+    ///
+    /// ```
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    /// use e2rs::model::Side;
+    ///
+    /// let numbered = E2_BOARD_SPEC.tiles.remap_edges(u8::from);
+    /// assert_eq!(numbered[1][Side::North], u8::from(E2_BOARD_SPEC.tiles[1][Side::North]));
+    /// ```
+    pub fn remap_edges<F, M: FnMut(E) -> F>(&self, mut map: M) -> TileSet<F> {
+        let tiles = self
+            .0
+            .iter()
+            .map(|t| {
+                Tile::new(
+                    map(t[Side::North]),
+                    map(t[Side::East]),
+                    map(t[Side::South]),
+                    map(t[Side::West]),
+                )
+            })
+            .collect();
+        TileSet(tiles)
+    }
+}
+
+impl<E: Copy + Into<u8> + From<u8>> TileSet<E> {
+    /// Relabel the edge colours into a canonical order.
+    ///
+    /// The outside edge, 0, keeps its label.
+    /// The other colours are numbered from 1 by descending frequency, that is by how many tile
+    /// sides carry them.
+    /// Ties are broken lexicographically, first by each colour's neighbourhood, which is the
+    /// sorted list of the frequencies of the colours next to it clockwise around each tile,
+    /// and only then by the original labels.
+    /// So tilesets differing only in how colours are named normally relabel to the same tileset,
+    /// and can be compared directly.
+    ///
+    /// Returns the relabelled tileset, and the map from old labels to new, indexed by old label.
+    ///
+    /// ```
+    /// use e2rs::e2::E2_BOARD_SPEC;
+    ///
+    /// let tiles = E2_BOARD_SPEC.tiles.remap_edges(u8::from);
+    /// // swap the names of colours 3 and 17
+    /// let renamed = tiles.remap_edges(|e| match e { 3 => 17, 17 => 3, e => e });
+    /// assert_ne!(renamed.write(e2rs::model::SIDES), tiles.write(e2rs::model::SIDES));
+    ///
+    /// let (a, _) = tiles.canonical_recolouring();
+    /// let (b, map) = renamed.canonical_recolouring();
+    /// assert_eq!(a.write(e2rs::model::SIDES), b.write(e2rs::model::SIDES));
+    /// assert_eq!(map[0], 0);
+    /// ```
+    pub fn canonical_recolouring(&self) -> (TileSet<E>, Vec<u8>) {
+        let label = |e: E| -> usize { Into::<u8>::into(e) as usize };
+
+        let mut counts = [0usize; 256];
+        let mut max = 0;
+        for tile in &self.0 {
+            for side in SIDES {
+                let e = label(tile[side]);
+                counts[e] += 1;
+                max = max.max(e);
+            }
+        }
+
+        let mut neighbourhoods = vec![Vec::new(); max + 1];
+        for tile in &self.0 {
+            for side in SIDES {
+                let next = tile[side.rotate(Rotation::Rot90)];
+                neighbourhoods[label(tile[side])].push(counts[label(next)]);
+            }
+        }
+        for n in &mut neighbourhoods {
+            n.sort_unstable_by(|a, b| b.cmp(a));
+        }
+
+        let mut colours: Vec<usize> = (1..=max).collect();
+        colours.sort_by(|a, b| {
+            counts[*b]
+                .cmp(&counts[*a])
+                .then_with(|| neighbourhoods[*b].cmp(&neighbourhoods[*a]))
+                .then_with(|| a.cmp(b))
+        });
+
+        let mut map = vec![0u8; max + 1];
+        for (new, old) in colours.iter().enumerate() {
+            map[*old] = (new + 1) as u8;
+        }
+
+        let tiles = self.remap_edges(|e| E::from(map[label(e)]));
+        (tiles, map)
+    }
 }
 
 impl <'a, E> TileSet<E> {
@@ -65,6 +317,34 @@ impl <E> Index<RangeFull> for TileSet<E> {
     }
 }
 
+/// Index tiles by their number, counting from 1 as in the puzzle numbering scheme.
+///
+/// Panics if the number is not in `1..=tileset.len()`.
+/// Use [TileSet::try_get] to check instead.
+///
+/// ```
+/// use e2rs::e2::E2_BOARD_SPEC;
+/// use e2rs::model::Side::North;
+///
+/// let tiles = &E2_BOARD_SPEC.tiles;
+/// assert_eq!(tiles[1][North], tiles[..][0][North]);
+/// assert!(tiles.try_get(0).is_none());
+/// ```
+impl <E> Index<usize> for TileSet<E> {
+    type Output = Tile<E>;
+
+    fn index(&self, number: usize) -> &Self::Output {
+        match self.try_get(number) {
+            Some(tile) => tile,
+            None => panic!(
+                "tile number {} is out of range: tiles are numbered 1..={}",
+                number,
+                self.len()
+            ),
+        }
+    }
+}
+
 impl <'a, E> Index<TileID<'a, E>> for TileSet<E> {
     type Output = Tile<E>;
 
@@ -92,38 +372,57 @@ impl<'a, E> IntoIterator for &'a TileSet<E> {
 /// So if S1 is North, the first edge in a row will be an edge assigned to the north side of a tile.
 pub fn parse_tiles<E, const S1: Side, const S2: Side, const S3: Side, const S4: Side>(
     txt: &str,
-) -> TileSet<E>
+) -> Result<TileSet<E>, ParseError>
+where
+    E: From<u8> + Copy + Default,
+{
+    parse_tiles_in_order(txt, [S1, S2, S3, S4])
+}
+
+/// Parse a tiles file, with the side order chosen at runtime.
+///
+/// This behaves as [parse_tiles], with `order[0]` playing the part of S1 and so on.
+pub fn parse_tiles_in_order<E>(txt: &str, order: [Side; 4]) -> Result<TileSet<E>, ParseError>
 where
     E: From<u8> + Copy + Default,
 {
-    let blank: Tile<E> = Default::default();
+    let lines = txt.lines().enumerate().map(|(i, line)| (i + 1, line));
+    parse_tile_lines(lines, order)
+}
 
+/// Parse tile lines, each given with its line number counted from 1.
+pub(crate) fn parse_tile_lines<'a, E>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    order: [Side; 4],
+) -> Result<TileSet<E>, ParseError>
+where
+    E: From<u8> + Copy + Default,
+{
     let mut tiles = Vec::new();
-    for line in txt.lines() {
-        // println!("`{}'", line);
-        let digits: Vec<_> = line
-            .trim()
-            .split(" ")
-            // .map(|d| { println!("`{}', ", d); d})
-            .collect();
+    for (line_no, line) in lines {
+        let digits: Vec<_> = line.split_whitespace().collect();
+        if digits.len() != 4 {
+            return Err(ParseError::FieldCount {
+                line: line_no,
+                expected: 4,
+                found: digits.len(),
+            });
+        }
 
-        match digits.len() {
-            4 => {
-                let mut tile = blank.clone();
-                let digits: Vec<_> = digits
-                    .iter()
-                    .map(|d| d.parse::<u8>().unwrap())
-                    .map(From::from)
-                    .collect();
-                tile[S1] = digits[0];
-                tile[S2] = digits[1];
-                tile[S3] = digits[2];
-                tile[S4] = digits[3];
-                tiles.push(tile);
-            }
-            l => panic!("Bad number of digits in board file: {}", l),
+        let mut tile: Tile<E> = Default::default();
+        for (side, digit) in order.iter().zip(digits) {
+            let edge = digit.parse::<u8>().map_err(|_| ParseError::BadNumber {
+                line: line_no,
+                text: digit.to_string(),
+            })?;
+            tile[*side] = edge.into();
         }
+        tiles.push(tile);
+    }
+
+    if tiles.len() > 256 {
+        return Err(ParseError::TooManyTiles(tiles.len()));
     }
 
-    TileSet::new(tiles)
+    Ok(TileSet::new(tiles))
 }
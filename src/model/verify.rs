@@ -0,0 +1,240 @@
+use std::fmt;
+
+use super::{Board, BoardShape, BoardSpec, Edge, Indx, Rotate, Side, TileId, ROTATIONS, SIDES};
+
+impl<E: Copy + Edge + PartialEq> BoardSpec<E> {
+    /// Check that a board is a solution to this puzzle, allowing up to `max_mismatches` edges
+    /// between neighbouring tiles that don't match.
+    ///
+    /// Apart from the mismatches, the board must be a real solution: the right shape, full,
+    /// with every tile of the tileset used exactly once, and with border edges on the outside
+    /// and only there.
+    /// Community solutions are usually best-effort boards, so a tolerance sets the pass mark.
+    ///
+    /// On success, the number of mismatched edges is returned.
+    /// Otherwise every problem found is reported.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::model::{Indx, VerifyError};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// assert_eq!(spec.verify(&board, 0), Ok(0));
+    ///
+    /// // swap two inner tiles, breaking the edges around them
+    /// let (a, b) = (Indx { col: 1, row: 1 }, Indx { col: 2, row: 2 });
+    /// let (ta, tb) = (board[a], board[b]);
+    /// board[a] = tb;
+    /// board[b] = ta;
+    /// assert!(!board.is_valid(&spec, 0));
+    /// assert!(board.is_valid(&spec, 8));
+    ///
+    /// board[a] = None;
+    /// let errors = spec.verify(&board, 8).unwrap_err();
+    /// assert_eq!(errors, vec![VerifyError::EmptyCell(a)]);
+    /// ```
+    pub fn verify(
+        &self,
+        board: &Board<E>,
+        max_mismatches: usize,
+    ) -> Result<usize, Vec<VerifyError>> {
+        let shape = board.shape();
+        if shape != self.dimensions {
+            return Err(vec![VerifyError::WrongShape {
+                expected: self.dimensions,
+                found: shape,
+            }]);
+        }
+
+        let mut errors = Vec::new();
+        let mut used: Vec<Option<Indx>> = vec![None; self.tiles.len()];
+        let mut mismatches = 0;
+        for row in 0..shape.rows {
+            for col in 0..shape.columns {
+                let at = Indx { col, row };
+                let Some(tile) = &board[at] else {
+                    errors.push(VerifyError::EmptyCell(at));
+                    continue;
+                };
+
+                let matches: Vec<TileId> = self
+                    .tiles
+                    .ids()
+                    .filter(|id| {
+                        let candidate = &self.tiles[..][id.index()];
+                        ROTATIONS
+                            .iter()
+                            .any(|r| candidate.rotate(*r).apply() == *tile)
+                    })
+                    .collect();
+                match matches.iter().find(|id| used[id.index()].is_none()) {
+                    Some(id) => used[id.index()] = Some(at),
+                    None => match matches.first() {
+                        Some(id) => errors.push(VerifyError::DuplicateTile {
+                            tile: *id,
+                            first: used[id.index()].expect("only used tiles are duplicated"),
+                            second: at,
+                        }),
+                        None => errors.push(VerifyError::UnknownTile(at)),
+                    },
+                }
+
+                for side in SIDES {
+                    if tile[side].is_border() != shape.on_border(at, side) {
+                        errors.push(VerifyError::Border { at, side });
+                    }
+                }
+                for side in [Side::East, Side::South] {
+                    let Some(next) = shape.neighbour(at, side) else {
+                        continue;
+                    };
+                    if let Some(other) = &board[next] {
+                        if tile[side] != other[side.flip()] {
+                            mismatches += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let unused = used.iter().filter(|u| u.is_none()).count();
+        if unused > 0 && errors.is_empty() {
+            errors.push(VerifyError::UnusedTiles(unused));
+        }
+        if mismatches > max_mismatches {
+            errors.push(VerifyError::Mismatches {
+                found: mismatches,
+                allowed: max_mismatches,
+            });
+        }
+
+        if errors.is_empty() {
+            Ok(mismatches)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> Board<E> {
+    /// Check if this board solves a puzzle with at most `max_mismatches` mismatched edges, as
+    /// for [BoardSpec::verify].
+    pub fn is_valid(&self, spec: &BoardSpec<E>, max_mismatches: usize) -> bool {
+        spec.verify(self, max_mismatches).is_ok()
+    }
+
+    /// Check if this board is full, with every edge between tiles matching, no border edges
+    /// inside and only border edges on the outside: that is, it has no [Board::conflicts].
+    ///
+    /// Unlike [Board::is_valid], this doesn't check the tiles against a puzzle, so a board
+    /// that uses a tile twice can still be solved.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    /// use e2rs::model::{Indx, Rotate, Rotation};
+    ///
+    /// let spec = toy_3x3();
+    /// let mut board = spec.dimensions.new_board();
+    /// let clues = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// board.apply_clues(&clues[..8]).unwrap();
+    /// assert!(!board.is_solved());
+    /// clues[8].apply(&mut board).unwrap();
+    /// assert!(board.is_solved());
+    ///
+    /// // a turned corner faces its border inwards
+    /// let corner = Indx { col: 0, row: 0 };
+    /// board[corner] = Some(board[corner].unwrap().rotate(Rotation::Rot90).apply());
+    /// assert!(board.is_complete());
+    /// assert!(!board.is_solved());
+    /// ```
+    pub fn is_solved(&self) -> bool {
+        self.is_complete() && self.conflicts().next().is_none()
+    }
+}
+
+/// A way in which a board fails to solve a puzzle, as found by [BoardSpec::verify].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The board is not the shape of the puzzle.
+    WrongShape {
+        /// The shape of the puzzle.
+        expected: BoardShape,
+        /// The shape of the board.
+        found: BoardShape,
+    },
+    /// A cell is empty.
+    EmptyCell(Indx),
+    /// The tile in a cell is not in the tileset.
+    UnknownTile(Indx),
+    /// A tile is used twice.
+    DuplicateTile {
+        /// The tile.
+        tile: TileId,
+        /// Where it was first found, in row-major order.
+        first: Indx,
+        /// Where it was found again.
+        second: Indx,
+    },
+    /// Tiles of the tileset are missing from the board, although every cell is filled.
+    UnusedTiles(usize),
+    /// A tile has a border edge facing inwards, or an inner edge facing outwards.
+    Border {
+        /// The cell of the tile.
+        at: Indx,
+        /// The wrong side.
+        side: Side,
+    },
+    /// Too many neighbouring edges don't match.
+    Mismatches {
+        /// The number of mismatched edges.
+        found: usize,
+        /// The number allowed.
+        allowed: usize,
+    },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::WrongShape { expected, found } => write!(
+                f,
+                "the board is {}x{}, but the puzzle is {}x{}",
+                found.columns, found.rows, expected.columns, expected.rows
+            ),
+            VerifyError::EmptyCell(at) => write!(f, "cell {},{} is empty", at.col, at.row),
+            VerifyError::UnknownTile(at) => {
+                write!(f, "the tile at {},{} is not in the tileset", at.col, at.row)
+            }
+            VerifyError::DuplicateTile {
+                tile,
+                first,
+                second,
+            } => write!(
+                f,
+                "tile {} is at both {},{} and {},{}",
+                tile.number(),
+                first.col,
+                first.row,
+                second.col,
+                second.row
+            ),
+            VerifyError::UnusedTiles(count) => write!(f, "{} tiles are not used", count),
+            VerifyError::Border { at, side } => write!(
+                f,
+                "the tile at {},{} has the wrong kind of edge on its {:?} side",
+                at.col, at.row, side
+            ),
+            VerifyError::Mismatches { found, allowed } => write!(
+                f,
+                "{} edges don't match, but at most {} are allowed",
+                found, allowed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
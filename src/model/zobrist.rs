@@ -0,0 +1,102 @@
+use super::{Board, Indx, Tile, SIDES};
+
+/// The Zobrist key of a tile placed in a cell, facing the way it does.
+///
+/// Every cell, tile and rotation has its own pseudo-random key, and the hash of a board is the
+/// exclusive or of the keys of its placed tiles, so placing or removing a tile changes the hash
+/// by its key alone.
+/// The keys depend only on the location and the edges, so they are the same on every board and
+/// in every run.
+///
+/// ```
+/// use e2rs::model::{zobrist_key, Indx, Tile};
+///
+/// let origin = Indx { col: 0, row: 0 };
+/// let blank: Tile<u8> = Tile::new(0, 0, 0, 0);
+/// assert_ne!(zobrist_key(origin, &blank), 0);
+/// assert_ne!(zobrist_key(origin, &blank), zobrist_key(Indx { col: 1, row: 0 }, &blank));
+/// ```
+pub fn zobrist_key<E: Copy + Into<u8>>(at: Indx, tile: &Tile<E>) -> u64 {
+    let edges = SIDES
+        .into_iter()
+        .fold(0, |bits, side| bits << 8 | tile[side].into() as u64);
+    let cell = (at.col as u64 & 0xffff) << 16 | at.row as u64 & 0xffff;
+    mix(cell << 32 | edges)
+}
+
+/// The splitmix64 step, which maps distinct inputs to distinct, well-scattered outputs.
+///
+/// The input is first offset by the splitmix64 increment, so that a blank tile in the top-left
+/// cell, whose input is 0, doesn't get the key 0 and leave the hash unchanged.
+fn mix(bits: u64) -> u64 {
+    let bits = bits.wrapping_add(0x9e3779b97f4a7c15);
+    let bits = (bits ^ bits >> 30).wrapping_mul(0xbf58476d1ce4e5b9);
+    let bits = (bits ^ bits >> 27).wrapping_mul(0x94d049bb133111eb);
+    bits ^ bits >> 31
+}
+
+impl<E: Copy + Into<u8>> Board<E> {
+    /// The Zobrist hash of the board: the exclusive or of the [zobrist_key] of each placed tile.
+    ///
+    /// Equal boards have equal hashes, and an empty board hashes to 0.
+    /// The board keeps its hash up to date as tiles are placed and removed with
+    /// [Board::replace], [Board::remove], [Board::apply_clues], [Board::entry] and
+    /// [Clue::apply](crate::model::Clue::apply), so this is read off in constant time, as a
+    /// transposition table needs.
+    /// The solvers place and lift tiles the same way, so their boards can be hashed at every node.
+    /// Writing to cells directly, by indexing or through [Board::cells_mut], can't be followed;
+    /// the hash is then worked out from every cell, until the next placement or removal stores
+    /// it again.
+    ///
+    /// ```
+    /// use e2rs::e2::{new_e2board, E2_CLUES};
+    ///
+    /// let mut board = new_e2board();
+    /// assert_eq!(board.hash64(), 0);
+    /// for clue in E2_CLUES.iter() {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// let hash = board.hash64();
+    /// assert_ne!(hash, 0);
+    /// assert_eq!(board.clone().hash64(), hash);
+    ///
+    /// // lifting a tile and putting it back restores the hash
+    /// let tile = board.remove(E2_CLUES[0].at).unwrap();
+    /// assert_ne!(board.hash64(), hash);
+    /// board.replace(E2_CLUES[0].at, tile).unwrap();
+    /// assert_eq!(board.hash64(), hash);
+    ///
+    /// // as does writing the cells directly
+    /// board[E2_CLUES[0].at] = None;
+    /// board[E2_CLUES[0].at] = Some(tile);
+    /// assert_eq!(board.hash64(), hash);
+    /// ```
+    ///
+    /// A search placing tiles and undoing them gets back the hash it had at each depth:
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_3x3, SOLUTION};
+    ///
+    /// let spec = toy_3x3();
+    /// let clues = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let mut board = spec.dimensions.new_board();
+    /// let mut seen = Vec::new();
+    /// for clue in clues {
+    ///     seen.push(board.hash64());
+    ///     board.replace(clue.at, clue.placed_tile()).unwrap();
+    ///     assert!(!seen.contains(&board.hash64()));
+    /// }
+    /// for clue in clues.iter().rev() {
+    ///     board.remove(clue.at);
+    ///     assert_eq!(Some(board.hash64()), seen.pop());
+    /// }
+    /// assert_eq!(board.hash64(), 0);
+    /// ```
+    pub fn hash64(&self) -> u64 {
+        self.hash.unwrap_or_else(|| {
+            self.cells()
+                .filter_map(|(at, cell)| cell.as_ref().map(|tile| zobrist_key(at, tile)))
+                .fold(0, |hash, key| hash ^ key)
+        })
+    }
+}
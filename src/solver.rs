@@ -0,0 +1,98 @@
+//! Searching for solutions to edge-matching puzzles.
+//!
+//! [Backtracker] is an exhaustive depth-first solver for small puzzles, [dlx] solves them
+//! as exact cover problems, and [ProfileSolver] counts their solutions by dynamic programming.
+//! [Annealer] and [evolve] instead improve full placements, by local moves and by breeding,
+//! which scales to large puzzles but can't tell when there is no solution.
+//! This also holds the pieces shared between search strategies, such as the heuristics that
+//! decide which cell to fill next and which tiles to try first.
+//! Heuristics are plugged into constructive solvers through the [CellOrdering] and
+//! [ValueOrdering] traits, so they can be written in other crates.
+//! Every solver implements [Solver], and a [Registry] finds them by name.
+//! Puzzles can also be posed for other solvers with [export].
+//!
+//! Runs are repeatable: given the same puzzle, settings and random seed, a solver makes the
+//! same moves and gives the same results every time.
+//! The hash maps used along the way are only looked up in, or sorted before anything is read
+//! out of them, so their randomly seeded iteration order never shows.
+//!
+//! ```
+//! use std::ops::ControlFlow;
+//! use rand::{rngs::StdRng, SeedableRng};
+//! use e2rs::examples::toy_4x4;
+//! use e2rs::solver::{Annealer, Backtracker};
+//!
+//! let spec = toy_4x4();
+//! let found = || {
+//!     let mut boards = Vec::new();
+//!     let outcome = Backtracker::new()
+//!         .with_backjumping()
+//!         .search(&spec, &[], |board: &_| {
+//!             boards.push(board.clone());
+//!             ControlFlow::Continue(())
+//!         })
+//!         .unwrap();
+//!     (boards, outcome.nodes)
+//! };
+//! assert_eq!(found(), found());
+//!
+//! let annealed = || {
+//!     let outcome = Annealer::default().anneal(&spec, &[], &mut StdRng::seed_from_u64(3));
+//!     outcome.unwrap().best
+//! };
+//! assert_eq!(annealed(), annealed());
+//! ```
+
+use crate::model::{Rotation, TileId};
+
+mod anneal;
+pub use anneal::*;
+mod backtrack;
+pub use backtrack::*;
+mod budget;
+pub use budget::*;
+mod counting;
+pub use counting::*;
+mod record;
+pub use record::*;
+mod events;
+pub use events::*;
+mod metrics;
+pub use metrics::*;
+pub mod dlx;
+pub mod export;
+mod filter;
+pub use filter::*;
+mod phases;
+pub use phases::*;
+mod propagate;
+pub use propagate::*;
+mod strategy;
+pub use strategy::*;
+mod symmetry;
+pub use symmetry::*;
+mod table;
+pub use table::*;
+mod zdd;
+pub use zdd::*;
+mod profile;
+pub use profile::*;
+mod genetic;
+pub use genetic::*;
+mod moves;
+pub use moves::*;
+mod hybrid;
+pub use hybrid::*;
+mod heuristics;
+pub use heuristics::*;
+mod evaluator;
+pub use evaluator::*;
+
+/// A candidate placement for a cell: a tile, and the rotation to place it in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    /// The tile.
+    pub tile: TileId,
+    /// How to rotate the tile.
+    pub rotation: Rotation,
+}
@@ -0,0 +1,401 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::error::PlacementError;
+use crate::model::{
+    Board, BoardShape, BoardSpec, Clue, Edge, Indx, Rotate, Tile, ROTATIONS, SIDES,
+};
+
+use super::{border_sides, Budget, Neighbourhood, NoRecords, Record, RecordHook, SearchStats};
+
+/// How the temperature of an [Annealer] falls over a run.
+///
+/// Any `Fn(f64) -> f64` closure is a schedule.
+pub trait Schedule {
+    /// The temperature when the given fraction of the run, from 0 to 1, is done.
+    fn temperature(&self, progress: f64) -> f64;
+}
+
+impl<F: Fn(f64) -> f64> Schedule for F {
+    fn temperature(&self, progress: f64) -> f64 {
+        self(progress)
+    }
+}
+
+/// A temperature falling by the same factor at every step, from `start` to `end`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Geometric {
+    /// The temperature at the start of the run.
+    pub start: f64,
+    /// The temperature at the end of the run.
+    pub end: f64,
+}
+
+impl Schedule for Geometric {
+    fn temperature(&self, progress: f64) -> f64 {
+        self.start * (self.end / self.start).powf(progress)
+    }
+}
+
+/// A temperature falling by the same amount at every step, from `start` to `end`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Linear {
+    /// The temperature at the start of the run.
+    pub start: f64,
+    /// The temperature at the end of the run.
+    pub end: f64,
+}
+
+impl Schedule for Linear {
+    fn temperature(&self, progress: f64) -> f64 {
+        self.start + (self.end - self.start) * progress
+    }
+}
+
+/// A simulated annealing solver, which improves a full placement of the tiles by local moves.
+///
+/// The run starts from a random placement, with the clues in their cells and every other cell
+/// holding a tile with as many outside edges as the cell has border sides, turned to face them
+/// outwards.
+/// Each step then makes a random move from a [Neighbourhood], either swapping the tiles in two
+/// cells of the same kind, turning each to face the border, or turning an inside tile in its
+/// cell, and keeps the change if it scores at least as well, or otherwise with a probability
+/// that falls with the temperature and with how much score would be lost.
+/// Boards are scored as by [Board::weighted_score], so edges that weigh their matches with
+/// [Edge::match_weight] steer the search.
+/// Clue cells are never changed.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use e2rs::examples::{toy_4x4, SOLUTION};
+/// use e2rs::solver::{Annealer, Budget};
+///
+/// let spec = toy_4x4();
+/// let clue = spec.clue_set(SOLUTION).unwrap().clues[5];
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let outcome = Annealer::default().anneal(&spec, &[clue], &mut rng).unwrap();
+///
+/// assert_eq!(outcome.best[clue.at], Some(clue.placed_tile()));
+/// assert_eq!(outcome.best_score, outcome.best.score());
+/// assert!(outcome.best_score >= outcome.start_score);
+///
+/// // a budget cuts the run short
+/// let quick = Annealer {
+///     budget: Budget::default().with_nodes(100),
+///     ..Annealer::default()
+/// };
+/// assert_eq!(quick.anneal(&spec, &[], &mut rng).unwrap().stats.nodes, 100);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Annealer<S = Geometric> {
+    /// The number of moves to try.
+    pub iterations: u64,
+    /// How the temperature falls over the run.
+    pub schedule: S,
+    /// Limits that end the run early, before it has cooled.
+    ///
+    /// Nodes are moves tried; there is no backtracking.
+    pub budget: Budget,
+}
+
+impl Default for Annealer {
+    /// 100,000 moves, cooling geometrically from 2 to 0.05.
+    fn default() -> Self {
+        Annealer {
+            iterations: 100_000,
+            schedule: Geometric {
+                start: 2.0,
+                end: 0.05,
+            },
+            budget: Budget::default(),
+        }
+    }
+}
+
+/// The result of a run of an [Annealer].
+#[derive(Clone, Debug)]
+pub struct AnnealOutcome<E> {
+    /// The best board reached.
+    pub best: Board<E>,
+    /// The score of the best board, as by [Board::weighted_score], which is [Board::score] for
+    /// edges with the default weights.
+    pub best_score: usize,
+    /// The score of the random placement the run started from.
+    pub start_score: usize,
+    /// The score of the board at the end of the run.
+    pub end_score: usize,
+    /// The number of moves kept.
+    pub accepted: u64,
+    /// What the annealer did, counting each move tried as a node.
+    pub stats: SearchStats,
+}
+
+impl<S: Schedule> Annealer<S> {
+    /// Run the annealer from a random placement extending the clues.
+    ///
+    /// Clues that don't fit the board are reported as by [BoardSpec::validate_clues].
+    /// If there are more tiles than cells of some kind, the extra tiles are left out, and if
+    /// there are fewer, the first cell that can't be filled is reported.
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::model::{BoardSpec, Edge};
+    /// use e2rs::solver::Annealer;
+    ///
+    /// // edges that score for not matching, so the annealer is steered away from solutions
+    /// #[derive(Clone, Copy, Debug, PartialEq)]
+    /// struct Contrary(u8);
+    /// impl Edge for Contrary {
+    ///     fn is_border(&self) -> bool { self.0 == 0 }
+    ///     fn match_weight(&self, other: &Self) -> u32 {
+    ///         (self != other) as u32
+    ///     }
+    /// }
+    /// impl From<Contrary> for u8 {
+    ///     fn from(edge: Contrary) -> u8 { edge.0 }
+    /// }
+    ///
+    /// let spec = toy_4x4();
+    /// let contrary = BoardSpec {
+    ///     dimensions: spec.dimensions,
+    ///     tiles: spec.tiles.remap_edges(Contrary),
+    ///     clue_sets: Vec::new(),
+    /// };
+    /// let annealer = Annealer::default();
+    /// let plain = annealer.anneal(&spec, &[], &mut StdRng::seed_from_u64(5)).unwrap();
+    /// let steered = annealer.anneal(&contrary, &[], &mut StdRng::seed_from_u64(5)).unwrap();
+    ///
+    /// assert_eq!(steered.best_score as u64, steered.best.weighted_score());
+    /// assert!(steered.best.score() < plain.best.score());
+    /// ```
+    pub fn anneal<E, R>(
+        &self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        rng: &mut R,
+    ) -> Result<AnnealOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        R: Rng,
+    {
+        self.anneal_with_hook(spec, clues, rng, &mut NoRecords)
+    }
+
+    /// Run the annealer as for [Annealer::anneal], telling the hook of the starting placement
+    /// and of each better placement found after it.
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Annealer, Record};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut records: Vec<Record<u8>> = Vec::new();
+    /// let outcome = Annealer::default()
+    ///     .anneal_with_hook(&spec, &[], &mut StdRng::seed_from_u64(3), &mut |record| {
+    ///         records.push(record)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(records[0].score, outcome.start_score);
+    /// assert!(records.windows(2).all(|r| r[0].score < r[1].score));
+    /// assert_eq!(records.last().unwrap().score, outcome.best_score);
+    /// ```
+    pub fn anneal_with_hook<E, R>(
+        &self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        rng: &mut R,
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<AnnealOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        R: Rng,
+    {
+        let board = random_fill(spec, clues, rng)?;
+        let locked: Vec<Indx> = clues.iter().map(|clue| clue.at).collect();
+        Ok(self.refine_with_hook(board, &locked, rng, hook))
+    }
+
+    /// Run the annealer from a full board, never changing the locked cells, telling the hook
+    /// of the starting board and of each better board found after it.
+    ///
+    /// Every tile on the board should face the border, as it does in a board filled by
+    /// [Annealer::anneal], since the moves keep the border as it is.
+    ///
+    /// ```
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::solver::{Annealer, NoRecords};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// // nothing beats a solution
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// let outcome = Annealer::default().refine_with_hook(board, &[], &mut rng, &mut NoRecords);
+    /// assert_eq!(outcome.best_score, outcome.start_score);
+    /// ```
+    pub fn refine_with_hook<E, R>(
+        &self,
+        mut board: Board<E>,
+        locked: &[Indx],
+        rng: &mut R,
+        hook: &mut dyn RecordHook<E>,
+    ) -> AnnealOutcome<E>
+    where
+        E: Copy + Edge + PartialEq,
+        R: Rng,
+    {
+        let moves = Neighbourhood::new(board.shape(), locked);
+
+        let start_score = board.weighted_score() as usize;
+        let mut score = start_score;
+        let mut best = board.clone();
+        let mut best_score = score;
+        let mut accepted = 0;
+        let mut stats = SearchStats::default();
+        let meter = self.budget.start();
+        hook.record(Record::new(&board, 0));
+
+        for step in 0..self.iterations {
+            if meter.exhausted(&stats) {
+                break;
+            }
+            stats.nodes += 1;
+            let Some(found) = moves.random(rng) else {
+                if moves.free().is_empty() {
+                    break;
+                }
+                continue;
+            };
+
+            let delta = found.delta(&board);
+            let temperature = self
+                .schedule
+                .temperature(step as f64 / self.iterations as f64);
+            let keep = delta >= 0
+                || (temperature > 0.0 && rng.gen::<f64>() < (delta as f64 / temperature).exp());
+            if keep {
+                found.apply(&mut board);
+                accepted += 1;
+                score = score.saturating_add_signed(delta);
+                if score > best_score {
+                    best_score = score;
+                    best = board.clone();
+                    hook.record(Record::new(&board, stats.nodes));
+                }
+            }
+        }
+
+        AnnealOutcome {
+            best,
+            best_score,
+            start_score,
+            end_score: score,
+            accepted,
+            stats,
+        }
+    }
+}
+
+/// A random full placement extending the clues, with every tile turned to face the border.
+///
+/// Each cell is given a tile with as many outside edges as the cell has border sides.
+pub(crate) fn random_fill<E, R>(
+    spec: &BoardSpec<E>,
+    clues: &[Clue<E>],
+    rng: &mut R,
+) -> Result<Board<E>, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+    R: Rng,
+{
+    spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+    let mut board = spec.dimensions.new_board();
+    for clue in clues {
+        board.set(clue.at, Some(clue.placed_tile()));
+    }
+    random_completion(spec, board, rng)
+}
+
+/// A random full placement extending a partial one, with every tile it adds turned to face the
+/// border.
+///
+/// Each empty cell is given a tile not on the board, with as many outside edges as the cell has
+/// border sides.
+pub(crate) fn random_completion<E, R>(
+    spec: &BoardSpec<E>,
+    mut board: Board<E>,
+    rng: &mut R,
+) -> Result<Board<E>, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+    R: Rng,
+{
+    let shape = spec.dimensions;
+    let mut used = vec![false; spec.tiles.len()];
+    for (at, placed) in board.clone() {
+        if let Some(placed) = placed {
+            let (id, _) = spec
+                .tiles
+                .identify(&placed)
+                .ok_or(PlacementError::UnknownTile(at))?;
+            used[id.index()] = true;
+        }
+    }
+
+    let mut pools: Vec<Vec<Tile<E>>> = vec![Vec::new(); 5];
+    for (tile, used) in spec.tiles[..].iter().zip(used) {
+        if !used {
+            let outside = SIDES.iter().filter(|s| tile[**s].is_border()).count();
+            pools[outside].push(*tile);
+        }
+    }
+    for pool in &mut pools {
+        pool.shuffle(rng);
+    }
+
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let at = Indx { col, row };
+            if board[at].is_some() {
+                continue;
+            }
+            let no_tile = PlacementError::NoTileFor(at);
+            let tile = pools[border_sides(shape, at)]
+                .pop()
+                .ok_or(no_tile.clone())?;
+            let placed = *orientations(shape, at, &tile).choose(rng).ok_or(no_tile)?;
+            board.set(at, Some(placed));
+        }
+    }
+    Ok(board)
+}
+
+/// The distinct ways a tile can be turned to face its outside edges, and only those, onto the
+/// border around a cell.
+pub(crate) fn orientations<E: Copy + Edge + PartialEq>(
+    shape: BoardShape,
+    at: Indx,
+    tile: &Tile<E>,
+) -> Vec<Tile<E>> {
+    let mut found: Vec<Tile<E>> = Vec::with_capacity(4);
+    for rotation in ROTATIONS {
+        let turned = tile.rotate(rotation).apply();
+        let fits = SIDES
+            .iter()
+            .all(|s| turned[*s].is_border() == shape.on_border(at, *s));
+        if fits && !found.contains(&turned) {
+            found.push(turned);
+        }
+    }
+    found
+}
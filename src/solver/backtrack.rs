@@ -0,0 +1,870 @@
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use crate::analysis::fits;
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge, Indx, Rotate, Tile, ROTATIONS};
+
+use super::{
+    Budget, Candidate, CellOrdering, EdgeCounts, Event, EventSink, Meter, Natural, NoEvents,
+    NoRecords, Propagator, Record, RecordHook, RowMajor, SearchState, SolveOutcome, SolveStats,
+    SymmetryBreak, ValueOrdering,
+};
+
+mod backjump;
+use backjump::{Conflicts, Culprits};
+mod discrepancy;
+pub use discrepancy::*;
+mod estimate;
+pub use estimate::*;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod restart;
+pub use restart::*;
+mod solutions;
+pub use solutions::*;
+mod split;
+pub use split::*;
+
+/// What a search did, as reported by [Backtracker::search].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// The number of placements tried.
+    pub nodes: u64,
+    /// The number of times the search ran out of candidates for a cell and took back the
+    /// placement before it.
+    pub backtracks: u64,
+    /// The number of solutions found.
+    pub solutions: u64,
+    /// True if every placement was tried, so that every solution was found.
+    ///
+    /// This is false if the search was stopped early, by the visitor or by the [Budget].
+    pub complete: bool,
+}
+
+/// A depth-first backtracking solver.
+///
+/// The solver places the clues, then repeatedly picks an empty cell with its [CellOrdering]
+/// and tries each unused tile, in each rotation that fits the border and the tiles already
+/// placed around the cell, in the order given by its [ValueOrdering].
+/// When no candidate fits, it takes back the last placement and tries the next candidate there.
+/// Rotations of a tile that look the same are only tried once, but identical tiles are each
+/// tried, so a tileset with repeated tiles gives each solution once per arrangement of them.
+///
+/// This is an exhaustive search, so it will find every solution of a small puzzle, but the
+/// Eternity 2 Puzzle itself is far out of its reach.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::solver::Backtracker;
+///
+/// let spec = toy_3x3();
+/// let outcome = Backtracker::new().solve(&spec, &[]).unwrap();
+/// assert!(spec.verify(outcome.solution().unwrap(), 0).is_ok());
+///
+/// // with one clue pinning down the orientation, the solution is the known one
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let outcome = Backtracker::new().solve(&spec, &solution[..1]).unwrap();
+/// let board = outcome.into_solution().unwrap();
+/// for clue in solution {
+///     assert_eq!(board[clue.at], Some(clue.placed_tile()));
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Backtracker<C = RowMajor, V = Natural> {
+    cell_ordering: C,
+    value_ordering: V,
+    budget: Budget,
+    forward_checking: bool,
+    symmetry_breaking: bool,
+    edge_counting: bool,
+    backjumping: bool,
+}
+
+impl Backtracker {
+    /// A solver that fills cells in row-major order and tries tiles in tileset order.
+    pub fn new() -> Self {
+        Backtracker::default()
+    }
+}
+
+impl<C, V> Backtracker<C, V> {
+    /// Use a different heuristic to choose which cell to fill next.
+    pub fn with_cell_ordering<D>(self, cell_ordering: D) -> Backtracker<D, V> {
+        Backtracker {
+            cell_ordering,
+            value_ordering: self.value_ordering,
+            budget: self.budget,
+            forward_checking: self.forward_checking,
+            symmetry_breaking: self.symmetry_breaking,
+            edge_counting: self.edge_counting,
+            backjumping: self.backjumping,
+        }
+    }
+
+    /// Use a different heuristic to choose the order in which tiles are tried.
+    pub fn with_value_ordering<W>(self, value_ordering: W) -> Backtracker<C, W> {
+        Backtracker {
+            cell_ordering: self.cell_ordering,
+            value_ordering,
+            budget: self.budget,
+            forward_checking: self.forward_checking,
+            symmetry_breaking: self.symmetry_breaking,
+            edge_counting: self.edge_counting,
+            backjumping: self.backjumping,
+        }
+    }
+
+    /// Give up after trying this many placements.
+    pub fn with_node_limit(mut self, node_limit: u64) -> Self {
+        self.budget.nodes = Some(node_limit);
+        self
+    }
+
+    /// Give up when any limit of the budget is reached.
+    ///
+    /// Nodes are placements tried, and the search backtracks each time it runs out of
+    /// candidates for a cell.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Backtracker, Budget, SolveOutcome};
+    ///
+    /// let spec = toy_4x4();
+    /// let outcome = Backtracker::new()
+    ///     .with_budget(Budget::default().with_backtracks(5))
+    ///     .solve(&spec, &[])
+    ///     .unwrap();
+    /// let SolveOutcome::BudgetExhausted { best_partial, stats } = outcome else {
+    ///     panic!("the budget is too small to solve the puzzle");
+    /// };
+    /// assert_eq!(stats.backtracks, 5);
+    /// let placed = best_partial.into_iter().filter(|(_, t)| t.is_some()).count();
+    /// assert!(placed > 0 && placed < 16);
+    /// ```
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Keep the candidates of every empty cell up to date with a [Propagator], and take back a
+    /// placement as soon as it leaves some empty cell with no candidates.
+    ///
+    /// This tries far fewer placements, at the cost of more work for each.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let all = |_: &_| ControlFlow::Continue(());
+    /// let plain = Backtracker::new().search(&spec, &[], all).unwrap();
+    /// let checked = Backtracker::new()
+    ///     .with_forward_checking()
+    ///     .search(&spec, &[], all)
+    ///     .unwrap();
+    /// assert_eq!(checked.solutions, plain.solutions);
+    /// assert!(checked.nodes < plain.nodes);
+    /// ```
+    pub fn with_forward_checking(mut self) -> Self {
+        self.forward_checking = true;
+        self
+    }
+
+    /// Find each solution in only one of its turns, by keeping a corner tile out of the other
+    /// corners, as by [SymmetryBreak].
+    ///
+    /// Only the turns that keep the clues are broken, so this can be used with any clues.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let all = |_: &_| ControlFlow::Continue(());
+    /// let every = Backtracker::new().search(&spec, &[], all).unwrap();
+    /// let broken = Backtracker::new()
+    ///     .with_symmetry_breaking()
+    ///     .search(&spec, &[], all)
+    ///     .unwrap();
+    /// assert_eq!(every.solutions, 4);
+    /// assert_eq!(broken.solutions, 1);
+    /// assert!(broken.nodes < every.nodes);
+    /// ```
+    pub fn with_symmetry_breaking(mut self) -> Self {
+        self.symmetry_breaking = true;
+        self
+    }
+
+    /// Keep count of the edge colours on the unplaced tiles and the open sides of the board
+    /// with [EdgeCounts], and take back a placement as soon as the counts of some colour can't
+    /// balance.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let all = |_: &_| ControlFlow::Continue(());
+    /// let plain = Backtracker::new().search(&spec, &[], all).unwrap();
+    /// let counted = Backtracker::new()
+    ///     .with_edge_counting()
+    ///     .search(&spec, &[], all)
+    ///     .unwrap();
+    /// assert_eq!(counted.solutions, plain.solutions);
+    /// assert!(counted.backtracks < plain.backtracks);
+    /// ```
+    pub fn with_edge_counting(mut self) -> Self {
+        self.edge_counting = true;
+        self
+    }
+
+    /// When a cell runs out of candidates, work out which earlier placements left it with none,
+    /// and go straight back to the latest of them, skipping the placements in between, which
+    /// would only run into the same dead end again.
+    ///
+    /// The placements to blame are also remembered as a nogood, and a placement that would
+    /// complete a nogood is never tried again.
+    /// Dead ends are only explained by the tiles already placed, so this does nothing with
+    /// forward checking, and after edge counting takes back a placement the search goes back
+    /// one cell at a time until the next dead end it can explain.
+    /// [Backtracker::solutions] searches without backjumping.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::bench::suite;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let all = |_: &_| ControlFlow::Continue(());
+    /// for instance in &suite()[..3] {
+    ///     let (spec, clues) = (&instance.spec, &instance.clues);
+    ///     let plain = Backtracker::new().search(spec, clues, all).unwrap();
+    ///     let jumping = Backtracker::new()
+    ///         .with_backjumping()
+    ///         .search(spec, clues, all)
+    ///         .unwrap();
+    ///     assert_eq!(jumping.solutions, plain.solutions);
+    ///     assert!(jumping.nodes <= plain.nodes);
+    /// }
+    /// ```
+    pub fn with_backjumping(mut self) -> Self {
+        self.backjumping = true;
+        self
+    }
+
+    /// Search for a solution extending the clues.
+    ///
+    /// If the budget runs out first, this gives the deepest partial placement the search
+    /// reached.
+    /// Clues that don't fit the board are reported as by [BoardSpec::validate_clues], and every
+    /// clue tile must be in the tileset.
+    pub fn solve<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        self.solve_with_hook(spec, clues, &mut NoRecords)
+    }
+
+    /// Search for a solution as for [Backtracker::solve], telling the hook each time the
+    /// search reaches a deeper placement than before.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Backtracker, Record};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut records: Vec<Record<u8>> = Vec::new();
+    /// Backtracker::new()
+    ///     .solve_with_hook(&spec, &[], &mut |record| records.push(record))
+    ///     .unwrap();
+    ///
+    /// // every placement is a record until the first dead end, and the last is the solution
+    /// assert!(records.windows(2).all(|r| r[0].placed < r[1].placed));
+    /// assert_eq!(records.last().unwrap().placed, 16);
+    /// ```
+    pub fn solve_with_hook<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        self.solve_with(spec, clues, hook, &mut NoEvents)
+    }
+
+    /// Search for a solution as for [Backtracker::solve], telling the sink of every step the
+    /// search takes.
+    ///
+    /// The events begin by placing the clues, then give each placement that is consistent
+    /// with the tiles around it, and with the candidates of the other cells if the solver
+    /// checks them, each time it is taken back, each backtrack and each new record.
+    /// The placements are not taken back when the search stops, so replaying the events ends
+    /// with the board the search stopped at: the solution, or the last partial placement.
+    /// Placements found inconsistent by forward checking are counted as nodes, but not logged.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Backtracker, Event};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut events = Vec::new();
+    /// let outcome = Backtracker::new()
+    ///     .solve_with_events(&spec, &[], &mut |event| events.push(event))
+    ///     .unwrap();
+    ///
+    /// let count = |f: fn(&Event) -> bool| events.iter().filter(|e| f(e)).count() as u64;
+    /// let placed = count(|e| matches!(e, Event::Place { .. }));
+    /// let removed = count(|e| matches!(e, Event::Remove { .. }));
+    /// assert_eq!(placed - removed, 16);
+    /// assert_eq!(count(|e| *e == Event::Backtrack), outcome.stats().backtracks);
+    /// ```
+    pub fn solve_with_events<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        events: &mut dyn EventSink,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        self.solve_with(spec, clues, &mut NoRecords, events)
+    }
+
+    /// Search for a solution, telling the hook of each record and the sink of each event.
+    fn solve_with<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+        events: &mut dyn EventSink,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        let mut found = None;
+        let run = Run {
+            leaf_depth: None,
+            cancel: None,
+            meter: self.budget.start(),
+            hook,
+            events,
+            metrics: None,
+            discrepancies: None,
+        };
+        let (stats, best_partial) = self.run(spec, clues, run, |board| {
+            found = Some(board.clone());
+            ControlFlow::Break(())
+        })?;
+        Ok(SolveOutcome::from_search(found, best_partial, stats))
+    }
+
+    /// Search for a solution as for [Backtracker::solve], measuring the run with every part
+    /// of the [SolveStats].
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let (_, plain) = Backtracker::new().solve_with_stats(&spec, &[]).unwrap();
+    /// let (_, checked) = Backtracker::new()
+    ///     .with_forward_checking()
+    ///     .solve_with_stats(&spec, &[])
+    ///     .unwrap();
+    /// assert_eq!(plain.propagations, 0);
+    /// assert_eq!(checked.propagations, checked.nodes);
+    /// assert!(checked.nodes < plain.nodes);
+    /// assert_eq!(checked.max_depth, 16);
+    /// ```
+    pub fn solve_with_stats<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<(SolveOutcome<E>, SolveStats), PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        let start = Instant::now();
+        let mut found = None;
+        let mut metrics = SolveStats::default();
+        let run = Run {
+            leaf_depth: None,
+            cancel: None,
+            meter: self.budget.start(),
+            hook: &mut NoRecords,
+            events: &mut NoEvents,
+            metrics: Some(&mut metrics),
+            discrepancies: None,
+        };
+        let (stats, best_partial) = self.run(spec, clues, run, |board| {
+            found = Some(board.clone());
+            ControlFlow::Break(())
+        })?;
+        let elapsed = start.elapsed();
+        let metrics = SolveStats {
+            nodes: stats.nodes,
+            backtracks: stats.backtracks,
+            elapsed,
+            ..metrics
+        };
+        Ok((
+            SolveOutcome::from_search(found, best_partial, stats),
+            metrics,
+        ))
+    }
+
+    /// Search for solutions extending the clues, showing each to the visitor as it is found.
+    ///
+    /// The visitor returns [ControlFlow::Break] to stop the search, or
+    /// [ControlFlow::Continue] to look for more.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::examples::toy_3x3;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// // the toy puzzle is solved uniquely, up to turning the whole board
+    /// let spec = toy_3x3();
+    /// let stats = Backtracker::new()
+    ///     .search(&spec, &[], |_| ControlFlow::Continue(()))
+    ///     .unwrap();
+    /// assert!(stats.complete);
+    /// assert_eq!(stats.solutions, 4);
+    ///
+    /// let limited = Backtracker::new()
+    ///     .with_node_limit(10)
+    ///     .search(&spec, &[], |_| ControlFlow::Continue(()))
+    ///     .unwrap();
+    /// assert!(!limited.complete);
+    /// assert_eq!(limited.nodes, 10);
+    /// ```
+    pub fn search<E, F>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        visit: F,
+    ) -> Result<SearchStats, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+        F: FnMut(&Board<E>) -> ControlFlow<()>,
+    {
+        let run = Run {
+            leaf_depth: None,
+            cancel: None,
+            meter: self.budget.start(),
+            hook: &mut NoRecords,
+            events: &mut NoEvents,
+            metrics: None,
+            discrepancies: None,
+        };
+        Ok(self.run(spec, clues, run, visit)?.0)
+    }
+
+    /// Search as for [Backtracker::search], but as controlled by `run`, and also give the
+    /// deepest partial placement reached.
+    fn run<E, F>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        run: Run<'_, E>,
+        mut visit: F,
+    ) -> Result<(SearchStats, Board<E>), PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+        F: FnMut(&Board<E>) -> ControlFlow<()>,
+    {
+        let Start {
+            mut board,
+            mut used,
+            mut propagator,
+            mut counts,
+            symmetry,
+            consistent,
+            placed,
+        } = self.start(spec, clues)?;
+        for (clue, candidate) in clues.iter().zip(placed) {
+            run.events.event(Event::Place {
+                at: clue.at,
+                candidate,
+            });
+        }
+
+        let mut stats = SearchStats::default();
+        let mut best_partial = board.clone();
+        let mut conflicts =
+            (self.backjumping && propagator.is_none()).then(|| Conflicts::new(spec));
+        let flow = if consistent {
+            let mut search = Search {
+                spec,
+                board: &mut board,
+                used: &mut used,
+                propagator: propagator.as_mut(),
+                counts: counts.as_mut(),
+                symmetry: symmetry.as_ref(),
+                stats: &mut stats,
+                depth: 0,
+                leaf_depth: run.leaf_depth,
+                cancel: run.cancel,
+                meter: run.meter,
+                deepest: 0,
+                best_partial: &mut best_partial,
+                hook: run.hook,
+                events: run.events,
+                conflicts: conflicts.as_mut(),
+                jump: None,
+                metrics: run.metrics,
+                discrepancies: run.discrepancies,
+            };
+            let flow = self.extend(&mut search, &mut visit);
+            if let Some(metrics) = search.metrics {
+                metrics.max_depth = search.deepest;
+            }
+            flow
+        } else {
+            ControlFlow::Continue(())
+        };
+        stats.complete = flow.is_continue();
+        Ok((stats, best_partial))
+    }
+
+    /// Place the clues, checking that they fit.
+    fn start<E>(&self, spec: &BoardSpec<E>, clues: &[Clue<E>]) -> Result<Start<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+    {
+        spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+
+        let mut start = Start {
+            board: spec.dimensions.new_board(),
+            used: vec![false; spec.tiles.len()],
+            propagator: self.forward_checking.then(|| Propagator::new(spec)),
+            counts: self.edge_counting.then(|| EdgeCounts::new(spec)),
+            symmetry: None,
+            consistent: true,
+            placed: Vec::with_capacity(clues.len()),
+        };
+        for clue in clues {
+            let placed = clue.placed_tile();
+            // duplicated tiles are told apart by taking the first copy not yet used
+            let (tile, rotation) = spec
+                .tiles
+                .identify_unused(&placed, &start.used)
+                .ok_or(PlacementError::UnknownTile(clue.at))?;
+            start.used[tile.index()] = true;
+            start.placed.push(Candidate { tile, rotation });
+            start.board.set(clue.at, Some(placed));
+            if let Some(propagator) = &mut start.propagator {
+                start.consistent &= propagator.place(clue.at, Candidate { tile, rotation });
+            }
+            if let Some(counts) = &mut start.counts {
+                counts.place(clue.at, Candidate { tile, rotation });
+            }
+        }
+        if let Some(counts) = &start.counts {
+            start.consistent &= counts.balanced();
+        }
+        if self.symmetry_breaking {
+            start.symmetry = SymmetryBreak::new(spec, clues);
+        }
+        if let (Some(symmetry), Some(propagator)) = (&start.symmetry, &mut start.propagator) {
+            for at in &symmetry.forbidden {
+                start.consistent &= propagator.exclude(*at, symmetry.tile);
+            }
+        }
+        Ok(start)
+    }
+
+    /// Try every candidate for the next cell, and extend the board from each.
+    fn extend<E, F>(&mut self, search: &mut Search<'_, E>, visit: &mut F) -> ControlFlow<()>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+        F: FnMut(&Board<E>) -> ControlFlow<()>,
+    {
+        let spec = search.spec;
+        if search.leaf_depth == Some(search.depth) {
+            search.jump = None;
+            return visit(search.board);
+        }
+        let state = SearchState {
+            spec,
+            board: search.board,
+            used: search.used,
+            propagator: search.propagator.as_deref(),
+        };
+        let Some(at) = self.cell_ordering.next_cell(&state) else {
+            search.stats.solutions += 1;
+            search.jump = None;
+            return visit(search.board);
+        };
+        let mut candidates = match &search.propagator {
+            Some(propagator) => propagator.candidates(at).collect(),
+            None => allowed(
+                search.symmetry,
+                at,
+                candidates(spec, search.board, search.used, at),
+            ),
+        };
+        // the placements to blame for this cell's dead end, while they are known
+        let level = search.depth;
+        let mut culprits = search
+            .conflicts
+            .as_ref()
+            .map(|conflicts| conflicts.excluded(spec, search.board, search.used, at, &candidates));
+        self.value_ordering
+            .order_candidates(&state, at, &mut candidates);
+
+        for (i, candidate) in candidates.into_iter().enumerate() {
+            if search.meter.exhausted(search.stats) {
+                return ControlFlow::Break(());
+            }
+            if search.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return ControlFlow::Break(());
+            }
+            // every candidate after the first goes against the value ordering
+            let discrepancy = i > 0 && search.discrepancies.is_some();
+            if let Some(limit) = search.discrepancies.as_deref_mut().filter(|_| discrepancy) {
+                if limit.left == 0 {
+                    limit.cut = true;
+                    culprits = None;
+                    break;
+                }
+            }
+            let nogood = search
+                .conflicts
+                .as_ref()
+                .and_then(|conflicts| conflicts.nogood(at, candidate));
+            if let Some(nogood) = nogood {
+                if let Some(culprits) = &mut culprits {
+                    culprits.blame(&nogood, level);
+                }
+                continue;
+            }
+            search.stats.nodes += 1;
+            if let Some(metrics) = &mut search.metrics {
+                metrics.expand(search.depth);
+                metrics.propagations +=
+                    search.propagator.is_some() as u64 + search.counts.is_some() as u64;
+            }
+
+            let consistent = search
+                .propagator
+                .as_mut()
+                .is_none_or(|propagator| propagator.place(at, candidate));
+            let balanced = search
+                .counts
+                .as_mut()
+                .is_none_or(|counts| counts.place(at, candidate));
+            let mut jump = None;
+            let flow = if consistent && balanced {
+                let tile = &spec.tiles[..][candidate.tile.index()];
+                let placed = tile.rotate(candidate.rotation).apply();
+                search.board.set(at, Some(placed));
+                search.used[candidate.tile.index()] = true;
+                if let Some(conflicts) = &mut search.conflicts {
+                    conflicts.place(at, candidate);
+                }
+                search.depth += 1;
+                search.events.event(Event::Place { at, candidate });
+                if search.depth > search.deepest {
+                    search.deepest = search.depth;
+                    search.best_partial.clone_from(search.board);
+                    let record = Record::new(search.board, search.stats.nodes);
+                    search.hook.record(record);
+                    search.events.event(Event::Record {
+                        nodes: search.stats.nodes,
+                    });
+                }
+                if let Some(limit) = search.discrepancies.as_deref_mut().filter(|_| discrepancy) {
+                    limit.left -= 1;
+                }
+                let flow = self.extend(search, visit);
+                if let Some(limit) = search.discrepancies.as_deref_mut().filter(|_| discrepancy) {
+                    limit.left += 1;
+                }
+                search.depth -= 1;
+                if let Some(conflicts) = &mut search.conflicts {
+                    conflicts.remove();
+                }
+                jump = search.jump.take();
+                search.used[candidate.tile.index()] = false;
+                search.board.set(at, None);
+                // a search that stops leaves the log with the board it stopped at
+                if flow.is_continue() {
+                    search.events.event(Event::Remove { at });
+                }
+                flow
+            } else {
+                ControlFlow::Continue(())
+            };
+            if let Some(propagator) = &mut search.propagator {
+                propagator.undo();
+            }
+            if let Some(counts) = &mut search.counts {
+                counts.remove(at);
+            }
+            flow?;
+            match jump {
+                // the dead end below doesn't depend on this cell, so nor will any other
+                // candidate's
+                Some(jump) if !jump.contains(level) => {
+                    search.jump = Some(jump);
+                    search.stats.backtracks += 1;
+                    search.events.event(Event::Backtrack);
+                    return ControlFlow::Continue(());
+                }
+                Some(jump) => {
+                    if let Some(culprits) = &mut culprits {
+                        culprits.blame(&jump, level);
+                    }
+                }
+                None => culprits = None,
+            }
+        }
+        if search.meter.exhausted(search.stats) {
+            return ControlFlow::Break(());
+        }
+        if let (Some(conflicts), Some(culprits)) = (&mut search.conflicts, &culprits) {
+            conflicts.record(culprits);
+        }
+        search.jump = culprits;
+        search.stats.backtracks += 1;
+        search.events.event(Event::Backtrack);
+        ControlFlow::Continue(())
+    }
+}
+
+/// A board with the clues placed, ready to search from.
+struct Start<E> {
+    board: Board<E>,
+    used: Vec<bool>,
+    propagator: Option<Propagator<E>>,
+    counts: Option<EdgeCounts>,
+    /// The turns of the board to break, if the solver breaks them.
+    symmetry: Option<SymmetryBreak>,
+    /// False if forward checking found that the clues leave some cell with no candidates, or
+    /// edge counting that they leave some colour unbalanced.
+    consistent: bool,
+    /// The tile and rotation of each clue, in the order of the clues.
+    placed: Vec<Candidate>,
+}
+
+/// The state of a search in progress.
+struct Search<'a, E> {
+    spec: &'a BoardSpec<E>,
+    board: &'a mut Board<E>,
+    used: &'a mut [bool],
+    propagator: Option<&'a mut Propagator<E>>,
+    counts: Option<&'a mut EdgeCounts>,
+    symmetry: Option<&'a SymmetryBreak>,
+    stats: &'a mut SearchStats,
+    /// The number of placements made beyond the clues.
+    depth: usize,
+    /// The depth at which boards are shown to the visitor as if complete.
+    leaf_depth: Option<usize>,
+    cancel: Option<&'a AtomicBool>,
+    meter: Meter,
+    /// The greatest depth reached so far.
+    deepest: usize,
+    /// The board when the search was first at its greatest depth.
+    best_partial: &'a mut Board<E>,
+    hook: &'a mut dyn RecordHook<E>,
+    events: &'a mut dyn EventSink,
+    /// The conflict analysis, if the search backjumps.
+    conflicts: Option<&'a mut Conflicts>,
+    /// The placements to blame for the dead end the last extension ran into, or none if it
+    /// found a solution or its dead end couldn't be explained.
+    jump: Option<Culprits>,
+    metrics: Option<&'a mut SolveStats>,
+    discrepancies: Option<&'a mut DiscrepancyLimit>,
+}
+
+/// How to run a search.
+struct Run<'a, E> {
+    /// The depth at which boards are shown to the visitor as if complete.
+    leaf_depth: Option<usize>,
+    /// Stop when this is set.
+    cancel: Option<&'a AtomicBool>,
+    meter: Meter,
+    /// What to tell of each deeper placement.
+    hook: &'a mut dyn RecordHook<E>,
+    /// What to tell of each step of the search.
+    events: &'a mut dyn EventSink,
+    /// What to measure the search in, beyond its stats.
+    metrics: Option<&'a mut SolveStats>,
+    /// How far the search may go against the value ordering, if it is limited.
+    discrepancies: Option<&'a mut DiscrepancyLimit>,
+}
+
+/// The unused tiles that fit an empty cell, in tileset order, in each rotation that fits.
+///
+/// Rotations of a tile that look the same are only given once.
+pub(crate) fn candidates<E: Copy + Edge + PartialEq>(
+    spec: &BoardSpec<E>,
+    board: &Board<E>,
+    used: &[bool],
+    at: Indx,
+) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+    for tile in spec.tiles.ids().filter(|id| !used[id.index()]) {
+        let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+        for rotation in ROTATIONS {
+            let placed = spec.tiles[..][tile.index()].rotate(rotation).apply();
+            if seen.contains(&placed) {
+                continue;
+            }
+            seen.push(placed);
+            if fits(board, at, &placed) {
+                candidates.push(Candidate { tile, rotation });
+            }
+        }
+    }
+    candidates
+}
+
+/// The candidates the symmetry break allows in a cell, for searches without a propagator,
+/// which has them removed already.
+fn allowed(
+    symmetry: Option<&SymmetryBreak>,
+    at: Indx,
+    mut candidates: Vec<Candidate>,
+) -> Vec<Candidate> {
+    if let Some(symmetry) = symmetry {
+        candidates.retain(|c| symmetry.allows(c.tile, at));
+    }
+    candidates
+}
+
+/// The number of tiles on a board.
+fn placed<E>(board: &Board<E>) -> usize {
+    board
+        .squares
+        .iter()
+        .filter(|square| square.is_some())
+        .count()
+}
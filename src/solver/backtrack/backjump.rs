@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use crate::model::{Board, BoardSpec, Edge, Indx, Rotate, Tile, ROTATIONS, SIDES};
+
+use super::super::Candidate;
+
+/// The most nogoods a search records, beyond which it stops recording them.
+const MAX_NOGOODS: usize = 100_000;
+
+/// The most placements in a nogood worth recording, since longer ones rarely match again.
+const MAX_NOGOOD_LEN: usize = 8;
+
+/// A set of levels, the placements beyond the clues numbered from 0 in the order they were
+/// made, that together explain a dead end.
+///
+/// A search places at most one tile of a tileset at each level, so levels fit in 256 bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(super) struct Culprits([u64; 4]);
+
+impl Culprits {
+    fn insert(&mut self, level: usize) {
+        self.0[level / 64] |= 1 << (level % 64);
+    }
+
+    /// True if the placement at this level is one of the culprits.
+    pub(super) fn contains(&self, level: usize) -> bool {
+        self.0[level / 64] & (1 << (level % 64)) != 0
+    }
+
+    /// Add the culprits of another dead end, except the placement at one level.
+    pub(super) fn blame(&mut self, other: &Culprits, except: usize) {
+        for (word, other) in self.0.iter_mut().zip(other.0) {
+            *word |= other;
+        }
+        if except < 256 {
+            self.0[except / 64] &= !(1 << (except % 64));
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn levels(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..256).filter(|level| self.contains(*level))
+    }
+}
+
+/// Why a placement doesn't fit a cell.
+enum Misfit {
+    Fits,
+    /// It doesn't fit the border or a clue.
+    Static,
+    /// It doesn't fit the placement at this level.
+    Level(usize),
+}
+
+/// Conflict analysis for backjumping: the level of each placement, so that dead ends can be
+/// blamed on the placements that caused them, and the nogoods found so far.
+///
+/// A nogood is a set of placements that can't all be part of a solution.
+/// Each dead end the search can explain gives one, the placements its culprits made.
+pub(super) struct Conflicts {
+    columns: usize,
+    /// The placements beyond the clues, by level, with the cell of each in row-major order.
+    path: Vec<(usize, Candidate)>,
+    /// The level of the placement in each cell, with none for empty cells and clues.
+    cells: Vec<Option<usize>>,
+    /// The level each tile was placed at, with none for unplaced tiles and clues.
+    tiles: Vec<Option<usize>>,
+    nogoods: Vec<Vec<(usize, Candidate)>>,
+    /// The nogoods holding each placement, by cell, tile index and rotation.
+    index: HashMap<(usize, usize, usize), Vec<usize>>,
+}
+
+impl Conflicts {
+    pub(super) fn new<E>(spec: &BoardSpec<E>) -> Self {
+        let shape = spec.dimensions;
+        Conflicts {
+            columns: shape.columns,
+            path: Vec::new(),
+            cells: vec![None; shape.columns * shape.rows],
+            tiles: vec![None; spec.tiles.len()],
+            nogoods: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn cell(&self, at: Indx) -> usize {
+        at.col + at.row * self.columns
+    }
+
+    /// Make the placement at the next level.
+    pub(super) fn place(&mut self, at: Indx, candidate: Candidate) {
+        let (cell, level) = (self.cell(at), self.path.len());
+        self.path.push((cell, candidate));
+        self.cells[cell] = Some(level);
+        self.tiles[candidate.tile.index()] = Some(level);
+    }
+
+    /// Take back the placement at the last level.
+    pub(super) fn remove(&mut self) {
+        if let Some((cell, candidate)) = self.path.pop() {
+            self.cells[cell] = None;
+            self.tiles[candidate.tile.index()] = None;
+        }
+    }
+
+    /// The culprits for every placement in a cell that isn't among its candidates: a neighbour
+    /// it doesn't match or, if it would fit, the placement of its tile.
+    ///
+    /// Placements ruled out by the border, the clues or symmetry breaking, which no placement
+    /// beyond the clues is to blame for, have no culprits.
+    pub(super) fn excluded<E: Copy + Edge + PartialEq>(
+        &self,
+        spec: &BoardSpec<E>,
+        board: &Board<E>,
+        used: &[bool],
+        at: Indx,
+        candidates: &[Candidate],
+    ) -> Culprits {
+        let mut culprits = Culprits::default();
+        for id in spec.tiles.ids() {
+            let mut blamed = Culprits::default();
+            let mut fits = false;
+            let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+            for rotation in ROTATIONS {
+                let placed = spec.tiles[..][id.index()].rotate(rotation).apply();
+                if seen.contains(&placed) {
+                    continue;
+                }
+                seen.push(placed);
+                if candidates.contains(&Candidate { tile: id, rotation }) {
+                    continue;
+                }
+                match self.misfit(board, at, &placed) {
+                    Misfit::Fits => fits = true,
+                    Misfit::Static => {}
+                    Misfit::Level(level) => blamed.insert(level),
+                }
+            }
+            match (used[id.index()], self.tiles[id.index()]) {
+                // a clue
+                (true, None) => {}
+                // it is only the tile being placed already that keeps it out
+                (true, Some(level)) if fits => culprits.insert(level),
+                _ => culprits.blame(&blamed, usize::MAX),
+            }
+        }
+        culprits
+    }
+
+    /// Why a tile as it would lie doesn't fit a cell.
+    fn misfit<E: Copy + Edge + PartialEq>(
+        &self,
+        board: &Board<E>,
+        at: Indx,
+        placed: &Tile<E>,
+    ) -> Misfit {
+        let shape = board.shape();
+        let mut misfit = Misfit::Fits;
+        for side in SIDES {
+            let edge = placed[side];
+            let level = match shape.neighbour(at, side) {
+                None if edge.is_border() => continue,
+                None => return Misfit::Static,
+                Some(_) if edge.is_border() => return Misfit::Static,
+                Some(next) => match board[next] {
+                    Some(tile) if tile[side.flip()] != edge => self.cells[self.cell(next)],
+                    _ => continue,
+                },
+            };
+            // a clue it doesn't match, or else the earliest placement
+            misfit = match (level, misfit) {
+                (None, _) => return Misfit::Static,
+                (Some(level), Misfit::Level(earlier)) => Misfit::Level(level.min(earlier)),
+                (Some(level), _) => Misfit::Level(level),
+            };
+        }
+        misfit
+    }
+
+    /// The culprits of a recorded nogood that this placement would complete, if any.
+    pub(super) fn nogood(&self, at: Indx, candidate: Candidate) -> Option<Culprits> {
+        let cell = self.cell(at);
+        let key = (cell, candidate.tile.index(), candidate.rotation as usize);
+        self.index.get(&key)?.iter().find_map(|n| {
+            let mut culprits = Culprits::default();
+            for (c, k) in &self.nogoods[*n] {
+                if *c == cell {
+                    continue;
+                }
+                let level = self.cells[*c].filter(|level| self.path[*level].1 == *k)?;
+                culprits.insert(level);
+            }
+            Some(culprits)
+        })
+    }
+
+    /// Record the placements of the culprits of a dead end as a nogood, if it is short enough
+    /// and there is room for it.
+    pub(super) fn record(&mut self, culprits: &Culprits) {
+        let len = culprits.len();
+        if len == 0 || len > MAX_NOGOOD_LEN || self.nogoods.len() >= MAX_NOGOODS {
+            return;
+        }
+        let nogood: Vec<(usize, Candidate)> =
+            culprits.levels().map(|level| self.path[level]).collect();
+        for (cell, candidate) in &nogood {
+            let key = (*cell, candidate.tile.index(), candidate.rotation as usize);
+            self.index.entry(key).or_default().push(self.nogoods.len());
+        }
+        self.nogoods.push(nogood);
+    }
+}
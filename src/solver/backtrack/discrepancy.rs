@@ -0,0 +1,205 @@
+use std::ops::ControlFlow;
+use std::time::Instant;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge};
+
+use super::super::{
+    Budget, CellOrdering, Natural, NoEvents, NoRecords, Record, RecordHook, RowMajor, SolveOutcome,
+    Solver, ValueOrdering,
+};
+use super::{placed, Backtracker, Run, SearchStats};
+
+/// A [Backtracker] that searches by limited discrepancy search: first only the placements its
+/// [ValueOrdering] likes best, then those going against it once, then twice, and so on.
+///
+/// A discrepancy is trying any candidate for a cell other than the first.
+/// Each run searches every board with at most so many discrepancies, so when the value ordering
+/// is mostly right a solution turns up in an early run, long before a depth-first search would
+/// get back to the early cells where the ordering went wrong.
+/// Each run searches the boards of the runs before it again, which costs little, since there are
+/// far more boards with more discrepancies.
+///
+/// The solver's own budget limits all the runs together.
+/// A run that never turns down a candidate for going against the ordering too often searches
+/// every board, so if it finds no solution there is none.
+/// If the most discrepancies allowed are used up first, the outcome is
+/// [SolveOutcome::BudgetExhausted] with the deepest placement of any run.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Backtracker, Discrepancies, SolveOutcome};
+///
+/// let spec = toy_4x4();
+/// let mut lds = Discrepancies::new(Backtracker::new());
+/// let outcome = lds.solve(&spec, &[]).unwrap();
+/// assert!(spec.verify(outcome.solution().unwrap(), 0).is_ok());
+///
+/// // never going against the ordering isn't enough to solve it
+/// let mut greedy = Discrepancies::new(Backtracker::new()).with_max_discrepancies(0);
+/// let outcome = greedy.solve(&spec, &[]).unwrap();
+/// assert!(matches!(outcome, SolveOutcome::BudgetExhausted { .. }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Discrepancies<C = RowMajor, V = Natural> {
+    solver: Backtracker<C, V>,
+    max_discrepancies: usize,
+}
+
+/// How far a search may still go against the value ordering.
+pub(super) struct DiscrepancyLimit {
+    /// The discrepancies left.
+    pub(super) left: usize,
+    /// True once a candidate was turned down for going against the ordering too often.
+    pub(super) cut: bool,
+}
+
+impl<C, V> Discrepancies<C, V> {
+    /// Search with the solver, allowing one more discrepancy each run until every board has
+    /// been searched.
+    pub fn new(solver: Backtracker<C, V>) -> Self {
+        Discrepancies {
+            solver,
+            max_discrepancies: usize::MAX,
+        }
+    }
+
+    /// Stop after the run allowing this many discrepancies.
+    pub fn with_max_discrepancies(mut self, max_discrepancies: usize) -> Self {
+        self.max_discrepancies = max_discrepancies;
+        self
+    }
+
+    /// Search for a solution extending the clues, as for [Backtracker::solve], allowing more
+    /// discrepancies each run.
+    pub fn solve<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        self.solve_with_hook(spec, clues, &mut NoRecords)
+    }
+
+    /// Search for a solution as for [Discrepancies::solve], telling the hook each time a run
+    /// reaches a deeper placement than any run before, with the nodes of all the runs so far.
+    pub fn solve_with_hook<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        let start = Instant::now();
+        let total = self.solver.budget.clone();
+        let mut stats = SearchStats::default();
+        let mut best_partial: Option<Board<E>> = None;
+        let mut deepest = 0;
+
+        for discrepancies in 0..=self.max_discrepancies {
+            let left = |limit: Option<u64>, used: u64| limit.map(|l| l.saturating_sub(used));
+            let budget = Budget {
+                nodes: left(total.nodes, stats.nodes),
+                backtracks: left(total.backtracks, stats.backtracks),
+                duration: total.duration.map(|d| d.saturating_sub(start.elapsed())),
+                cancel: total.cancel.clone(),
+            };
+            if budget.nodes == Some(0)
+                || budget.backtracks == Some(0)
+                || budget.duration.is_some_and(|d| d.is_zero())
+                || budget.cancelled()
+            {
+                break;
+            }
+
+            let mut found = None;
+            let mut limit = DiscrepancyLimit {
+                left: discrepancies,
+                cut: false,
+            };
+            let offset = stats.nodes;
+            let mut records = |record: Record<E>| {
+                if record.placed > deepest {
+                    deepest = record.placed;
+                    hook.record(Record {
+                        nodes: offset + record.nodes,
+                        ..record
+                    });
+                }
+            };
+            let run = Run {
+                leaf_depth: None,
+                cancel: None,
+                meter: budget.start(),
+                hook: &mut records,
+                events: &mut NoEvents,
+                metrics: None,
+                discrepancies: Some(&mut limit),
+            };
+            let (run_stats, partial) = self.solver.run(spec, clues, run, |board| {
+                found = Some(board.clone());
+                ControlFlow::Break(())
+            })?;
+
+            // a run cut short by the limit hasn't searched every board
+            let complete = run_stats.complete && !limit.cut;
+            stats = SearchStats {
+                nodes: stats.nodes + run_stats.nodes,
+                backtracks: stats.backtracks + run_stats.backtracks,
+                solutions: stats.solutions + run_stats.solutions,
+                complete,
+            };
+            if let Some(board) = found {
+                return Ok(SolveOutcome::Solved { board, stats });
+            }
+            if complete {
+                return Ok(SolveOutcome::NoSolution { stats });
+            }
+            if best_partial
+                .as_ref()
+                .is_none_or(|best| placed(&partial) > placed(best))
+            {
+                best_partial = Some(partial);
+            }
+            if !run_stats.complete {
+                break;
+            }
+        }
+
+        let best_partial = match best_partial {
+            Some(board) => board,
+            None => self.solver.start(spec, clues)?.board,
+        };
+        Ok(SolveOutcome::BudgetExhausted {
+            best_partial,
+            stats: SearchStats {
+                complete: false,
+                ..stats
+            },
+        })
+    }
+}
+
+impl<E, C, V> Solver<E> for Discrepancies<C, V>
+where
+    E: Copy + Edge + PartialEq,
+    C: CellOrdering<E>,
+    V: ValueOrdering<E>,
+{
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        Discrepancies::solve_with_hook(self, spec, clues, hook)
+    }
+}
@@ -0,0 +1,105 @@
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rayon::prelude::*;
+
+use crate::error::PlacementError;
+use crate::model::{BoardSpec, Clue, Edge};
+
+use super::super::{CellOrdering, NoEvents, NoRecords, SolveOutcome, ValueOrdering};
+use super::{placed, Backtracker, Run, SearchStats};
+
+impl<C, V> Backtracker<C, V> {
+    /// Search for a solution extending the clues on several threads.
+    ///
+    /// The search is split at `split_depth` placements beyond the clues: every way of making
+    /// those placements is found first, and then each is searched from on the rayon thread
+    /// pool by its own copy of the solver, until one finds a solution or all are exhausted.
+    /// When one copy finds a solution, the others stop at their next placement.
+    /// The search runs on the current rayon pool, so use
+    /// [ThreadPool::install](rayon::ThreadPool::install) to choose the number of threads.
+    ///
+    /// A deeper split gives more, smaller pieces of work to share between threads, but takes
+    /// longer to find them all.
+    /// The node and backtrack limits of the budget apply to finding the pieces and to each piece
+    /// separately, but the time limit covers the whole search, and the statistics are summed
+    /// over every piece.
+    /// Which solution is found, if there are several, depends on how the threads are scheduled.
+    /// The pieces are searched apart, so there are no [Record](crate::solver::Record)s to report.
+    ///
+    /// This needs the `parallel` feature.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let outcome = Backtracker::new()
+    ///     .with_forward_checking()
+    ///     .solve_parallel(&spec, &[], 2)
+    ///     .unwrap();
+    /// assert!(spec.verify(outcome.solution().unwrap(), 0).is_ok());
+    /// ```
+    pub fn solve_parallel<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        split_depth: usize,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq + Send + Sync,
+        C: CellOrdering<E> + Clone + Send + Sync,
+        V: ValueOrdering<E> + Clone + Send + Sync,
+    {
+        let meter = self.budget.start();
+        let (split, best_partial) = self.split_with(spec, clues, split_depth, meter.clone())?;
+
+        let cancel = AtomicBool::new(false);
+        let solver = &*self;
+        let (solution, best_partial, stats) = split
+            .units
+            .into_par_iter()
+            .map(|prefix| {
+                let mut worker = solver.clone();
+                let mut solution = None;
+                let run = Run {
+                    leaf_depth: None,
+                    cancel: Some(&cancel),
+                    meter: meter.clone(),
+                    hook: &mut NoRecords,
+                    events: &mut NoEvents,
+                    metrics: None,
+                    discrepancies: None,
+                };
+                let run = worker.run(spec, &prefix, run, |board| {
+                    solution = Some(board.clone());
+                    ControlFlow::Break(())
+                });
+                // a piece that can't be searched leaves the search incomplete
+                let (stats, partial) =
+                    run.unwrap_or_else(|_| (SearchStats::default(), spec.dimensions.new_board()));
+                if solution.is_some() {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+                (solution, partial, stats)
+            })
+            .reduce(
+                || (None, best_partial.clone(), split.stats),
+                |(found, deepest, total), (other, partial, stats)| {
+                    let deepest = if placed(&partial) > placed(&deepest) {
+                        partial
+                    } else {
+                        deepest
+                    };
+                    let total = SearchStats {
+                        nodes: total.nodes + stats.nodes,
+                        backtracks: total.backtracks + stats.backtracks,
+                        solutions: total.solutions + stats.solutions,
+                        complete: total.complete && stats.complete,
+                    };
+                    (found.or(other), deepest, total)
+                },
+            );
+        Ok(SolveOutcome::from_search(solution, best_partial, stats))
+    }
+}
@@ -0,0 +1,197 @@
+use std::time::Instant;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge};
+
+use super::super::{
+    Budget, CellOrdering, Natural, NoRecords, Record, RecordHook, RowMajor, Shuffled, SolveOutcome,
+    Solver, ValueOrdering,
+};
+use super::{placed, Backtracker, SearchStats};
+
+/// A [Backtracker] that starts again with a new random value ordering each time a run tries too
+/// many placements.
+///
+/// The time a backtracking search takes varies enormously with the order it tries tiles in: a
+/// few early choices can bury it in a subtree with no solutions.
+/// Restarting after a fixed number of nodes escapes such subtrees, at the cost of searching
+/// parts of the tree again.
+/// Each run sorts its candidates with the solver's [ValueOrdering], but breaks ties at random, as
+/// by [Shuffled], seeded from the next of the given seeds, so the whole sequence of runs can be
+/// repeated.
+///
+/// The solver's own budget limits all the runs together.
+/// A run that finishes without finding a solution proves there is none, whatever order it tried
+/// tiles in.
+/// If every seed is used up first, the outcome is [SolveOutcome::BudgetExhausted] with the
+/// deepest placement of any run.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Backtracker, Restarts, SolveOutcome};
+///
+/// let spec = toy_4x4();
+/// let mut restarts = Restarts::new(Backtracker::new(), [1, 2, 3, 4, 5], 50);
+/// let outcome = restarts.solve(&spec, &[]).unwrap();
+/// assert!(spec.verify(outcome.solution().unwrap(), 0).is_ok());
+///
+/// // the same seeds repeat the same runs
+/// let again = restarts.solve(&spec, &[]).unwrap();
+/// assert_eq!(again.stats(), outcome.stats());
+///
+/// // runs too short to place every tile use up all the seeds
+/// let mut short = Restarts::new(Backtracker::new(), [1, 2, 3, 4, 5], 10);
+/// let outcome = short.solve(&spec, &[]).unwrap();
+/// assert!(matches!(outcome, SolveOutcome::BudgetExhausted { .. }));
+/// assert_eq!(outcome.stats().nodes, 50);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Restarts<C = RowMajor, V = Natural> {
+    solver: Backtracker<C, V>,
+    seeds: Vec<u64>,
+    run_nodes: u64,
+}
+
+impl<C, V> Restarts<C, V> {
+    /// Restart the solver after every `run_nodes` nodes, with one run for each seed.
+    pub fn new<S>(solver: Backtracker<C, V>, seeds: S, run_nodes: u64) -> Self
+    where
+        S: IntoIterator<Item = u64>,
+    {
+        Restarts {
+            solver,
+            seeds: seeds.into_iter().collect(),
+            run_nodes,
+        }
+    }
+
+    /// The seeds of the runs, in the order they are made.
+    pub fn seeds(&self) -> &[u64] {
+        &self.seeds
+    }
+
+    /// Search for a solution extending the clues, as for [Backtracker::solve], restarting as
+    /// needed.
+    pub fn solve<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E> + Clone,
+        V: ValueOrdering<E> + Clone,
+    {
+        self.solve_with_hook(spec, clues, &mut NoRecords)
+    }
+
+    /// Search for a solution as for [Restarts::solve], telling the hook each time a run
+    /// reaches a deeper placement than any run before, with the nodes of all the runs so far.
+    pub fn solve_with_hook<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E> + Clone,
+        V: ValueOrdering<E> + Clone,
+    {
+        let start = Instant::now();
+        let total = self.solver.budget.clone();
+        let mut stats = SearchStats::default();
+        let mut best_partial: Option<Board<E>> = None;
+        let mut deepest = 0;
+
+        for &seed in &self.seeds {
+            let left = |limit: Option<u64>, used: u64| limit.map(|l| l.saturating_sub(used));
+            let budget = Budget {
+                nodes: Some(
+                    left(total.nodes, stats.nodes)
+                        .map_or(self.run_nodes, |n| n.min(self.run_nodes)),
+                ),
+                backtracks: left(total.backtracks, stats.backtracks),
+                duration: total.duration.map(|d| d.saturating_sub(start.elapsed())),
+                cancel: total.cancel.clone(),
+            };
+            if budget.nodes == Some(0)
+                || budget.backtracks == Some(0)
+                || budget.duration.is_some_and(|d| d.is_zero())
+                || budget.cancelled()
+            {
+                break;
+            }
+
+            let mut run = self
+                .solver
+                .clone()
+                .with_value_ordering(Shuffled::new(self.solver.value_ordering.clone(), seed))
+                .with_budget(budget);
+            let offset = stats.nodes;
+            let outcome = run.solve_with_hook(spec, clues, &mut |record: Record<E>| {
+                if record.placed > deepest {
+                    deepest = record.placed;
+                    hook.record(Record {
+                        nodes: offset + record.nodes,
+                        ..record
+                    });
+                }
+            })?;
+
+            let run_stats = *outcome.stats();
+            stats = SearchStats {
+                nodes: stats.nodes + run_stats.nodes,
+                backtracks: stats.backtracks + run_stats.backtracks,
+                solutions: stats.solutions + run_stats.solutions,
+                complete: run_stats.complete,
+            };
+            match outcome {
+                SolveOutcome::Solved { board, .. } => {
+                    return Ok(SolveOutcome::Solved { board, stats })
+                }
+                SolveOutcome::NoSolution { .. } => return Ok(SolveOutcome::NoSolution { stats }),
+                SolveOutcome::BudgetExhausted {
+                    best_partial: partial,
+                    ..
+                } => {
+                    let deeper = match &best_partial {
+                        Some(best) => placed(&partial) > placed(best),
+                        None => true,
+                    };
+                    if deeper {
+                        best_partial = Some(partial);
+                    }
+                }
+            }
+        }
+
+        let best_partial = match best_partial {
+            Some(board) => board,
+            None => self.solver.start(spec, clues)?.board,
+        };
+        Ok(SolveOutcome::BudgetExhausted {
+            best_partial,
+            stats: SearchStats {
+                complete: false,
+                ..stats
+            },
+        })
+    }
+}
+
+impl<E, C, V> Solver<E> for Restarts<C, V>
+where
+    E: Copy + Edge + PartialEq,
+    C: CellOrdering<E> + Clone,
+    V: ValueOrdering<E> + Clone,
+{
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        Restarts::solve_with_hook(self, spec, clues, hook)
+    }
+}
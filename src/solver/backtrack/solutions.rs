@@ -0,0 +1,209 @@
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge, Indx, Rotate};
+
+use super::super::{
+    Candidate, CellOrdering, EdgeCounts, Meter, Propagator, SearchState, SymmetryBreak,
+    ValueOrdering,
+};
+use super::{allowed, candidates, Backtracker, SearchStats, Start};
+
+impl<C, V> Backtracker<C, V> {
+    /// The solutions extending the clues, found one at a time as the iterator is advanced.
+    ///
+    /// The search is paused between solutions, rather than run to the end up front, so taking
+    /// the first few solutions only does the work of finding those.
+    /// The iterator ends when the search is complete or the budget runs out, which
+    /// [Solutions::stats] tells apart.
+    /// Clues are checked as for [Backtracker::solve].
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let mut solver = Backtracker::new();
+    /// let mut solutions = solver.solutions(&spec, &[]).unwrap();
+    /// let first = solutions.next().unwrap();
+    /// assert!(spec.verify(&first, 0).is_ok());
+    /// assert!(!solutions.stats().complete);
+    ///
+    /// // the rest of the turns of the board
+    /// assert_eq!(solutions.by_ref().count(), 3);
+    /// assert!(solutions.stats().complete);
+    /// assert_eq!(solutions.stats().solutions, 4);
+    /// ```
+    pub fn solutions<'a, E>(
+        &'a mut self,
+        spec: &'a BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<Solutions<'a, E, C, V>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+    {
+        let Start {
+            board,
+            used,
+            propagator,
+            counts,
+            symmetry,
+            consistent,
+            ..
+        } = self.start(spec, clues)?;
+        let meter = self.budget.start();
+        Ok(Solutions {
+            solver: self,
+            spec,
+            board,
+            used,
+            propagator,
+            counts,
+            symmetry,
+            meter,
+            stats: SearchStats {
+                complete: !consistent,
+                ..SearchStats::default()
+            },
+            stack: Vec::new(),
+            descend: consistent,
+            done: !consistent,
+        })
+    }
+}
+
+/// The solutions found by a [Backtracker], as given by [Backtracker::solutions].
+///
+/// This is the same search as [Backtracker::search], with the recursion unrolled into a stack of
+/// cells so that it can stop after each solution and carry on where it left off.
+pub struct Solutions<'a, E, C, V> {
+    solver: &'a mut Backtracker<C, V>,
+    spec: &'a BoardSpec<E>,
+    board: Board<E>,
+    used: Vec<bool>,
+    propagator: Option<Propagator<E>>,
+    counts: Option<EdgeCounts>,
+    symmetry: Option<SymmetryBreak>,
+    meter: Meter,
+    stats: SearchStats,
+    /// The cells being filled, the latest last.
+    stack: Vec<Frame>,
+    /// True if the board was just extended, so the next cell is still to be chosen.
+    descend: bool,
+    /// True once the search has finished or been stopped.
+    done: bool,
+}
+
+/// A cell being filled.
+struct Frame {
+    at: Indx,
+    /// The candidates for the cell, in the order they are tried.
+    candidates: Vec<Candidate>,
+    /// The number of candidates tried so far.
+    next: usize,
+    /// The candidate being tried, and whether it was placed on the board, rather than turned
+    /// down by forward checking or edge counting.
+    trying: Option<(Candidate, bool)>,
+}
+
+impl<E, C, V> Solutions<'_, E, C, V> {
+    /// What the search has done so far.
+    ///
+    /// The stats are only [complete](SearchStats::complete) once the iterator has ended
+    /// because every placement was tried.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+}
+
+impl<E, C, V> Iterator for Solutions<'_, E, C, V>
+where
+    E: Copy + Edge + PartialEq,
+    C: CellOrdering<E>,
+    V: ValueOrdering<E>,
+{
+    type Item = Board<E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let spec = self.spec;
+        while !self.done {
+            if self.descend {
+                self.descend = false;
+                let state = SearchState {
+                    spec,
+                    board: &self.board,
+                    used: &self.used,
+                    propagator: self.propagator.as_ref(),
+                };
+                let Some(at) = self.solver.cell_ordering.next_cell(&state) else {
+                    self.stats.solutions += 1;
+                    return Some(self.board.clone());
+                };
+                let mut candidates = match &self.propagator {
+                    Some(propagator) => propagator.candidates(at).collect(),
+                    None => allowed(
+                        self.symmetry.as_ref(),
+                        at,
+                        candidates(spec, &self.board, &self.used, at),
+                    ),
+                };
+                self.solver
+                    .value_ordering
+                    .order_candidates(&state, at, &mut candidates);
+                self.stack.push(Frame {
+                    at,
+                    candidates,
+                    next: 0,
+                    trying: None,
+                });
+            }
+
+            let Some(frame) = self.stack.last_mut() else {
+                self.done = true;
+                self.stats.complete = true;
+                break;
+            };
+            let at = frame.at;
+            if let Some((candidate, placed)) = frame.trying.take() {
+                if placed {
+                    self.used[candidate.tile.index()] = false;
+                    self.board.set(at, None);
+                }
+                if let Some(propagator) = &mut self.propagator {
+                    propagator.undo();
+                }
+                if let Some(counts) = &mut self.counts {
+                    counts.remove(at);
+                }
+            }
+            if self.meter.exhausted(&self.stats) {
+                self.done = true;
+                break;
+            }
+            let Some(&candidate) = frame.candidates.get(frame.next) else {
+                self.stats.backtracks += 1;
+                self.stack.pop();
+                continue;
+            };
+            frame.next += 1;
+            self.stats.nodes += 1;
+
+            let consistent = self
+                .propagator
+                .as_mut()
+                .is_none_or(|propagator| propagator.place(at, candidate));
+            let balanced = self
+                .counts
+                .as_mut()
+                .is_none_or(|counts| counts.place(at, candidate));
+            let placed = consistent && balanced;
+            if placed {
+                let tile = &spec.tiles[..][candidate.tile.index()];
+                let turned = tile.rotate(candidate.rotation).apply();
+                self.board.set(at, Some(turned));
+                self.used[candidate.tile.index()] = true;
+                self.descend = true;
+            }
+            frame.trying = Some((candidate, placed));
+        }
+        None
+    }
+}
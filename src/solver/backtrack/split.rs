@@ -0,0 +1,129 @@
+use std::ops::ControlFlow;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge, Indx};
+
+use super::super::{CellOrdering, Meter, NoEvents, NoRecords, ValueOrdering};
+use super::{Backtracker, Run, SearchStats};
+
+/// A search split into pieces that can be searched apart, as made by [Backtracker::split].
+#[derive(Clone, Debug)]
+pub struct Split<E> {
+    /// The pieces, each as clues for every tile on the board when it begins: the clues split,
+    /// and the placements beyond them.
+    pub units: Vec<Vec<Clue<E>>>,
+    /// What the search for the pieces did.
+    ///
+    /// Solutions are left to be counted when the pieces are searched.
+    pub stats: SearchStats,
+}
+
+impl<C, V> Backtracker<C, V> {
+    /// Split the search for solutions extending the clues into pieces, at `depth` placements
+    /// beyond the clues.
+    ///
+    /// Every consistent way of making those placements is found, in the order the search makes
+    /// them, and each is given as clues, so it can be written out with
+    /// [BoardSpec::write_clues] and searched by any solver, anywhere, independently of the
+    /// others.
+    /// Searching every piece fully finds every solution the whole search would.
+    /// With the default row-major cell ordering and a depth of the board's width, the pieces are
+    /// the fillings of the first row.
+    /// A piece may already fill the board, if the clues leave fewer than `depth` cells.
+    ///
+    /// If the budget runs out before every piece is found, [SearchStats::complete] is false and
+    /// the pieces don't cover the whole search.
+    /// Clues that don't fit are reported as by [Backtracker::solve].
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::Backtracker;
+    ///
+    /// let spec = toy_4x4();
+    /// let split = Backtracker::new().split(&spec, &[], 4).unwrap();
+    /// assert!(split.stats.complete);
+    ///
+    /// let mut solutions = 0;
+    /// for unit in &split.units {
+    ///     let stats = Backtracker::new()
+    ///         .search(&spec, unit, |_| ControlFlow::Continue(()))
+    ///         .unwrap();
+    ///     solutions += stats.solutions;
+    /// }
+    /// assert_eq!(solutions, 4);
+    /// ```
+    pub fn split<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        depth: usize,
+    ) -> Result<Split<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        let meter = self.budget.start();
+        Ok(self.split_with(spec, clues, depth, meter)?.0)
+    }
+
+    /// Split the search as for [Backtracker::split], spending the meter, and also give the
+    /// deepest partial placement reached.
+    pub(super) fn split_with<E>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        depth: usize,
+        meter: Meter,
+    ) -> Result<(Split<E>, Board<E>), PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+    {
+        let mut units = Vec::new();
+        let run = Run {
+            leaf_depth: Some(depth),
+            cancel: None,
+            meter,
+            hook: &mut NoRecords,
+            events: &mut NoEvents,
+            metrics: None,
+            discrepancies: None,
+        };
+        let (stats, best_partial) = self.run(spec, clues, run, |board| {
+            units.push(prefix_clues(spec, board));
+            ControlFlow::Continue(())
+        })?;
+        let stats = SearchStats {
+            solutions: 0,
+            ..stats
+        };
+        Ok((Split { units, stats }, best_partial))
+    }
+}
+
+/// The tiles placed on a board, as clues.
+pub(crate) fn prefix_clues<E: Copy + PartialEq>(
+    spec: &BoardSpec<E>,
+    board: &Board<E>,
+) -> Vec<Clue<E>> {
+    let mut clues = Vec::new();
+    for row in 0..board.rows {
+        for col in 0..board.columns {
+            let at = Indx { col, row };
+            let Some(placed) = &board[at] else {
+                continue;
+            };
+            // every tile on the board was placed from the tileset
+            let (id, rotation) = spec.tiles.identify(placed).unwrap();
+            clues.push(Clue {
+                tile: spec.tiles[..][id.index()],
+                rotation,
+                at,
+            });
+        }
+    }
+    clues
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::model::Board;
+
+use super::SearchStats;
+
+/// Limits on how much work a solver may do before giving up.
+///
+/// Each limit is optional, and the solver stops as soon as any one is reached.
+/// What counts as a node depends on the solver: a placement tried by a constructive search, a
+/// move tried by local search, or a child bred by a genetic algorithm.
+///
+/// ```
+/// use std::time::Duration;
+/// use e2rs::solver::Budget;
+///
+/// let budget = Budget::default()
+///     .with_nodes(1_000_000)
+///     .with_duration(Duration::from_secs(60));
+/// assert_eq!(budget.backtracks, None);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    /// The most nodes to try.
+    pub nodes: Option<u64>,
+    /// The most times to backtrack, for solvers that do.
+    pub backtracks: Option<u64>,
+    /// The longest to run for.
+    pub duration: Option<Duration>,
+    /// Stop when this is cancelled.
+    pub cancel: Option<Cancel>,
+}
+
+impl Budget {
+    /// Stop after trying this many nodes.
+    pub fn with_nodes(mut self, nodes: u64) -> Self {
+        self.nodes = Some(nodes);
+        self
+    }
+
+    /// Stop after backtracking this many times.
+    pub fn with_backtracks(mut self, backtracks: u64) -> Self {
+        self.backtracks = Some(backtracks);
+        self
+    }
+
+    /// Stop after running for this long.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Stop when the token is cancelled, as if the budget had run out.
+    ///
+    /// This is how to stop a solver from another thread or a signal handler, and still get
+    /// the best board it reached.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Backtracker, Budget, Cancel, SolveOutcome};
+    ///
+    /// let cancel = Cancel::new();
+    /// let budget = Budget::default().with_cancel(cancel.clone());
+    /// cancel.cancel();
+    ///
+    /// let spec = toy_4x4();
+    /// let outcome = Backtracker::new()
+    ///     .with_budget(budget)
+    ///     .solve(&spec, &[])
+    ///     .unwrap();
+    /// assert!(matches!(outcome, SolveOutcome::BudgetExhausted { .. }));
+    /// assert_eq!(outcome.stats().nodes, 0);
+    /// ```
+    pub fn with_cancel(mut self, cancel: Cancel) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// True if the budget's token has been cancelled.
+    pub(crate) fn cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(Cancel::is_cancelled)
+    }
+
+    /// Start spending the budget now.
+    pub(crate) fn start(&self) -> Meter {
+        Meter {
+            budget: self.clone(),
+            deadline: self.duration.map(|d| Instant::now() + d),
+        }
+    }
+}
+
+/// A [Budget] being spent.
+#[derive(Clone, Debug)]
+pub(crate) struct Meter {
+    budget: Budget,
+    deadline: Option<Instant>,
+}
+
+impl Meter {
+    /// True if the search has used up any part of its budget.
+    pub(crate) fn exhausted(&self, stats: &SearchStats) -> bool {
+        self.budget.nodes.is_some_and(|n| stats.nodes >= n)
+            || self
+                .budget
+                .backtracks
+                .is_some_and(|b| stats.backtracks >= b)
+            || self.deadline.is_some_and(|d| Instant::now() >= d)
+            || self.budget.cancelled()
+    }
+}
+
+/// A token for stopping a solver early, as by [Budget::with_cancel].
+///
+/// The token is a flag shared between its clones, so one clone can be given to the budgets of
+/// the solvers to stop, and another kept by whatever cancels them, such as another thread.
+#[derive(Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    /// A token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Cancel::default()
+    }
+
+    /// Stop the solvers with this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// True once the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for Cancel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Cancel {}
+
+impl fmt::Debug for Cancel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cancel").field(&self.is_cancelled()).finish()
+    }
+}
+
+/// How a run of a solver ended.
+#[derive(Clone, Debug)]
+pub enum SolveOutcome<E> {
+    /// A solution was found.
+    Solved {
+        /// The solution.
+        board: Board<E>,
+        /// What the solver did.
+        stats: SearchStats,
+    },
+    /// The whole search space was explored without finding a solution, so there is none.
+    NoSolution {
+        /// What the solver did.
+        stats: SearchStats,
+    },
+    /// The solver used up its [Budget], or was cancelled, before finding a solution.
+    BudgetExhausted {
+        /// The best board the solver reached: the deepest consistent partial placement for a
+        /// constructive search, or the highest-scoring board for local search.
+        best_partial: Board<E>,
+        /// What the solver did.
+        stats: SearchStats,
+    },
+}
+
+impl<E> SolveOutcome<E> {
+    /// The outcome of a constructive search, which proves there is no solution by finishing.
+    pub(crate) fn from_search(
+        solution: Option<Board<E>>,
+        best_partial: Board<E>,
+        stats: SearchStats,
+    ) -> Self {
+        match solution {
+            Some(board) => SolveOutcome::Solved { board, stats },
+            None if stats.complete => SolveOutcome::NoSolution { stats },
+            None => SolveOutcome::BudgetExhausted {
+                best_partial,
+                stats,
+            },
+        }
+    }
+
+    /// The solution, if one was found.
+    pub fn solution(&self) -> Option<&Board<E>> {
+        match self {
+            SolveOutcome::Solved { board, .. } => Some(board),
+            _ => None,
+        }
+    }
+
+    /// Take the solution, if one was found.
+    pub fn into_solution(self) -> Option<Board<E>> {
+        match self {
+            SolveOutcome::Solved { board, .. } => Some(board),
+            _ => None,
+        }
+    }
+
+    /// What the solver did.
+    pub fn stats(&self) -> &SearchStats {
+        match self {
+            SolveOutcome::Solved { stats, .. }
+            | SolveOutcome::NoSolution { stats }
+            | SolveOutcome::BudgetExhausted { stats, .. } => stats,
+        }
+    }
+}
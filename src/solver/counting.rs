@@ -0,0 +1,194 @@
+use crate::model::{BoardShape, BoardSpec, Edge, Indx, Rotate, ROTATIONS, SIDES};
+
+use super::Candidate;
+
+/// Edge counting for constructive searches: how many sides of each colour the unplaced tiles
+/// have, against how many the open sides of the board need, kept up to date as tiles are
+/// placed and taken back.
+///
+/// Every side of an unplaced tile will end up against the border, against a placed tile, or
+/// against another unplaced tile.
+/// So the unplaced tiles must have exactly as many border sides as the empty cells have sides
+/// on the outside of the board, and for every other colour, at least as many sides as the
+/// placed tiles show empty cells, with an even number left over to pair up between the empty
+/// cells.
+/// Once the counts of some colour can't balance, no way of filling the empty cells will
+/// solve the board, however well each tile fits where it is, and a search can take back its
+/// last placement at once.
+///
+/// The counts only depend on which tiles are placed where, so they can be used alongside a
+/// [Propagator](super::Propagator), and catch dead ends it can't, where the colours run out
+/// far from where they are needed.
+/// Every edge that is a border edge counts as the same colour.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::model::Indx;
+/// use e2rs::solver::{Candidate, EdgeCounts};
+///
+/// let spec = toy_3x3();
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let candidate = |i: usize| {
+///     let (tile, rotation) = spec.tiles.identify(&solution[i].placed_tile()).unwrap();
+///     Candidate { tile, rotation }
+/// };
+/// let mut counts = EdgeCounts::new(&spec);
+/// assert!(counts.balanced());
+///
+/// // the solution keeps the counts balanced all the way
+/// for (i, clue) in solution.iter().enumerate() {
+///     assert!(counts.place(clue.at, candidate(i)));
+/// }
+///
+/// // but moving the centre tile to a corner leaves the corner tile's border sides nowhere
+/// // to go
+/// let centre = solution.iter().position(|c| c.at == Indx { col: 1, row: 1 }).unwrap();
+/// let corner = solution.iter().position(|c| c.at == Indx { col: 0, row: 0 }).unwrap();
+/// counts.remove(solution[centre].at);
+/// counts.remove(solution[corner].at);
+/// assert!(!counts.place(solution[corner].at, candidate(centre)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EdgeCounts {
+    shape: BoardShape,
+    /// The colour of each side of each tile, by [TileId::index](crate::model::TileId::index)
+    /// then rotation, with colour 0 for the border.
+    colours: Vec<[[usize; 4]; 4]>,
+    /// The colours of the sides of the tile in each cell, if there is one.
+    cells: Vec<Option<[usize; 4]>>,
+    /// The number of sides of each colour on the unplaced tiles.
+    supply: Vec<usize>,
+    /// The number of sides of empty cells that need each colour: those facing a placed tile
+    /// that shows it, and for the border, those on the outside of the board.
+    demand: Vec<usize>,
+    /// The number of colours whose counts can't balance.
+    unbalanced: usize,
+}
+
+impl EdgeCounts {
+    /// Start with an empty board, every tile unplaced.
+    pub fn new<E: Copy + Edge + PartialEq>(spec: &BoardSpec<E>) -> Self {
+        let shape = spec.dimensions;
+        let mut palette: Vec<E> = Vec::new();
+        let mut colour = |edge: E| {
+            if edge.is_border() {
+                return 0;
+            }
+            match palette.iter().position(|e| *e == edge) {
+                Some(i) => i + 1,
+                None => {
+                    palette.push(edge);
+                    palette.len()
+                }
+            }
+        };
+        let colours: Vec<[[usize; 4]; 4]> = spec
+            .tiles
+            .into_iter()
+            .map(|tile| {
+                ROTATIONS.map(|rotation| {
+                    let placed = tile.rotate(rotation).apply();
+                    SIDES.map(|side| colour(placed[side]))
+                })
+            })
+            .collect();
+
+        let mut supply = vec![0; palette.len() + 1];
+        for tile in &colours {
+            for c in tile[0] {
+                supply[c] += 1;
+            }
+        }
+        let mut demand = vec![0; palette.len() + 1];
+        demand[0] = 2 * (shape.columns + shape.rows);
+
+        let mut counts = EdgeCounts {
+            shape,
+            colours,
+            cells: vec![None; shape.columns * shape.rows],
+            supply,
+            demand,
+            unbalanced: 0,
+        };
+        counts.unbalanced = (0..counts.supply.len())
+            .filter(|c| !counts.balances(*c))
+            .count();
+        counts
+    }
+
+    /// Place a tile in an empty cell, and tell whether the counts still balance.
+    pub fn place(&mut self, at: Indx, candidate: Candidate) -> bool {
+        let colours = self.colours[candidate.tile.index()][candidate.rotation as usize];
+        for side in SIDES {
+            let c = colours[side as usize];
+            self.change(c, |counts| counts.supply[c] -= 1);
+            match self.shape.neighbour(at, side) {
+                None => self.change(0, |counts| counts.demand[0] -= 1),
+                Some(n) => match self.cells[self.cell(n)] {
+                    // the side of this cell no longer needs the neighbour's colour
+                    Some(neighbour) => {
+                        let shown = neighbour[side.flip() as usize];
+                        self.change(shown, |counts| counts.demand[shown] -= 1);
+                    }
+                    // the side of the neighbour now needs this tile's colour
+                    None => self.change(c, |counts| counts.demand[c] += 1),
+                },
+            }
+        }
+        let cell = self.cell(at);
+        self.cells[cell] = Some(colours);
+        self.balanced()
+    }
+
+    /// Take back the tile in a cell, if there is one.
+    pub fn remove(&mut self, at: Indx) {
+        let cell = self.cell(at);
+        let Some(colours) = self.cells[cell].take() else {
+            return;
+        };
+        for side in SIDES {
+            let c = colours[side as usize];
+            self.change(c, |counts| counts.supply[c] += 1);
+            match self.shape.neighbour(at, side) {
+                None => self.change(0, |counts| counts.demand[0] += 1),
+                Some(n) => match self.cells[self.cell(n)] {
+                    Some(neighbour) => {
+                        let shown = neighbour[side.flip() as usize];
+                        self.change(shown, |counts| counts.demand[shown] += 1);
+                    }
+                    None => self.change(c, |counts| counts.demand[c] -= 1),
+                },
+            }
+        }
+    }
+
+    /// True if the counts of every colour can still balance.
+    pub fn balanced(&self) -> bool {
+        self.unbalanced == 0
+    }
+
+    /// True if the counts of the colour can balance.
+    fn balances(&self, colour: usize) -> bool {
+        let (supply, demand) = (self.supply[colour], self.demand[colour]);
+        if colour == 0 {
+            supply == demand
+        } else {
+            supply >= demand && (supply - demand) % 2 == 0
+        }
+    }
+
+    /// Change the counts of a colour, keeping track of whether they balance.
+    fn change(&mut self, colour: usize, f: impl FnOnce(&mut Self)) {
+        let before = self.balances(colour);
+        f(self);
+        match (before, self.balances(colour)) {
+            (true, false) => self.unbalanced += 1,
+            (false, true) => self.unbalanced -= 1,
+            _ => {}
+        }
+    }
+
+    fn cell(&self, at: Indx) -> usize {
+        at.row * self.shape.columns + at.col
+    }
+}
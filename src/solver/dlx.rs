@@ -0,0 +1,815 @@
+//! Solving edge-matching puzzles as exact cover problems, with Knuth's Algorithm X and dancing
+//! links.
+//!
+//! An exact cover problem is a set of items and a set of rows, each row covering some of the
+//! items, and a solution is a choice of rows covering every item exactly once.
+//! [ExactCover] solves any such problem, and [PlacementCover] poses an edge-matching puzzle as
+//! one:
+//!
+//! * every cell is an item, covered by the placements in that cell;
+//! * every tile is an item, covered by the placements of that tile;
+//! * every edge between two cells, together with every inside colour, is an item.
+//!   A placement covers, on the edges to its east and south, the items of every colour except
+//!   the one its tile shows there, and on the edges to its west and north, only the item of the
+//!   colour its tile shows there.
+//!   Each of these items is then covered exactly once only if the tiles on either side of the
+//!   edge show the same colour.
+//!
+//! Placements that put an outside edge anywhere but the border, or an inside edge on the
+//! border, are left out altogether.
+
+use std::ops::ControlFlow;
+
+use crate::error::PlacementError;
+use crate::model::{
+    Board, BoardShape, BoardSpec, Clue, Edge, Indx, Rotate, Side, Tile, TileSet, ROTATIONS, SIDES,
+};
+
+use super::{Budget, Meter, NoRecords, Record, RecordHook, SearchStats, SolveOutcome, Solver};
+
+/// The root of the list of primary items in an [ExactCover].
+const ROOT: usize = 0;
+
+/// An exact cover problem, solved with dancing links.
+///
+/// Items are numbered from 0.
+/// Primary items must be covered exactly once; secondary items may be covered at most once.
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use e2rs::solver::dlx::ExactCover;
+///
+/// // Knuth's example: rows 0, 3 and 4 cover each of the seven items once
+/// let mut cover = ExactCover::new(7, 0);
+/// for row in [
+///     &[2, 4, 5][..],
+///     &[0, 3, 6],
+///     &[1, 2, 5],
+///     &[0, 3],
+///     &[1, 6],
+///     &[3, 4, 6],
+/// ] {
+///     cover.add_row(row);
+/// }
+///
+/// let mut solutions = Vec::new();
+/// let stats = cover.search(|rows| {
+///     solutions.push(rows.to_vec());
+///     ControlFlow::Continue(())
+/// });
+/// assert!(stats.complete);
+/// assert_eq!(solutions, vec![vec![3, 0, 4]]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ExactCover {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The header node of the item each node belongs to.
+    item: Vec<usize>,
+    /// The row each node belongs to, or [usize::MAX] for header nodes.
+    row: Vec<usize>,
+    /// The number of rows still covering each item, indexed by header node.
+    size: Vec<usize>,
+    rows: usize,
+    budget: Budget,
+    /// The rows chosen when the last search was first at its greatest depth.
+    deepest: Vec<usize>,
+}
+
+impl ExactCover {
+    /// Make a problem with no rows, and `primary` primary items followed by `secondary`
+    /// secondary items.
+    pub fn new(primary: usize, secondary: usize) -> Self {
+        let headers = 1 + primary + secondary;
+        let mut cover = ExactCover {
+            left: (0..headers).collect(),
+            right: (0..headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            item: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            rows: 0,
+            budget: Budget::default(),
+            deepest: Vec::new(),
+        };
+        for header in 0..=primary {
+            cover.left[header] = if header == ROOT { primary } else { header - 1 };
+            cover.right[header] = if header == primary { ROOT } else { header + 1 };
+        }
+        cover
+    }
+
+    /// The number of rows added so far.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Give up searching when any limit of the budget is reached.
+    ///
+    /// Nodes are rows tried, and the search backtracks each time an item has no rows left to
+    /// try.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// The rows chosen when the last search was first at its greatest depth, in the order they
+    /// were chosen.
+    ///
+    /// These cover no item twice, but may leave some uncovered.
+    pub fn deepest(&self) -> &[usize] {
+        &self.deepest
+    }
+
+    /// Add a row covering the given items, and give its number.
+    ///
+    /// Rows are numbered from 0 in the order they are added.
+    /// This panics if an item is out of range or given twice.
+    pub fn add_row(&mut self, items: &[usize]) -> usize {
+        let row = self.rows;
+        self.rows += 1;
+        let first = self.left.len();
+        for (i, item) in items.iter().enumerate() {
+            let header = item + 1;
+            assert!(header < self.size.len(), "item {} is out of range", item);
+            assert!(
+                !items[..i].contains(item),
+                "item {} is covered twice by one row",
+                item
+            );
+
+            let node = self.left.len();
+            self.left.push(if i == 0 { node } else { node - 1 });
+            self.right.push(first);
+            if i > 0 {
+                self.right[node - 1] = node;
+                self.left[first] = node;
+            }
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.item.push(header);
+            self.row.push(row);
+            self.size[header] += 1;
+        }
+        row
+    }
+
+    /// Search for solutions, showing each to the visitor as the rows chosen, in the order they
+    /// were chosen.
+    ///
+    /// The visitor returns [ControlFlow::Break] to stop the search, or
+    /// [ControlFlow::Continue] to look for more.
+    /// Each node is a row tried.
+    /// The problem is left as it was, so it can be searched again.
+    pub fn search<F>(&mut self, visit: F) -> SearchStats
+    where
+        F: FnMut(&[usize]) -> ControlFlow<()>,
+    {
+        self.run(visit, &mut |_, _| {})
+    }
+
+    /// The solutions, each as the rows chosen in the order they were chosen, found one at a
+    /// time as the iterator is advanced.
+    ///
+    /// This is the same search as [ExactCover::search], paused between solutions.
+    /// The iterator ends when the search is complete or the budget runs out, which
+    /// [CoverSolutions::stats] tells apart.
+    /// The problem is left as it was when the iterator is dropped, so it can be searched again.
+    ///
+    /// ```
+    /// use e2rs::solver::dlx::ExactCover;
+    ///
+    /// // every way of covering four items with pairs of neighbours or single items
+    /// let mut cover = ExactCover::new(4, 0);
+    /// for row in [&[0, 1][..], &[1, 2], &[2, 3], &[0], &[1], &[2], &[3]] {
+    ///     cover.add_row(row);
+    /// }
+    /// let mut solutions = cover.solutions();
+    /// assert_eq!(solutions.next().unwrap().len(), 2);
+    /// assert_eq!(solutions.by_ref().count(), 4);
+    /// assert!(solutions.stats().complete);
+    /// ```
+    pub fn solutions(&mut self) -> CoverSolutions<'_> {
+        let meter = self.budget.start();
+        self.deepest.clear();
+        CoverSolutions {
+            cover: self,
+            meter,
+            stats: SearchStats::default(),
+            stack: Vec::new(),
+            chosen: Vec::new(),
+            descend: true,
+            done: false,
+        }
+    }
+
+    /// Search as for [ExactCover::search], also showing `deeper` the rows chosen and the nodes
+    /// tried each time the search chooses more rows than before.
+    fn run<F>(&mut self, mut visit: F, deeper: &mut dyn FnMut(&[usize], u64)) -> SearchStats
+    where
+        F: FnMut(&[usize]) -> ControlFlow<()>,
+    {
+        let mut stats = SearchStats::default();
+        let mut chosen = Vec::new();
+        let meter = self.budget.start();
+        self.deepest.clear();
+        let flow = self.extend(&mut chosen, &mut stats, &meter, &mut visit, deeper);
+        stats.complete = flow.is_continue();
+        stats
+    }
+
+    fn extend<F>(
+        &mut self,
+        chosen: &mut Vec<usize>,
+        stats: &mut SearchStats,
+        meter: &Meter,
+        visit: &mut F,
+        deeper: &mut dyn FnMut(&[usize], u64),
+    ) -> ControlFlow<()>
+    where
+        F: FnMut(&[usize]) -> ControlFlow<()>,
+    {
+        if self.right[ROOT] == ROOT {
+            stats.solutions += 1;
+            return visit(chosen);
+        }
+
+        // the primary item with the fewest rows left
+        let mut header = self.right[ROOT];
+        let mut next = self.right[header];
+        while next != ROOT {
+            if self.size[next] < self.size[header] {
+                header = next;
+            }
+            next = self.right[next];
+        }
+        if self.size[header] == 0 {
+            return self.backtrack(stats, meter);
+        }
+
+        self.cover(header);
+        let mut node = self.down[header];
+        let mut flow = ControlFlow::Continue(());
+        while node != header && flow.is_continue() {
+            if meter.exhausted(stats) {
+                flow = ControlFlow::Break(());
+                break;
+            }
+            stats.nodes += 1;
+            chosen.push(self.row[node]);
+            if chosen.len() > self.deepest.len() {
+                self.deepest.clone_from(chosen);
+                deeper(chosen, stats.nodes);
+            }
+            let mut j = self.right[node];
+            while j != node {
+                self.cover(self.item[j]);
+                j = self.right[j];
+            }
+            flow = self.extend(chosen, stats, meter, visit, deeper);
+            let mut j = self.left[node];
+            while j != node {
+                self.uncover(self.item[j]);
+                j = self.left[j];
+            }
+            chosen.pop();
+            node = self.down[node];
+        }
+        self.uncover(header);
+        flow?;
+        self.backtrack(stats, meter)
+    }
+
+    /// Count a backtrack, unless the budget has run out.
+    fn backtrack(&self, stats: &mut SearchStats, meter: &Meter) -> ControlFlow<()> {
+        if meter.exhausted(stats) {
+            return ControlFlow::Break(());
+        }
+        stats.backtracks += 1;
+        ControlFlow::Continue(())
+    }
+
+    /// Take an item out of the list of items, and every row covering it out of the other items.
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.item[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    /// Undo [ExactCover::cover].
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.item[j]] += 1;
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = j;
+                self.up[down] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+}
+
+/// The solutions of an [ExactCover], as given by [ExactCover::solutions].
+///
+/// The recursion of [ExactCover::search] is unrolled into a stack of the items being covered,
+/// so that the search can stop after each solution and carry on where it left off.
+#[derive(Debug)]
+pub struct CoverSolutions<'a> {
+    cover: &'a mut ExactCover,
+    meter: Meter,
+    stats: SearchStats,
+    /// The header of each item being covered, with the node of the row chosen for it, or the
+    /// header itself before the first row is chosen.
+    stack: Vec<(usize, usize)>,
+    chosen: Vec<usize>,
+    /// True if a row was just chosen, so the next item is still to be picked.
+    descend: bool,
+    /// True once the search has finished or been stopped.
+    done: bool,
+}
+
+impl CoverSolutions<'_> {
+    /// What the search has done so far.
+    ///
+    /// The stats are only [complete](SearchStats::complete) once the iterator has ended
+    /// because every row was tried.
+    pub fn stats(&self) -> &SearchStats {
+        &self.stats
+    }
+
+    /// Count a backtrack, or stop if the budget has run out.
+    fn backtrack(&mut self) {
+        if self.meter.exhausted(&self.stats) {
+            self.done = true;
+        } else {
+            self.stats.backtracks += 1;
+        }
+    }
+}
+
+impl Iterator for CoverSolutions<'_> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let cover = &mut *self.cover;
+            if self.descend {
+                self.descend = false;
+                if cover.right[ROOT] == ROOT {
+                    self.stats.solutions += 1;
+                    return Some(self.chosen.clone());
+                }
+                // the primary item with the fewest rows left
+                let mut header = cover.right[ROOT];
+                let mut next = cover.right[header];
+                while next != ROOT {
+                    if cover.size[next] < cover.size[header] {
+                        header = next;
+                    }
+                    next = cover.right[next];
+                }
+                if cover.size[header] == 0 {
+                    self.backtrack();
+                    continue;
+                }
+                cover.cover(header);
+                self.stack.push((header, header));
+            }
+
+            let Some((header, node)) = self.stack.last_mut() else {
+                self.done = true;
+                self.stats.complete = true;
+                break;
+            };
+            if self.meter.exhausted(&self.stats) {
+                // dropping the iterator uncovers what is still covered
+                self.done = true;
+                break;
+            }
+            let header = *header;
+            if *node != header {
+                let mut j = cover.left[*node];
+                while j != *node {
+                    cover.uncover(cover.item[j]);
+                    j = cover.left[j];
+                }
+                self.chosen.pop();
+            }
+            let row = cover.down[*node];
+            if row == header {
+                cover.uncover(header);
+                self.stack.pop();
+                self.backtrack();
+                continue;
+            }
+            *node = row;
+            self.stats.nodes += 1;
+            self.chosen.push(cover.row[row]);
+            if self.chosen.len() > cover.deepest.len() {
+                cover.deepest.clone_from(&self.chosen);
+            }
+            let mut j = cover.right[row];
+            while j != row {
+                cover.cover(cover.item[j]);
+                j = cover.right[j];
+            }
+            self.descend = true;
+        }
+        None
+    }
+}
+
+impl Drop for CoverSolutions<'_> {
+    /// Uncover everything the search covered, leaving the problem as it was.
+    fn drop(&mut self) {
+        let cover = &mut *self.cover;
+        while let Some((header, node)) = self.stack.pop() {
+            if node != header {
+                let mut j = cover.left[node];
+                while j != node {
+                    cover.uncover(cover.item[j]);
+                    j = cover.left[j];
+                }
+            }
+            cover.uncover(header);
+        }
+    }
+}
+
+/// An edge-matching puzzle posed as an [ExactCover] problem, as described in the
+/// [module documentation](self).
+///
+/// If there are more tiles than cells, the tiles are secondary items, so solutions leave some
+/// tiles out; with fewer tiles than cells there are no solutions.
+/// Rotations of a tile that look the same give one placement, but identical tiles each give
+/// their own, so a tileset with repeated tiles gives each solution once per arrangement of them.
+///
+/// ```
+/// use e2rs::examples::toy_3x3;
+/// use e2rs::solver::dlx::PlacementCover;
+///
+/// let spec = toy_3x3();
+/// let mut cover = PlacementCover::new(&spec.tiles, spec.dimensions);
+/// let board = cover.solve().into_solution().unwrap();
+/// assert!(spec.verify(&board, 0).is_ok());
+///
+/// // the toy puzzle is solved uniquely, up to turning the whole board
+/// let stats = cover.search(|_| std::ops::ControlFlow::Continue(()));
+/// assert_eq!(stats.solutions, 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PlacementCover<E> {
+    cover: ExactCover,
+    /// The placement each row of the cover stands for.
+    placements: Vec<Clue<E>>,
+    /// The clues, which every board the search reports has in place, even before it has
+    /// chosen their rows.
+    clues: Vec<Clue<E>>,
+    shape: BoardShape,
+}
+
+impl<E: Copy + Edge + PartialEq> PlacementCover<E> {
+    /// Pose the puzzle of placing the tiles on a board of the given shape.
+    pub fn new(tiles: &TileSet<E>, shape: BoardShape) -> Self {
+        PlacementCover::with_clues(tiles, shape, &[])
+    }
+
+    /// Pose the puzzle of placing the tiles on a board of the given shape, extending the clues.
+    ///
+    /// Only the clue placement is posed in a clue cell, and a clue tile is posed nowhere else.
+    /// A clue tile that isn't in the tileset leaves its cell with no placements, and so the
+    /// puzzle with no solutions.
+    pub fn with_clues(tiles: &TileSet<E>, shape: BoardShape, clues: &[Clue<E>]) -> Self {
+        let BoardShape { columns, rows } = shape;
+        let clue_tiles: Vec<_> = clues
+            .iter()
+            .filter_map(|clue| tiles.identify(&clue.placed_tile()).map(|(id, _)| id))
+            .collect();
+        let cells = columns * rows;
+
+        let mut colours: Vec<E> = Vec::new();
+        for tile in tiles {
+            for side in SIDES {
+                let edge = tile[side];
+                if !edge.is_border() && !colours.contains(&edge) {
+                    colours.push(edge);
+                }
+            }
+        }
+        let colour = |edge: E| colours.iter().position(|c| *c == edge).unwrap();
+
+        // the edges to the east of each cell but the last column, then to the south of each
+        // cell but the last row
+        let across = columns.saturating_sub(1) * rows;
+        let edges = across + columns * rows.saturating_sub(1);
+        let edge_to = |at: Indx, side: Side| match side {
+            Side::North => across + at.col + (at.row - 1) * columns,
+            Side::East => at.col + at.row * (columns - 1),
+            Side::South => across + at.col + at.row * columns,
+            Side::West => at.col - 1 + at.row * (columns - 1),
+        };
+
+        // cells, then each edge in each colour, then tiles, which come last so that they can be
+        // made secondary
+        let edge_items = |edge: usize| {
+            let first = cells + edge * colours.len();
+            first..first + colours.len()
+        };
+        let tile_item = |index: usize| cells + edges * colours.len() + index;
+        let mut cover = if tiles.len() > cells {
+            ExactCover::new(cells + edges * colours.len(), tiles.len())
+        } else {
+            ExactCover::new(cells + edges * colours.len() + tiles.len(), 0)
+        };
+
+        let mut placements = Vec::new();
+        let mut items = Vec::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let at = Indx { col, row };
+                for (id, tile) in tiles.ids().zip(tiles) {
+                    let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+                    for rotation in ROTATIONS {
+                        let placed = tile.rotate(rotation).apply();
+                        if seen.contains(&placed) {
+                            continue;
+                        }
+                        seen.push(placed);
+                        if !SIDES
+                            .iter()
+                            .all(|s| placed[*s].is_border() == shape.on_border(at, *s))
+                        {
+                            continue;
+                        }
+                        let clue = clues.iter().find(|c| c.at == at);
+                        let fixed = match clue {
+                            Some(clue) => clue.placed_tile() != placed,
+                            None => clue_tiles.contains(&id),
+                        };
+                        if fixed {
+                            continue;
+                        }
+
+                        items.clear();
+                        items.push(at.col + at.row * columns);
+                        items.push(tile_item(id.index()));
+                        for side in SIDES {
+                            if shape.on_border(at, side) {
+                                continue;
+                            }
+                            let shown = colour(placed[side]);
+                            let all = edge_items(edge_to(at, side));
+                            match side {
+                                Side::East | Side::South => items.extend(
+                                    all.enumerate().filter(|(c, _)| *c != shown).map(|(_, i)| i),
+                                ),
+                                Side::North | Side::West => items.push(all.start + shown),
+                            }
+                        }
+                        cover.add_row(&items);
+                        placements.push(Clue {
+                            tile: *tile,
+                            rotation,
+                            at,
+                        });
+                    }
+                }
+            }
+        }
+
+        PlacementCover {
+            cover,
+            placements,
+            clues: clues.to_vec(),
+            shape,
+        }
+    }
+}
+
+impl<E: Copy> PlacementCover<E> {
+    /// The placements posed, one for each row of the cover.
+    pub fn placements(&self) -> &[Clue<E>] {
+        &self.placements
+    }
+
+    /// Search for solutions, showing each to the visitor as its placements in row-major order.
+    ///
+    /// The visitor returns [ControlFlow::Break] to stop the search, or
+    /// [ControlFlow::Continue] to look for more.
+    pub fn search<F>(&mut self, mut visit: F) -> SearchStats
+    where
+        F: FnMut(Vec<Clue<E>>) -> ControlFlow<()>,
+    {
+        let placements = &self.placements;
+        self.cover.search(|rows| {
+            let mut clues: Vec<Clue<E>> = rows.iter().map(|r| placements[*r]).collect();
+            clues.sort_by_key(|c| (c.at.row, c.at.col));
+            visit(clues)
+        })
+    }
+
+    /// The solutions, found one at a time as the iterator is advanced, as for
+    /// [ExactCover::solutions].
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::dlx::PlacementCover;
+    ///
+    /// let spec = toy_4x4();
+    /// let mut cover = PlacementCover::new(&spec.tiles, spec.dimensions);
+    /// let mut solutions = cover.solutions();
+    /// assert!(spec.verify(&solutions.next().unwrap(), 0).is_ok());
+    /// assert_eq!(solutions.by_ref().count(), 3);
+    /// assert_eq!(solutions.stats().solutions, 4);
+    /// ```
+    pub fn solutions(&mut self) -> PlacementSolutions<'_, E> {
+        PlacementSolutions {
+            solutions: self.cover.solutions(),
+            placements: &self.placements,
+            clues: &self.clues,
+            shape: self.shape,
+        }
+    }
+
+    /// Give up searching when any limit of the budget is reached, as for
+    /// [ExactCover::with_budget].
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::dlx::PlacementCover;
+    /// use e2rs::solver::{Budget, SolveOutcome};
+    ///
+    /// let spec = toy_4x4();
+    /// let mut cover = PlacementCover::new(&spec.tiles, spec.dimensions)
+    ///     .with_budget(Budget::default().with_nodes(5));
+    /// let SolveOutcome::BudgetExhausted { best_partial, stats } = cover.solve() else {
+    ///     panic!("the budget is too small to solve the puzzle");
+    /// };
+    /// assert_eq!(stats.nodes, 5);
+    /// assert!(best_partial.into_iter().any(|(_, t)| t.is_some()));
+    /// ```
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.cover = self.cover.with_budget(budget);
+        self
+    }
+
+    /// Search for one solution.
+    ///
+    /// If the budget runs out first, this gives the deepest partial placement the search
+    /// reached.
+    pub fn solve(&mut self) -> SolveOutcome<E>
+    where
+        E: Edge + PartialEq,
+    {
+        self.solve_with_hook(&mut NoRecords)
+    }
+
+    /// Search for one solution as for [PlacementCover::solve], telling the hook each time the
+    /// search reaches a deeper placement than before.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::dlx::PlacementCover;
+    /// use e2rs::solver::Record;
+    ///
+    /// let spec = toy_4x4();
+    /// let mut records: Vec<Record<u8>> = Vec::new();
+    /// PlacementCover::new(&spec.tiles, spec.dimensions)
+    ///     .solve_with_hook(&mut |record| records.push(record));
+    /// assert!(records.windows(2).all(|r| r[0].placed < r[1].placed));
+    /// assert_eq!(records.last().unwrap().placed, 16);
+    /// ```
+    pub fn solve_with_hook(&mut self, hook: &mut dyn RecordHook<E>) -> SolveOutcome<E>
+    where
+        E: Edge + PartialEq,
+    {
+        let mut found = None;
+        let placed = |rows: &[usize]| board(self.shape, &self.clues, &self.placements, rows);
+        let stats = self.cover.run(
+            |rows| {
+                found = Some(rows.to_vec());
+                ControlFlow::Break(())
+            },
+            &mut |rows, nodes| hook.record(Record::new(&placed(rows), nodes)),
+        );
+        SolveOutcome::from_search(
+            found.map(|rows| placed(&rows)),
+            placed(self.cover.deepest()),
+            stats,
+        )
+    }
+}
+
+/// The solutions of a [PlacementCover], as given by [PlacementCover::solutions].
+#[derive(Debug)]
+pub struct PlacementSolutions<'a, E> {
+    solutions: CoverSolutions<'a>,
+    placements: &'a [Clue<E>],
+    clues: &'a [Clue<E>],
+    shape: BoardShape,
+}
+
+impl<E> PlacementSolutions<'_, E> {
+    /// What the search has done so far, as for [CoverSolutions::stats].
+    pub fn stats(&self) -> &SearchStats {
+        self.solutions.stats()
+    }
+}
+
+impl<E: Copy> Iterator for PlacementSolutions<'_, E> {
+    type Item = Board<E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rows = self.solutions.next()?;
+        Some(board(self.shape, self.clues, self.placements, &rows))
+    }
+}
+
+/// The board with the clues and the placements of the given rows.
+fn board<E: Copy>(
+    shape: BoardShape,
+    clues: &[Clue<E>],
+    placements: &[Clue<E>],
+    rows: &[usize],
+) -> Board<E> {
+    let mut board = shape.new_board();
+    for clue in clues {
+        board[clue.at] = Some(clue.placed_tile());
+    }
+    for row in rows {
+        let clue = &placements[*row];
+        board[clue.at] = Some(clue.placed_tile());
+    }
+    board
+}
+
+/// A [Solver] posing each puzzle as a [PlacementCover] extending the clues.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::solver::dlx::DlxSolver;
+/// use e2rs::solver::Solver;
+///
+/// let spec = toy_3x3();
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let board = DlxSolver::default()
+///     .solve(&spec, &solution[..1])
+///     .unwrap()
+///     .into_solution()
+///     .unwrap();
+/// for clue in solution {
+///     assert_eq!(board[clue.at], Some(clue.placed_tile()));
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DlxSolver {
+    /// Limits on the search, as for [ExactCover::with_budget].
+    pub budget: Budget,
+}
+
+impl<E: Copy + Edge + PartialEq> Solver<E> for DlxSolver {
+    /// Clues that don't fit the board are reported as by [BoardSpec::validate_clues], and every
+    /// clue tile must be in the tileset.
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+        if let Some(clue) = clues
+            .iter()
+            .find(|c| spec.tiles.identify(&c.placed_tile()).is_none())
+        {
+            return Err(PlacementError::UnknownTile(clue.at));
+        }
+        let mut cover = PlacementCover::with_clues(&spec.tiles, spec.dimensions, clues)
+            .with_budget(self.budget.clone());
+        Ok(cover.solve_with_hook(hook))
+    }
+}
@@ -0,0 +1,169 @@
+use crate::analysis::TileSetStats;
+use crate::model::{Edge, Indx, Rotate, SIDES};
+
+use super::{Candidate, SearchState, ValueOrdering};
+
+/// The features of a candidate placement that an [Evaluator] scores.
+///
+/// These are cheap to compute at every node of a search, and are laid out as a flat vector by
+/// [Features::to_vec] for models trained outside the crate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Features {
+    /// The candidate being scored.
+    pub candidate: Candidate,
+    /// The cell the candidate would fill.
+    pub at: Indx,
+    /// The fraction of the board already filled, from 0 to 1.
+    pub progress: f32,
+    /// How many sides of the cell lie on the board border.
+    pub border_sides: usize,
+    /// How many sides of the cell face tiles already placed.
+    pub placed_neighbours: usize,
+    /// How many tile sides in the tileset carry each edge of the rotated candidate, clockwise
+    /// from north.
+    pub edge_counts: [usize; 4],
+}
+
+/// The number of values in [Features::to_vec].
+pub const FEATURE_COUNT: usize = 11;
+
+impl Features {
+    /// The features of a candidate for the cell at `at`.
+    pub fn new<E: Copy + Edge + Into<u8>>(
+        state: &SearchState<'_, E>,
+        stats: &TileSetStats,
+        at: Indx,
+        candidate: Candidate,
+    ) -> Self {
+        let board = state.board;
+        let shape = board.shape();
+        let cells = board.columns * board.rows;
+        let filled = state.used.iter().filter(|u| **u).count();
+
+        let tile = &state.spec.tiles[..][candidate.tile.index()];
+        let placed = tile.rotate(candidate.rotation).apply();
+
+        Features {
+            candidate,
+            at,
+            progress: if cells == 0 {
+                0.0
+            } else {
+                filled as f32 / cells as f32
+            },
+            border_sides: SIDES.iter().filter(|s| shape.on_border(at, **s)).count(),
+            placed_neighbours: SIDES
+                .iter()
+                .filter_map(|s| shape.neighbour(at, *s))
+                .filter(|n| board[*n].is_some())
+                .count(),
+            edge_counts: SIDES.map(|s| stats.count(placed[s])),
+        }
+    }
+
+    /// The features as a flat vector of [FEATURE_COUNT] values, in field order.
+    ///
+    /// The candidate is given by its tile index and clockwise quarter turns, and the cell by
+    /// its column and row.
+    pub fn to_vec(&self) -> Vec<f32> {
+        let mut v = Vec::with_capacity(FEATURE_COUNT);
+        v.push(self.candidate.tile.index() as f32);
+        v.push(self.candidate.rotation as usize as f32);
+        v.push(self.at.col as f32);
+        v.push(self.at.row as f32);
+        v.push(self.progress);
+        v.push(self.border_sides as f32);
+        v.push(self.placed_neighbours as f32);
+        v.extend(self.edge_counts.iter().map(|c| *c as f32));
+        v
+    }
+}
+
+/// A learned or hand-written scoring of candidate placements.
+///
+/// Solvers call the evaluator, through an [EvaluatorOrdering], to rank the candidates for a
+/// cell, so trained models can guide the search without any change to the search code.
+/// Higher scores are tried first.
+///
+/// Any `FnMut(&Features) -> f64` closure is an evaluator.
+pub trait Evaluator {
+    /// Score a candidate placement.
+    fn evaluate(&mut self, features: &Features) -> f64;
+}
+
+impl<F: FnMut(&Features) -> f64> Evaluator for F {
+    fn evaluate(&mut self, features: &Features) -> f64 {
+        self(features)
+    }
+}
+
+/// A value ordering that tries candidates in order of their [Evaluator] scores, highest first.
+///
+/// Candidates with equal scores keep their original order.
+///
+/// ```
+/// use e2rs::analysis::tileset_stats;
+/// use e2rs::e2::{new_e2board, E2_BOARD_SPEC};
+/// use e2rs::model::{Indx, Rotation};
+/// use e2rs::solver::{Candidate, EvaluatorOrdering, Features, SearchState, ValueOrdering};
+///
+/// let spec = &*E2_BOARD_SPEC;
+/// let board = new_e2board();
+/// let used = vec![false; spec.tiles.len()];
+/// let state = SearchState { spec, board: &board, used: &used, propagator: None };
+///
+/// // prefer candidates whose edges are rare in the tileset
+/// let rarest = |f: &Features| -(f.edge_counts.iter().sum::<usize>() as f64);
+/// let stats = tileset_stats(&spec.tiles);
+/// let mut ordering = EvaluatorOrdering::new(stats.clone(), rarest);
+///
+/// let at = Indx { col: 5, row: 5 };
+/// let mut candidates: Vec<_> = spec
+///     .tiles
+///     .ids()
+///     .map(|tile| Candidate { tile, rotation: Rotation::Rot0 })
+///     .collect();
+/// ordering.order_candidates(&state, at, &mut candidates);
+///
+/// let score = |c| rarest(&Features::new(&state, &stats, at, c));
+/// assert!(score(candidates[0]) >= score(candidates[candidates.len() - 1]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct EvaluatorOrdering<V> {
+    stats: TileSetStats,
+    evaluator: V,
+}
+
+impl<V: Evaluator> EvaluatorOrdering<V> {
+    /// Rank candidates with an evaluator, using the statistics of the puzzle's tileset for
+    /// [Features::edge_counts].
+    pub fn new(stats: TileSetStats, evaluator: V) -> Self {
+        EvaluatorOrdering { stats, evaluator }
+    }
+
+    /// The evaluator.
+    pub fn evaluator(&mut self) -> &mut V {
+        &mut self.evaluator
+    }
+}
+
+impl<E: Copy + Edge + Into<u8>, V: Evaluator> ValueOrdering<E> for EvaluatorOrdering<V> {
+    fn order_candidates(
+        &mut self,
+        state: &SearchState<'_, E>,
+        at: Indx,
+        candidates: &mut [Candidate],
+    ) {
+        let mut scored: Vec<(f64, Candidate)> = candidates
+            .iter()
+            .map(|c| {
+                let features = Features::new(state, &self.stats, at, *c);
+                (self.evaluator.evaluate(&features), *c)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        for (slot, (_, c)) in candidates.iter_mut().zip(scored) {
+            *slot = c;
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use std::io::{self, ErrorKind, Read, Write};
+
+use thiserror::Error;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardShape, BoardSpec, Indx, Rotate, TileSet, ROTATIONS};
+
+use super::Candidate;
+
+/// The version of the event log format written by [EventWriter].
+pub const EVENT_LOG_VERSION: u8 = 1;
+
+/// The bytes every event log starts with.
+const MAGIC: &[u8; 4] = b"E2EV";
+
+const PLACE: u8 = 1;
+const REMOVE: u8 = 2;
+const BACKTRACK: u8 = 3;
+const RECORD: u8 = 4;
+
+/// One step of a constructive search, as logged by [Backtracker::solve_with_events].
+///
+/// Replaying the events of a search in order with a [Replay] rebuilds the board as the search
+/// saw it at every step.
+///
+/// [Backtracker::solve_with_events]: super::Backtracker::solve_with_events
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A tile was placed in an empty cell.
+    Place {
+        /// The cell.
+        at: Indx,
+        /// The tile and its rotation.
+        candidate: Candidate,
+    },
+    /// The tile in a cell was taken back.
+    Remove {
+        /// The cell.
+        at: Indx,
+    },
+    /// The search ran out of candidates for a cell, and went back to the placement before.
+    Backtrack,
+    /// The board reached a deeper placement than any before, making a [Record](super::Record).
+    Record {
+        /// The number of nodes the search had tried.
+        nodes: u64,
+    },
+}
+
+/// Something to tell of each [Event] of a search.
+///
+/// Any `FnMut(Event)` closure is a sink, and so is an [EventWriter].
+pub trait EventSink {
+    /// Take the next event.
+    fn event(&mut self, event: Event);
+}
+
+impl<F: FnMut(Event)> EventSink for F {
+    fn event(&mut self, event: Event) {
+        self(event)
+    }
+}
+
+/// A sink that ignores every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoEvents;
+
+impl EventSink for NoEvents {
+    fn event(&mut self, _event: Event) {}
+}
+
+/// Errors raised while writing or reading an event log.
+#[derive(Debug, Error)]
+pub enum EventLogError {
+    /// The log could not be written or read.
+    #[error("event log: {0}")]
+    Io(#[from] io::Error),
+    /// The stream does not start as an event log does.
+    #[error("not an event log")]
+    NotAnEventLog,
+    /// The log was written in a version of the format this can't read.
+    #[error("event log version {0} is not supported")]
+    UnsupportedVersion(u8),
+    /// The board is too large to log.
+    #[error("a {}x{} board is too large for an event log", .0.columns, .0.rows)]
+    TooLarge(BoardShape),
+    /// The log is of a board of another shape.
+    #[error(transparent)]
+    Placement(#[from] PlacementError),
+    /// An event could not be decoded, or names a cell or tile the board doesn't have.
+    #[error("byte {byte}: bad event")]
+    BadEvent {
+        /// The offset of the event, counting from 0 at the start of the log.
+        byte: u64,
+    },
+    /// The log ends part way through an event.
+    #[error("byte {byte}: the event log ends part way through an event")]
+    Truncated {
+        /// The offset of the event, counting from 0 at the start of the log.
+        byte: u64,
+    },
+}
+
+/// A sink writing events to a stream, in the event log format described in
+/// `docs/formats.md`.
+///
+/// Writing can't fail part way through a search, so the first error is kept, no more is
+/// written, and the error is given by [EventWriter::finish].
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Backtracker, EventReader, EventWriter, Replay};
+///
+/// let spec = toy_4x4();
+/// let mut writer = EventWriter::new(Vec::new(), spec.dimensions).unwrap();
+/// let outcome = Backtracker::new()
+///     .solve_with_events(&spec, &[], &mut writer)
+///     .unwrap();
+/// let log = writer.finish().unwrap();
+///
+/// // replaying the whole log ends with the solution on the board
+/// let mut replay = Replay::new(&spec);
+/// for event in EventReader::new(&log[..], &spec).unwrap() {
+///     replay.apply(&event.unwrap()).unwrap();
+/// }
+/// let solution: Vec<_> = outcome.into_solution().unwrap().into_iter().collect();
+/// assert_eq!(replay.into_board().into_iter().collect::<Vec<_>>(), solution);
+/// ```
+#[derive(Debug)]
+pub struct EventWriter<W: Write> {
+    write: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> EventWriter<W> {
+    /// Start a log of a search of a board of this shape, writing the header.
+    pub fn new(mut write: W, shape: BoardShape) -> Result<Self, EventLogError> {
+        let (Ok(columns), Ok(rows)) = (u16::try_from(shape.columns), u16::try_from(shape.rows))
+        else {
+            return Err(EventLogError::TooLarge(shape));
+        };
+        write.write_all(MAGIC)?;
+        write.write_all(&[EVENT_LOG_VERSION])?;
+        write.write_all(&columns.to_le_bytes())?;
+        write.write_all(&rows.to_le_bytes())?;
+        Ok(EventWriter { write, error: None })
+    }
+
+    /// Flush the log, and give back the stream, or the first error writing it.
+    pub fn finish(mut self) -> Result<W, EventLogError> {
+        if let Some(e) = self.error {
+            return Err(e.into());
+        }
+        self.write.flush()?;
+        Ok(self.write)
+    }
+}
+
+impl<W: Write> EventSink for EventWriter<W> {
+    fn event(&mut self, event: Event) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut bytes = [0; 9];
+        let len = match event {
+            Event::Place { at, candidate } => {
+                bytes[0] = PLACE;
+                bytes[1..3].copy_from_slice(&(at.col as u16).to_le_bytes());
+                bytes[3..5].copy_from_slice(&(at.row as u16).to_le_bytes());
+                bytes[5..7].copy_from_slice(&(candidate.tile.number() as u16).to_le_bytes());
+                bytes[7] = candidate.rotation as u8;
+                8
+            }
+            Event::Remove { at } => {
+                bytes[0] = REMOVE;
+                bytes[1..3].copy_from_slice(&(at.col as u16).to_le_bytes());
+                bytes[3..5].copy_from_slice(&(at.row as u16).to_le_bytes());
+                5
+            }
+            Event::Backtrack => {
+                bytes[0] = BACKTRACK;
+                1
+            }
+            Event::Record { nodes } => {
+                bytes[0] = RECORD;
+                bytes[1..9].copy_from_slice(&nodes.to_le_bytes());
+                9
+            }
+        };
+        if let Err(e) = self.write.write_all(&bytes[..len]) {
+            self.error = Some(e);
+        }
+    }
+}
+
+/// The events of a log written by [EventWriter], read back one at a time.
+///
+/// The log must be of a board of the spec's shape, and tiles are looked up in the spec's tileset.
+/// Reading stops at the first error.
+#[derive(Debug)]
+pub struct EventReader<'a, R, E> {
+    read: R,
+    tiles: &'a TileSet<E>,
+    shape: BoardShape,
+    /// The offset of the next event.
+    byte: u64,
+    failed: bool,
+}
+
+impl<'a, R: Read, E> EventReader<'a, R, E> {
+    /// Read the header of a log of a search of the spec.
+    pub fn new(mut read: R, spec: &'a BoardSpec<E>) -> Result<Self, EventLogError> {
+        let mut header = [0; 9];
+        read.read_exact(&mut header).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => EventLogError::NotAnEventLog,
+            _ => e.into(),
+        })?;
+        if &header[..4] != MAGIC {
+            return Err(EventLogError::NotAnEventLog);
+        }
+        if header[4] != EVENT_LOG_VERSION {
+            return Err(EventLogError::UnsupportedVersion(header[4]));
+        }
+        let found = BoardShape {
+            columns: u16::from_le_bytes([header[5], header[6]]) as usize,
+            rows: u16::from_le_bytes([header[7], header[8]]) as usize,
+        };
+        if found != spec.dimensions {
+            return Err(PlacementError::ShapeMismatch {
+                expected: spec.dimensions,
+                found,
+            }
+            .into());
+        }
+        Ok(EventReader {
+            read,
+            tiles: &spec.tiles,
+            shape: found,
+            byte: header.len() as u64,
+            failed: false,
+        })
+    }
+
+    /// Read the next event, or `None` at the end of the log.
+    fn next_event(&mut self) -> Result<Option<Event>, EventLogError> {
+        let byte = self.byte;
+        let mut tag = [0];
+        loop {
+            match self.read.read(&mut tag) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        let len = match tag[0] {
+            PLACE => 7,
+            REMOVE => 4,
+            BACKTRACK => 0,
+            RECORD => 8,
+            _ => return Err(EventLogError::BadEvent { byte }),
+        };
+        let mut fields = [0; 8];
+        self.read
+            .read_exact(&mut fields[..len])
+            .map_err(|e| match e.kind() {
+                ErrorKind::UnexpectedEof => EventLogError::Truncated { byte },
+                _ => e.into(),
+            })?;
+        self.byte += 1 + len as u64;
+
+        let u16_at = |i: usize| u16::from_le_bytes([fields[i], fields[i + 1]]) as usize;
+        let cell = || {
+            let at = Indx {
+                col: u16_at(0),
+                row: u16_at(2),
+            };
+            (at.col < self.shape.columns && at.row < self.shape.rows).then_some(at)
+        };
+        let event = match tag[0] {
+            PLACE => {
+                let at = cell();
+                let tile = self.tiles.tile_id(u16_at(4));
+                let rotation = ROTATIONS.get(fields[6] as usize);
+                match (at, tile, rotation) {
+                    (Some(at), Some(tile), Some(&rotation)) => Event::Place {
+                        at,
+                        candidate: Candidate { tile, rotation },
+                    },
+                    _ => return Err(EventLogError::BadEvent { byte }),
+                }
+            }
+            REMOVE => match cell() {
+                Some(at) => Event::Remove { at },
+                None => return Err(EventLogError::BadEvent { byte }),
+            },
+            BACKTRACK => Event::Backtrack,
+            _ => Event::Record {
+                nodes: u64::from_le_bytes(fields),
+            },
+        };
+        Ok(Some(event))
+    }
+}
+
+impl<R: Read, E> Iterator for EventReader<'_, R, E> {
+    type Item = Result<Event, EventLogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let next = self.next_event().transpose();
+        self.failed = matches!(next, Some(Err(_)));
+        next
+    }
+}
+
+/// A board rebuilt from the events of a search.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::model::{Indx, Rotation};
+/// use e2rs::solver::{Candidate, Event, Replay};
+///
+/// let spec = toy_4x4();
+/// let at = Indx { col: 0, row: 0 };
+/// let candidate = Candidate {
+///     tile: spec.tiles.tile_id(1).unwrap(),
+///     rotation: Rotation::Rot0,
+/// };
+/// let mut replay = Replay::new(&spec);
+/// replay.apply(&Event::Place { at, candidate }).unwrap();
+/// assert!(replay.board()[at].is_some());
+/// replay.apply(&Event::Remove { at }).unwrap();
+/// assert!(replay.board()[at].is_none());
+/// ```
+#[derive(Debug)]
+pub struct Replay<'a, E> {
+    spec: &'a BoardSpec<E>,
+    board: Board<E>,
+}
+
+impl<'a, E: Copy> Replay<'a, E> {
+    /// An empty board of the spec's shape, to replay a search of the spec on.
+    pub fn new(spec: &'a BoardSpec<E>) -> Self {
+        Replay {
+            spec,
+            board: spec.dimensions.new_board(),
+        }
+    }
+
+    /// Change the board as the event did.
+    ///
+    /// Only placements and removals change the board.
+    pub fn apply(&mut self, event: &Event) -> Result<(), PlacementError> {
+        match *event {
+            Event::Place { at, candidate } => {
+                self.check(at)?;
+                let tile = self
+                    .spec
+                    .tiles
+                    .get(candidate.tile)
+                    .ok_or(PlacementError::UnknownTile(at))?;
+                self.board[at] = Some(tile.rotate(candidate.rotation).apply());
+            }
+            Event::Remove { at } => {
+                self.check(at)?;
+                self.board[at] = None;
+            }
+            Event::Backtrack | Event::Record { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// The board as the events so far left it.
+    pub fn board(&self) -> &Board<E> {
+        &self.board
+    }
+
+    /// The board as the events left it, taken from the replay.
+    pub fn into_board(self) -> Board<E> {
+        self.board
+    }
+
+    fn check(&self, at: Indx) -> Result<(), PlacementError> {
+        if at.col < self.board.columns && at.row < self.board.rows {
+            Ok(())
+        } else {
+            Err(PlacementError::OutOfBounds {
+                at,
+                columns: self.board.columns,
+                rows: self.board.rows,
+            })
+        }
+    }
+}
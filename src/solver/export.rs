@@ -0,0 +1,366 @@
+//! Posing edge-matching puzzles for general-purpose solvers.
+//!
+//! These write a puzzle out as a model that constraint and integer programming solvers can
+//! load directly, so that they can be benchmarked against the solvers of this crate without
+//! writing the model by hand each time.
+//! [minizinc] writes a MiniZinc model, and [lp] a 0/1 integer program in CPLEX LP format.
+//!
+//! Tiles are numbered from 1, rotations are counted anti-clockwise from 0 to 3 as in
+//! [Rotation](crate::model::Rotation), and cells are numbered from 0 by column then row, as
+//! they are in clues files.
+//! Edge colours are numbered from 1 in the order they are first seen in the tileset, with 0
+//! for the border.
+
+use std::fmt::Write;
+
+use crate::model::{BoardSpec, Clue, Edge, Indx, Rotate, Side, Tile, ROTATIONS, SIDES};
+
+/// A MiniZinc model of the puzzle of filling the board with its tiles, extending the clues.
+///
+/// The tiles, the board shape and the clues are given as parameters at the top of the model,
+/// and the constraints below them only refer to the parameters, so the data can be swapped
+/// for another puzzle's.
+/// The model places a different tile in each cell, in one of its four rotations, with every
+/// border edge on the outside of the board, every other edge inside it, matching edges
+/// between neighbouring cells, and each clue tile in its cell in the clue's rotation.
+/// A clue tile that isn't in the tileset is given as tile 0, which leaves the model with no
+/// solutions.
+///
+/// A solution is written out in the clues file format, one line per cell, so it can be read
+/// back with [BoardSpec::load_clues] with anti-clockwise rotations.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::solver::export::minizinc;
+///
+/// let spec = toy_3x3();
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let model = minizinc(&spec, &solution[..1]);
+/// assert!(model.contains("int: rows = 3;"));
+/// assert!(model.contains("int: n_clues = 1;"));
+/// assert!(model.contains("solve satisfy;"));
+/// ```
+pub fn minizinc<E: Copy + Edge + PartialEq>(spec: &BoardSpec<E>, clues: &[Clue<E>]) -> String {
+    let shape = spec.dimensions;
+    let (colours, n_colours) = colours(spec);
+    let mut model = String::new();
+
+    writeln!(model, "% An edge-matching puzzle, exported by e2rs.").unwrap();
+    writeln!(model, "include \"globals.mzn\";").unwrap();
+    writeln!(model).unwrap();
+    writeln!(model, "int: rows = {};", shape.rows).unwrap();
+    writeln!(model, "int: cols = {};", shape.columns).unwrap();
+    writeln!(model, "int: n_tiles = {};", spec.tiles.len()).unwrap();
+    writeln!(model, "int: n_colours = {};", n_colours).unwrap();
+    writeln!(model, "int: n_clues = {};", clues.len()).unwrap();
+    writeln!(model).unwrap();
+    writeln!(model, "set of int: ROWS = 1..rows;").unwrap();
+    writeln!(model, "set of int: COLS = 1..cols;").unwrap();
+    writeln!(model, "set of int: TILES = 1..n_tiles;").unwrap();
+    writeln!(model, "set of int: ROTATIONS = 0..3;").unwrap();
+    writeln!(model, "% north, east, south and west").unwrap();
+    writeln!(model, "set of int: SIDES = 1..4;").unwrap();
+    writeln!(model, "set of int: COLOURS = 0..n_colours;").unwrap();
+    writeln!(model, "set of int: CLUES = 1..n_clues;").unwrap();
+    writeln!(model).unwrap();
+
+    writeln!(
+        model,
+        "% the colour on each side of each tile, once rotated, with 0 for the border"
+    )
+    .unwrap();
+    writeln!(
+        model,
+        "array[TILES, ROTATIONS, SIDES] of COLOURS: colour = array3d(TILES, ROTATIONS, SIDES, ["
+    )
+    .unwrap();
+    let tiles: Vec<String> = colours
+        .iter()
+        .map(|tile| {
+            let rotated: Vec<String> = tile
+                .iter()
+                .map(|sides| sides.map(|c| c.to_string()).join(", "))
+                .collect();
+            format!("  {}", rotated.join(", "))
+        })
+        .collect();
+    writeln!(model, "{}", tiles.join(",\n")).unwrap();
+    writeln!(model, "]);").unwrap();
+    writeln!(model).unwrap();
+
+    let clue_tiles: Vec<(usize, usize)> = clues
+        .iter()
+        .map(|clue| {
+            spec.tiles
+                .identify(&clue.placed_tile())
+                .map_or((0, 0), |(id, rotation)| (id.number(), rotation as usize))
+        })
+        .collect();
+    let list = |values: &mut dyn Iterator<Item = usize>| {
+        values.map(|v| v.to_string()).collect::<Vec<_>>().join(", ")
+    };
+    writeln!(model, "% the cell, tile and rotation of each clue").unwrap();
+    writeln!(
+        model,
+        "array[CLUES] of ROWS: clue_row = [{}];",
+        list(&mut clues.iter().map(|c| c.at.row + 1))
+    )
+    .unwrap();
+    writeln!(
+        model,
+        "array[CLUES] of COLS: clue_col = [{}];",
+        list(&mut clues.iter().map(|c| c.at.col + 1))
+    )
+    .unwrap();
+    writeln!(
+        model,
+        "array[CLUES] of int: clue_tile = [{}];",
+        list(&mut clue_tiles.iter().map(|(tile, _)| *tile))
+    )
+    .unwrap();
+    writeln!(
+        model,
+        "array[CLUES] of ROTATIONS: clue_rotation = [{}];",
+        list(&mut clue_tiles.iter().map(|(_, rotation)| *rotation))
+    )
+    .unwrap();
+    writeln!(model).unwrap();
+
+    model.push_str(MINIZINC_CONSTRAINTS);
+    model
+}
+
+/// The part of the MiniZinc model that only refers to the parameters.
+const MINIZINC_CONSTRAINTS: &str = r#"array[ROWS, COLS] of var TILES: tile;
+array[ROWS, COLS] of var ROTATIONS: rotation;
+
+% the colour each cell shows on each side
+array[ROWS, COLS, SIDES] of var COLOURS: edge;
+constraint forall(r in ROWS, c in COLS, s in SIDES)(
+  edge[r, c, s] = colour[tile[r, c], rotation[r, c], s]
+);
+
+constraint alldifferent([tile[r, c] | r in ROWS, c in COLS]);
+
+% border edges on the outside of the board, and only there
+constraint forall(r in ROWS, c in COLS)(
+  (edge[r, c, 1] = 0 <-> r = 1) /\
+  (edge[r, c, 2] = 0 <-> c = cols) /\
+  (edge[r, c, 3] = 0 <-> r = rows) /\
+  (edge[r, c, 4] = 0 <-> c = 1)
+);
+
+% matching edges between neighbouring cells
+constraint forall(r in ROWS, c in 1..cols - 1)(edge[r, c, 2] = edge[r, c + 1, 4]);
+constraint forall(r in 1..rows - 1, c in COLS)(edge[r, c, 3] = edge[r + 1, c, 1]);
+
+constraint forall(i in CLUES)(
+  tile[clue_row[i], clue_col[i]] = clue_tile[i] /\
+  rotation[clue_row[i], clue_col[i]] = clue_rotation[i]
+);
+
+solve satisfy;
+
+% the solution as a clues file: tile, column, row and anti-clockwise rotation
+output [
+  show(tile[r, c]) ++ " " ++ show(c - 1) ++ " " ++ show(r - 1) ++ " " ++
+  show(rotation[r, c]) ++ "\n"
+  | r in ROWS, c in COLS
+];
+"#;
+
+/// A 0/1 integer program of the puzzle of filling the board with its tiles, extending the
+/// clues, in CPLEX LP format.
+///
+/// There is a binary variable `x_<tile>_<col>_<row>_<rotation>` for each placement of a tile
+/// in a cell that fits the border, which is 1 if the tile is placed there in that rotation.
+/// Rotations of a tile that look the same give one variable, and as in the [dlx](super::dlx)
+/// solver, only the clue placement has a variable in a clue cell, and a clue tile has none
+/// anywhere else.
+/// The constraints are:
+///
+/// * `cell_<col>_<row>`: each cell has exactly one placement;
+/// * `tile_<tile>`: each tile is placed at most once;
+/// * `east_<col>_<row>_<colour>` and `south_<col>_<row>_<colour>`: for each edge between two
+///   cells and each colour, the cell on one side shows the colour there exactly when the cell
+///   on the other side does.
+///
+/// There is no objective, as any feasible solution solves the puzzle.
+/// A cell with no placements at all, as when a clue tile isn't in the tileset, is asked to
+/// have one of an unused variable `empty`, which leaves the program infeasible.
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::solver::export::lp;
+///
+/// let spec = toy_3x3();
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let program = lp(&spec, &solution[..1]);
+/// assert!(program.starts_with("\\ An edge-matching puzzle"));
+/// assert!(program.contains("Subject To"));
+/// assert!(program.contains(" cell_1_1: "));
+/// assert!(program.trim_end().ends_with("End"));
+/// ```
+pub fn lp<E: Copy + Edge + PartialEq>(spec: &BoardSpec<E>, clues: &[Clue<E>]) -> String {
+    let shape = spec.dimensions;
+    let (colours, n_colours) = colours(spec);
+    let clue_tiles: Vec<_> = clues
+        .iter()
+        .filter_map(|clue| spec.tiles.identify(&clue.placed_tile()).map(|(id, _)| id))
+        .collect();
+
+    // the variables of each cell, in row-major order, with the colours they show
+    let mut cells: Vec<Vec<(String, [usize; 4])>> = Vec::new();
+    let mut tiles: Vec<Vec<String>> = vec![Vec::new(); spec.tiles.len()];
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let at = Indx { col, row };
+            let clue = clues.iter().find(|c| c.at == at);
+            let mut variables = Vec::new();
+            for (id, tile) in spec.tiles.ids().zip(&spec.tiles) {
+                let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+                for rotation in ROTATIONS {
+                    let placed = tile.rotate(rotation).apply();
+                    if seen.contains(&placed) {
+                        continue;
+                    }
+                    seen.push(placed);
+                    let allowed = match clue {
+                        Some(clue) => clue.placed_tile() == placed,
+                        None => !clue_tiles.contains(&id),
+                    };
+                    if allowed
+                        && SIDES
+                            .iter()
+                            .all(|s| placed[*s].is_border() == shape.on_border(at, *s))
+                    {
+                        let variable =
+                            format!("x_{}_{}_{}_{}", id.number(), col, row, rotation as usize);
+                        tiles[id.index()].push(variable.clone());
+                        variables.push((variable, colours[id.index()][rotation as usize]));
+                    }
+                }
+            }
+            cells.push(variables);
+        }
+    }
+
+    let mut program = String::new();
+    writeln!(program, "\\ An edge-matching puzzle, exported by e2rs.").unwrap();
+    writeln!(program, "Minimize").unwrap();
+    writeln!(program, "Subject To").unwrap();
+    let mut empty = false;
+    for (i, variables) in cells.iter().enumerate() {
+        let (col, row) = (i % shape.columns, i / shape.columns);
+        let name = format!("cell_{}_{}", col, row);
+        if variables.is_empty() {
+            empty = true;
+            writeln!(program, " {}: 0 empty = 1", name).unwrap();
+        } else {
+            let terms = variables.iter().map(|(v, _)| (true, v.as_str()));
+            constraint(&mut program, &name, terms, "= 1");
+        }
+    }
+    for (id, variables) in spec.tiles.ids().zip(&tiles) {
+        if !variables.is_empty() {
+            let terms = variables.iter().map(|v| (true, v.as_str()));
+            constraint(&mut program, &format!("tile_{}", id.number()), terms, "<= 1");
+        }
+    }
+    for i in 0..cells.len() {
+        let at = Indx {
+            col: i % shape.columns,
+            row: i / shape.columns,
+        };
+        for (side, name) in [(Side::East, "east"), (Side::South, "south")] {
+            if shape.on_border(at, side) {
+                continue;
+            }
+            let next = match side {
+                Side::East => i + 1,
+                _ => i + shape.columns,
+            };
+            for colour in 1..=n_colours {
+                let showing = |cell: usize, side: Side| {
+                    cells[cell]
+                        .iter()
+                        .filter(move |(_, shown)| shown[side as usize] == colour)
+                        .map(|(v, _)| v.as_str())
+                };
+                let mut terms = showing(i, side)
+                    .map(|v| (true, v))
+                    .chain(showing(next, side.flip()).map(|v| (false, v)))
+                    .peekable();
+                if terms.peek().is_some() {
+                    let name = format!("{}_{}_{}_{}", name, at.col, at.row, colour);
+                    constraint(&mut program, &name, terms, "= 0");
+                }
+            }
+        }
+    }
+
+    writeln!(program, "Binaries").unwrap();
+    let variables = cells.iter().flatten().map(|(v, _)| v.as_str());
+    let variables: Vec<&str> = variables.chain(empty.then_some("empty")).collect();
+    for line in variables.chunks(TERMS_PER_LINE) {
+        writeln!(program, " {}", line.join(" ")).unwrap();
+    }
+    writeln!(program, "End").unwrap();
+    program
+}
+
+/// The most terms written on one line of an LP file, which keeps lines well inside the 510
+/// characters CPLEX reads.
+const TERMS_PER_LINE: usize = 8;
+
+/// Write a constraint summing the variables, each added if true or subtracted if false, then
+/// comparing the sum as given.
+fn constraint<'a>(
+    program: &mut String,
+    name: &str,
+    terms: impl Iterator<Item = (bool, &'a str)>,
+    comparison: &str,
+) {
+    write!(program, " {}:", name).unwrap();
+    for (i, (add, variable)) in terms.enumerate() {
+        if i > 0 && i % TERMS_PER_LINE == 0 {
+            write!(program, "\n  ").unwrap();
+        }
+        let sign = match (add, i) {
+            (true, 0) => "",
+            (true, _) => "+ ",
+            (false, _) => "- ",
+        };
+        write!(program, " {}{}", sign, variable).unwrap();
+    }
+    writeln!(program, " {}", comparison).unwrap();
+}
+
+/// The colour of each side of each tile in each rotation, by tile index, rotation and side,
+/// with 0 for the border, and the number of colours other than the border.
+fn colours<E: Copy + Edge + PartialEq>(spec: &BoardSpec<E>) -> (Vec<[[usize; 4]; 4]>, usize) {
+    let mut palette: Vec<E> = Vec::new();
+    let mut colour = |edge: E| {
+        if edge.is_border() {
+            return 0;
+        }
+        match palette.iter().position(|e| *e == edge) {
+            Some(i) => i + 1,
+            None => {
+                palette.push(edge);
+                palette.len()
+            }
+        }
+    };
+    let colours = spec
+        .tiles
+        .into_iter()
+        .map(|tile| {
+            ROTATIONS.map(|rotation| {
+                let placed = tile.rotate(rotation).apply();
+                SIDES.map(|side| colour(placed[side]))
+            })
+        })
+        .collect();
+    (colours, palette.len())
+}
@@ -0,0 +1,87 @@
+use std::convert::Infallible;
+
+use crate::model::{Board, BoardSpec, Edge, Indx};
+
+use super::{candidates, Candidate};
+
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "gpu")]
+pub use gpu::*;
+
+/// A way to extend a partial board: an empty cell to fill, and the tiles already used.
+#[derive(Clone, Copy, Debug)]
+pub struct Extension<'a, E> {
+    /// The board.
+    pub board: &'a Board<E>,
+    /// Which tiles of the tileset are on the board, by [TileId::index](crate::model::TileId::index).
+    pub used: &'a [bool],
+    /// The empty cell to fill.
+    pub at: Indx,
+}
+
+/// A backend that finds the candidates for many extensions of a search frontier at once.
+///
+/// Finding candidates is the inner loop of every constructive solver, and a search that keeps
+/// a frontier of partial boards, such as a beam or breadth-first search, can hand a whole batch
+/// to a backend in one call.
+/// [CpuFilter] finds them one after another on the current thread, and, with the `gpu` feature,
+/// `GpuFilter` finds thousands at a time on the GPU; both give the same candidates, so solvers
+/// can swap one for the other.
+pub trait CandidateFilter<E> {
+    /// The error raised when the backend fails while filtering.
+    type Error;
+
+    /// The candidates for each extension, as by [Propagator::candidates](super::Propagator::candidates):
+    /// the unused tiles that fit the cell, in tileset order, in each distinct rotation that fits.
+    fn filter(
+        &mut self,
+        extensions: &[Extension<'_, E>],
+    ) -> Result<Vec<Vec<Candidate>>, Self::Error>;
+}
+
+/// A [CandidateFilter] checking every tile against every extension in turn.
+///
+/// ```
+/// use e2rs::examples::toy_3x3;
+/// use e2rs::model::Indx;
+/// use e2rs::solver::{CandidateFilter, CpuFilter, Extension};
+///
+/// let spec = toy_3x3();
+/// let board = spec.dimensions.new_board();
+/// let used = vec![false; spec.tiles.len()];
+/// let extend = |at| Extension { board: &board, used: &used, at };
+///
+/// let found = CpuFilter::new(&spec).filter(&[
+///     extend(Indx { col: 0, row: 0 }),
+///     extend(Indx { col: 1, row: 1 }),
+/// ]).unwrap();
+/// // each corner tile fits the corner in one rotation
+/// assert_eq!(found[0].len(), 4);
+/// assert!(found[1].iter().all(|c| !spec.tiles[..][c.tile.index()].is_edge()));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct CpuFilter<'a, E> {
+    spec: &'a BoardSpec<E>,
+}
+
+impl<'a, E> CpuFilter<'a, E> {
+    /// Filter the candidates from the tiles of this puzzle.
+    pub fn new(spec: &'a BoardSpec<E>) -> Self {
+        CpuFilter { spec }
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> CandidateFilter<E> for CpuFilter<'_, E> {
+    type Error = Infallible;
+
+    fn filter(
+        &mut self,
+        extensions: &[Extension<'_, E>],
+    ) -> Result<Vec<Vec<Candidate>>, Infallible> {
+        Ok(extensions
+            .iter()
+            .map(|x| candidates(self.spec, x.board, x.used, x.at))
+            .collect())
+    }
+}
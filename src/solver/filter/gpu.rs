@@ -0,0 +1,359 @@
+use std::borrow::Cow;
+use std::sync::mpsc;
+
+use thiserror::Error;
+use wgpu::util::DeviceExt;
+
+use crate::model::{BoardSpec, Edge, Rotate, Tile, ROTATIONS, SIDES};
+
+use super::super::Candidate;
+use super::{CandidateFilter, Extension};
+
+/// The shader testing every option against every extension, 32 options to an invocation.
+///
+/// Edges are numbered with 0 for the border, and each side of an extension needs either a
+/// given edge or, for an empty inside neighbour, any inside edge.
+const SHADER: &str = r#"
+struct Params {
+    options: u32,
+    option_words: u32,
+    tile_words: u32,
+    extensions: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+// the north, east, south and west edges of each option, then its tile
+@group(0) @binding(1) var<storage, read> options: array<u32>;
+@group(0) @binding(2) var<storage, read> needs: array<vec4<u32>>;
+@group(0) @binding(3) var<storage, read> used: array<u32>;
+@group(0) @binding(4) var<storage, read_write> fits: array<u32>;
+
+const ANY_INSIDE: u32 = 0xffffffffu;
+
+fn side_fits(edge: u32, need: u32) -> bool {
+    if (need == ANY_INSIDE) {
+        return edge != 0u;
+    }
+    return edge == need;
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.extensions * params.option_words) {
+        return;
+    }
+    let x = index / params.option_words;
+    let word = index % params.option_words;
+    let need = needs[x];
+    var mask = 0u;
+    for (var bit = 0u; bit < 32u; bit++) {
+        let o = word * 32u + bit;
+        if (o >= params.options) {
+            break;
+        }
+        let tile = options[o * 5u + 4u];
+        let taken = (used[x * params.tile_words + tile / 32u] >> (tile % 32u)) & 1u;
+        if (taken == 0u
+            && side_fits(options[o * 5u], need.x)
+            && side_fits(options[o * 5u + 1u], need.y)
+            && side_fits(options[o * 5u + 2u], need.z)
+            && side_fits(options[o * 5u + 3u], need.w)) {
+            mask |= 1u << bit;
+        }
+    }
+    fits[index] = mask;
+}
+"#;
+
+/// What an extension needs on a side away from the border with an empty neighbour.
+const ANY_INSIDE: u32 = u32::MAX;
+/// What an extension needs on a side facing an edge no option has, which nothing fits.
+const NOTHING: u32 = u32::MAX - 1;
+/// The number of invocations in a workgroup, as in the shader.
+const WORKGROUP: u32 = 64;
+
+/// Errors raised while setting up or using the GPU.
+#[derive(Debug, Error)]
+pub enum GpuError {
+    /// No GPU adapter was found.
+    #[error("no GPU adapter is available")]
+    NoAdapter,
+    /// The adapter would not give a device.
+    #[error("the GPU device could not be opened: {0}")]
+    Device(#[from] wgpu::RequestDeviceError),
+    /// The device was lost before the results came back.
+    #[error("the GPU device was lost")]
+    DeviceLost,
+    /// The results could not be read back from the GPU.
+    #[error("the GPU results could not be read: {0}")]
+    Read(#[from] wgpu::BufferAsyncError),
+}
+
+/// A [CandidateFilter] testing a batch of extensions on the GPU with a wgpu compute shader.
+///
+/// Every distinct orientation of every tile is uploaded once, when the filter is made, with
+/// its edges numbered.
+/// Each call to [CandidateFilter::filter] then uploads what each extension needs on each side
+/// of its cell and which tiles it has used, and tests every orientation against every
+/// extension in one dispatch, so the more extensions in a batch, the better the GPU pays off.
+/// Very large batches are split over several dispatches.
+///
+/// Filtering fails if the GPU device is lost or its results can't be read back.
+/// This needs the `gpu` feature.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::model::Indx;
+/// use e2rs::solver::{CandidateFilter, CpuFilter, Extension, GpuFilter};
+///
+/// let spec = toy_4x4();
+/// let Ok(mut gpu) = GpuFilter::new(&spec) else {
+///     // there is no GPU to compare with here
+///     return;
+/// };
+/// let board = spec.dimensions.new_board();
+/// let used = vec![false; spec.tiles.len()];
+/// let extensions: Vec<_> = (0..4)
+///     .flat_map(|row| (0..4).map(move |col| Indx { col, row }))
+///     .map(|at| Extension { board: &board, used: &used, at })
+///     .collect();
+/// let cpu = CpuFilter::new(&spec).filter(&extensions).unwrap();
+/// assert_eq!(gpu.filter(&extensions).unwrap(), cpu);
+/// ```
+#[derive(Debug)]
+pub struct GpuFilter<E> {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    options_buffer: wgpu::Buffer,
+    /// The placement each option stands for.
+    options: Vec<Candidate>,
+    /// The inside edges, numbered from 1 by their position here.
+    colours: Vec<E>,
+    tiles: usize,
+}
+
+impl<E: Copy + Edge + PartialEq> GpuFilter<E> {
+    /// Open the GPU and upload the tiles of this puzzle.
+    pub fn new(spec: &BoardSpec<E>) -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+        let adapter = futures_lite::future::block_on(instance.request_adapter(
+            &wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            },
+        ))
+        .ok_or(GpuError::NoAdapter)?;
+        let (device, queue) = futures_lite::future::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("e2rs candidate filter"),
+                required_features: wgpu::Features::empty(),
+                required_limits:
+                    wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+            },
+            None,
+        ))?;
+
+        let mut colours: Vec<E> = Vec::new();
+        for tile in &spec.tiles {
+            for side in SIDES {
+                if !tile[side].is_border() && !colours.contains(&tile[side]) {
+                    colours.push(tile[side]);
+                }
+            }
+        }
+
+        let mut options = Vec::new();
+        let mut table = Vec::new();
+        for (id, tile) in spec.tiles.ids().zip(&spec.tiles) {
+            let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+            for rotation in ROTATIONS {
+                let placed = tile.rotate(rotation).apply();
+                if seen.contains(&placed) {
+                    continue;
+                }
+                seen.push(placed);
+                options.push(Candidate { tile: id, rotation });
+                table.extend(SIDES.map(|side| number(&colours, placed[side])));
+                table.push(id.index() as u32);
+            }
+        }
+        let options_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("options"),
+            contents: &bytes(&table),
+            usage: wgpu::BufferUsages::STORAGE,
+        });
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("candidate filter"),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(SHADER)),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("candidate filter"),
+            layout: None,
+            module: &module,
+            entry_point: "main",
+            compilation_options: Default::default(),
+        });
+
+        Ok(GpuFilter {
+            device,
+            queue,
+            pipeline,
+            options_buffer,
+            options,
+            colours,
+            tiles: spec.tiles.len(),
+        })
+    }
+
+    /// Test one batch of extensions, small enough for a single dispatch.
+    fn dispatch(&self, extensions: &[Extension<'_, E>]) -> Result<Vec<Vec<Candidate>>, GpuError> {
+        let option_words = self.options.len().div_ceil(32);
+        let tile_words = self.tiles.div_ceil(32).max(1);
+
+        let mut needs = Vec::with_capacity(extensions.len() * 4);
+        let mut used = vec![0u32; extensions.len() * tile_words];
+        for (i, x) in extensions.iter().enumerate() {
+            let shape = x.board.shape();
+            for side in SIDES {
+                needs.push(match shape.neighbour(x.at, side) {
+                    None => 0,
+                    Some(next) => match &x.board[next] {
+                        None => ANY_INSIDE,
+                        Some(tile) if tile[side.flip()].is_border() => NOTHING,
+                        Some(tile) => number(&self.colours, tile[side.flip()]),
+                    },
+                });
+            }
+            for (tile, _) in x.used.iter().enumerate().filter(|(_, used)| **used) {
+                used[i * tile_words + tile / 32] |= 1 << (tile % 32);
+            }
+        }
+
+        let words = extensions.len() * option_words;
+        let params = [
+            self.options.len() as u32,
+            option_words as u32,
+            tile_words as u32,
+            extensions.len() as u32,
+        ];
+        let init = |label, contents: &[u32], usage| {
+            self.device
+                .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some(label),
+                    contents: &bytes(contents),
+                    usage,
+                })
+        };
+        let params = init("params", &params, wgpu::BufferUsages::UNIFORM);
+        let needs = init("needs", &needs, wgpu::BufferUsages::STORAGE);
+        let used = init("used", &used, wgpu::BufferUsages::STORAGE);
+        let size = (words.max(1) * 4) as u64;
+        let fits = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fits"),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("staging"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let bindings = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("candidate filter"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &[
+                (0, &params),
+                (1, &self.options_buffer),
+                (2, &needs),
+                (3, &used),
+                (4, &fits),
+            ]
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding,
+                resource: buffer.as_entire_binding(),
+            }),
+        });
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("candidate filter"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &bindings, &[]);
+            pass.dispatch_workgroups((words as u32).div_ceil(WORKGROUP), 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&fits, 0, &staging, 0, size);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |mapped| {
+            let _ = sender.send(mapped);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().map_err(|_| GpuError::DeviceLost)??;
+        let masks: Vec<u32> = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        staging.unmap();
+
+        Ok(masks[..words]
+            .chunks(option_words)
+            .map(|masks| {
+                self.options
+                    .iter()
+                    .enumerate()
+                    .filter(|(o, _)| masks[o / 32] & (1 << (o % 32)) != 0)
+                    .map(|(_, option)| *option)
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> CandidateFilter<E> for GpuFilter<E> {
+    type Error = GpuError;
+
+    fn filter(&mut self, extensions: &[Extension<'_, E>]) -> Result<Vec<Vec<Candidate>>, GpuError> {
+        let option_words = self.options.len().div_ceil(32).max(1);
+        let limits = self.device.limits();
+        let invocations = limits.max_compute_workgroups_per_dimension as usize * WORKGROUP as usize;
+        let storage = limits.max_storage_buffer_binding_size as usize / 4;
+        let batch = (invocations.min(storage) / option_words).max(1);
+        let mut found = Vec::with_capacity(extensions.len());
+        for batch in extensions.chunks(batch) {
+            found.extend(self.dispatch(batch)?);
+        }
+        Ok(found)
+    }
+}
+
+/// The number of an edge in the shader: 0 for the border, and from 1 for the inside edges.
+fn number<E: Edge + PartialEq>(colours: &[E], edge: E) -> u32 {
+    if edge.is_border() {
+        return 0;
+    }
+    colours
+        .iter()
+        .position(|c| *c == edge)
+        .map_or(NOTHING, |i| i as u32 + 1)
+}
+
+/// Words as little-endian bytes, padded so that no buffer is empty.
+fn bytes(words: &[u32]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    if bytes.is_empty() {
+        bytes.resize(4, 0);
+    }
+    bytes
+}
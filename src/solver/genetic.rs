@@ -0,0 +1,467 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::error::PlacementError;
+use crate::model::{
+    Board, BoardShape, BoardSpec, Clue, Edge, Indx, Rotate, Rotation, Side, Tile, TileId,
+    ROTATIONS, SIDES,
+};
+
+use super::{Budget, Move, Neighbourhood, NoRecords, Record, RecordHook, SearchStats};
+
+/// Settings for [evolve].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneticConfig {
+    /// The number of individuals in each generation.
+    pub population: usize,
+    /// The number of generations to breed.
+    pub generations: usize,
+    /// The number of individuals taking part in each tournament to choose a parent.
+    pub tournament: usize,
+    /// The number of the best individuals carried over unchanged into the next generation.
+    pub elites: usize,
+    /// The chance, from 0 to 1, that a child is bred by crossover rather than copied from one
+    /// parent.
+    pub crossover_rate: f64,
+    /// The chance, from 0 to 1, that each child is mutated.
+    pub mutation_rate: f64,
+    /// The number of swaps or turns made by each mutation.
+    pub mutations: usize,
+    /// Limits that end the run before all the generations are bred.
+    ///
+    /// Nodes are children bred, and there is no backtracking.
+    /// The limits are checked between generations, so the last generation is always finished.
+    pub budget: Budget,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            population: 100,
+            generations: 200,
+            tournament: 3,
+            elites: 2,
+            crossover_rate: 0.9,
+            mutation_rate: 0.5,
+            mutations: 2,
+            budget: Budget::default(),
+        }
+    }
+}
+
+/// An individual of the genetic algorithm: the tile in every cell, and how it is turned.
+///
+/// Both are given in row-major cell order, so the tiles are a permutation of the tiles placed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Genome {
+    /// The tile in each cell.
+    pub tiles: Vec<TileId>,
+    /// The rotation of the tile in each cell.
+    pub rotations: Vec<Rotation>,
+}
+
+impl Genome {
+    /// Place the tiles on a board.
+    pub fn to_board<E: Copy>(&self, spec: &BoardSpec<E>) -> Board<E> {
+        let mut board = spec.dimensions.new_board();
+        for (i, cell) in board.cells_mut().map(|(_, cell)| cell).enumerate() {
+            let tile = &spec.tiles[..][self.tiles[i].index()];
+            *cell = Some(tile.rotate(self.rotations[i]).apply());
+        }
+        board
+    }
+
+    /// Make a move on the individual, as on a board with this many columns.
+    pub fn apply(&mut self, found: &Move, columns: usize) {
+        let cell = |at: Indx| at.col + at.row * columns;
+        let moved: Vec<(TileId, Rotation)> = found
+            .steps
+            .iter()
+            .map(|step| {
+                let from = cell(step.from);
+                (self.tiles[from], self.rotations[from] + step.turn)
+            })
+            .collect();
+        for (step, (tile, rotation)) in found.steps.iter().zip(moved) {
+            self.tiles[cell(step.to)] = tile;
+            self.rotations[cell(step.to)] = rotation;
+        }
+    }
+}
+
+/// The result of [evolve].
+#[derive(Clone, Debug)]
+pub struct Evolved<E> {
+    /// The best individual bred.
+    pub best: Genome,
+    /// The best individual, placed on a board.
+    pub board: Board<E>,
+    /// The score of the best individual, as by [Board::weighted_score].
+    pub score: usize,
+    /// The best score in each generation, starting with the first, random one.
+    pub history: Vec<usize>,
+    /// What the algorithm did, counting each child bred as a node.
+    pub stats: SearchStats,
+}
+
+/// Search for a solution with a genetic algorithm.
+///
+/// Each individual is a [Genome], and its fitness is its score, with each pair of facing edges
+/// scored by [Edge::match_weight].
+/// Individuals only ever put a tile in a cell with as many border sides as the tile has outside
+/// edges, turned to face them outwards, and never change the clue cells, so the search is over
+/// arrangements that are right at the border and differ only in how well they match inside.
+///
+/// The first generation is random.
+/// Each later generation keeps the elites of the last, and breeds the rest from parents chosen
+/// by tournament.
+/// Crossover copies a random rectangle of cells from one parent, then takes the other parent's
+/// tile wherever that tile isn't already used, and fills the cells left over with the tiles
+/// left over, so that patches of matched tiles survive from both parents.
+/// Mutation makes random moves from a [Neighbourhood], swapping the tiles in two cells of the
+/// same kind, or turning an inside tile.
+///
+/// Clues that don't fit the board are reported as by [BoardSpec::validate_clues].
+/// If there are more tiles than cells of some kind, the extra tiles are left out, and if
+/// there are fewer, the first cell that can't be filled is reported.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use e2rs::examples::{toy_4x4, SOLUTION};
+/// use e2rs::solver::{evolve, GeneticConfig};
+///
+/// let spec = toy_4x4();
+/// let clue = spec.clue_set(SOLUTION).unwrap().clues[5];
+/// let config = GeneticConfig { generations: 50, ..GeneticConfig::default() };
+/// let evolved = evolve(&config, &spec, &[clue], &mut StdRng::seed_from_u64(3)).unwrap();
+///
+/// assert_eq!(evolved.board[clue.at], Some(clue.placed_tile()));
+/// assert_eq!(evolved.score, evolved.board.score());
+/// assert!(evolved.score >= evolved.history[0]);
+/// ```
+pub fn evolve<E, R>(
+    config: &GeneticConfig,
+    spec: &BoardSpec<E>,
+    clues: &[Clue<E>],
+    rng: &mut R,
+) -> Result<Evolved<E>, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+    R: Rng,
+{
+    evolve_with_hook(config, spec, clues, rng, &mut NoRecords)
+}
+
+/// Search for a solution as for [evolve], telling the hook of the best individual of the first
+/// generation and of each later generation whose best beats every one before.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{evolve_with_hook, GeneticConfig, Record};
+///
+/// let spec = toy_4x4();
+/// let config = GeneticConfig { generations: 50, ..GeneticConfig::default() };
+/// let mut records: Vec<Record<u8>> = Vec::new();
+/// let mut rng = StdRng::seed_from_u64(3);
+/// let evolved = evolve_with_hook(&config, &spec, &[], &mut rng, &mut |record| {
+///     records.push(record)
+/// })
+/// .unwrap();
+///
+/// assert_eq!(records[0].score, evolved.history[0]);
+/// assert_eq!(records.last().unwrap().score, evolved.score);
+/// ```
+pub fn evolve_with_hook<E, R>(
+    config: &GeneticConfig,
+    spec: &BoardSpec<E>,
+    clues: &[Clue<E>],
+    rng: &mut R,
+    hook: &mut dyn RecordHook<E>,
+) -> Result<Evolved<E>, PlacementError>
+where
+    E: Copy + Edge + PartialEq,
+    R: Rng,
+{
+    let problem = Problem::new(spec, clues)?;
+    let size = config.population.max(1);
+
+    let mut population: Vec<(usize, Genome)> = Vec::with_capacity(size);
+    for _ in 0..size {
+        let genome = problem.random(rng)?;
+        population.push((problem.fitness(&genome), genome));
+    }
+    population.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    let mut history = vec![population[0].0];
+    let mut stats = SearchStats::default();
+    let meter = config.budget.start();
+    let mut record = population[0].0;
+    hook.record(Record::new(&population[0].1.to_board(spec), 0));
+
+    for _ in 0..config.generations {
+        if meter.exhausted(&stats) {
+            break;
+        }
+        let mut next: Vec<(usize, Genome)> = population
+            .iter()
+            .take(config.elites.min(size))
+            .cloned()
+            .collect();
+        while next.len() < size {
+            let a = tournament(&population, config.tournament, rng);
+            let mut child = if rng.gen_bool(config.crossover_rate.clamp(0.0, 1.0)) {
+                let b = tournament(&population, config.tournament, rng);
+                problem.crossover(a, b, rng)
+            } else {
+                a.clone()
+            };
+            if rng.gen_bool(config.mutation_rate.clamp(0.0, 1.0)) {
+                for _ in 0..config.mutations {
+                    problem.mutate(&mut child, rng);
+                }
+            }
+            stats.nodes += 1;
+            next.push((problem.fitness(&child), child));
+        }
+        next.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        population = next;
+        history.push(population[0].0);
+        if population[0].0 > record {
+            record = population[0].0;
+            hook.record(Record::new(&population[0].1.to_board(spec), stats.nodes));
+        }
+    }
+
+    let (score, best) = population.swap_remove(0);
+    Ok(Evolved {
+        board: best.to_board(spec),
+        best,
+        score,
+        history,
+        stats,
+    })
+}
+
+/// The best of a few individuals chosen at random.
+fn tournament<'a, R: Rng>(
+    population: &'a [(usize, Genome)],
+    size: usize,
+    rng: &mut R,
+) -> &'a Genome {
+    let mut best = population.choose(rng).unwrap();
+    for _ in 1..size {
+        let other = population.choose(rng).unwrap();
+        if other.0 > best.0 {
+            best = other;
+        }
+    }
+    &best.1
+}
+
+/// What the genetic operators need to know about a puzzle.
+struct Problem<'a, E> {
+    spec: &'a BoardSpec<E>,
+    shape: BoardShape,
+    /// Every tile in every rotation, by tile index and then rotation.
+    turned: Vec<[Tile<E>; 4]>,
+    /// The clue in each cell, if any.
+    locked: Vec<Option<(TileId, Rotation)>>,
+    /// The cells without clues, grouped by their number of border sides.
+    kinds: Vec<Vec<usize>>,
+    /// The tiles not used by clues, grouped by their number of outside edges.
+    pools: Vec<Vec<TileId>>,
+    /// The moves mutation makes.
+    moves: Neighbourhood,
+}
+
+impl<'a, E: Copy + Edge + PartialEq> Problem<'a, E> {
+    fn new(spec: &'a BoardSpec<E>, clues: &[Clue<E>]) -> Result<Self, PlacementError> {
+        spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+        let shape = spec.dimensions;
+        let cells = shape.columns * shape.rows;
+
+        let mut locked = vec![None; cells];
+        let mut used = vec![false; spec.tiles.len()];
+        for clue in clues {
+            let found = spec
+                .tiles
+                .identify(&clue.placed_tile())
+                .ok_or(PlacementError::UnknownTile(clue.at))?;
+            used[found.0.index()] = true;
+            locked[clue.at.col + clue.at.row * shape.columns] = Some(found);
+        }
+
+        let mut kinds = vec![Vec::new(); 5];
+        for (cell, lock) in locked.iter().enumerate() {
+            if lock.is_none() {
+                let at = Indx::from_position(cell, shape.columns);
+                kinds[SIDES.iter().filter(|s| shape.on_border(at, **s)).count()].push(cell);
+            }
+        }
+        let mut pools = vec![Vec::new(); 5];
+        for (id, tile) in spec.tiles.ids().zip(&spec.tiles) {
+            if !used[id.index()] {
+                pools[SIDES.iter().filter(|s| tile[**s].is_border()).count()].push(id);
+            }
+        }
+        for (kind, cells) in kinds.iter().enumerate() {
+            if let Some(cell) = cells.get(pools[kind].len()) {
+                return Err(PlacementError::NoTileFor(Indx::from_position(
+                    *cell,
+                    shape.columns,
+                )));
+            }
+        }
+
+        let turned = spec.tiles[..]
+            .iter()
+            .map(|tile| ROTATIONS.map(|r| tile.rotate(r).apply()))
+            .collect();
+
+        Ok(Problem {
+            spec,
+            shape,
+            turned,
+            locked,
+            kinds,
+            pools,
+            moves: Neighbourhood::new(shape, &clues.iter().map(|c| c.at).collect::<Vec<_>>()),
+        })
+    }
+
+    /// A random individual.
+    fn random<R: Rng>(&self, rng: &mut R) -> Result<Genome, PlacementError> {
+        let mut tiles = vec![None; self.locked.len()];
+        for (kind, cells) in self.kinds.iter().enumerate() {
+            let mut pool = self.pools[kind].clone();
+            pool.shuffle(rng);
+            for (cell, tile) in cells.iter().zip(pool) {
+                tiles[*cell] = Some(tile);
+            }
+        }
+        self.complete(tiles, vec![None; self.locked.len()], rng)
+    }
+
+    /// Fill in the clues and the rotations of a partly chosen individual.
+    ///
+    /// Every cell without a clue must have a tile, and tiles without a rotation, or with one
+    /// that doesn't face the border, are turned to one at random that does.
+    fn complete<R: Rng>(
+        &self,
+        tiles: Vec<Option<TileId>>,
+        rotations: Vec<Option<Rotation>>,
+        rng: &mut R,
+    ) -> Result<Genome, PlacementError> {
+        let mut genome = Genome {
+            tiles: Vec::with_capacity(tiles.len()),
+            rotations: Vec::with_capacity(tiles.len()),
+        };
+        for (cell, (tile, rotation)) in tiles.into_iter().zip(rotations).enumerate() {
+            let at = Indx::from_position(cell, self.shape.columns);
+            let (tile, rotation) = match (self.locked[cell], tile) {
+                (Some(clue), _) => clue,
+                (None, Some(tile)) => {
+                    let fitting = self.fitting(at, tile);
+                    let rotation = match rotation {
+                        Some(r) if fitting.contains(&r) => r,
+                        _ => *fitting.choose(rng).ok_or(PlacementError::NoTileFor(at))?,
+                    };
+                    (tile, rotation)
+                }
+                (None, None) => return Err(PlacementError::NoTileFor(at)),
+            };
+            genome.tiles.push(tile);
+            genome.rotations.push(rotation);
+        }
+        Ok(genome)
+    }
+
+    /// The rotations of a tile that face its outside edges onto the border around a cell.
+    fn fitting(&self, at: Indx, tile: TileId) -> Vec<Rotation> {
+        let turned = &self.turned[tile.index()];
+        ROTATIONS
+            .into_iter()
+            .filter(|r| {
+                SIDES
+                    .iter()
+                    .all(|s| turned[*r as usize][*s].is_border() == self.shape.on_border(at, *s))
+            })
+            .collect()
+    }
+
+    /// The score of an individual, read straight from the genome.
+    fn fitness(&self, genome: &Genome) -> usize {
+        let BoardShape { columns, rows } = self.shape;
+        let placed =
+            |cell: usize| &self.turned[genome.tiles[cell].index()][genome.rotations[cell] as usize];
+        let mut score = 0;
+        for row in 0..rows {
+            for col in 0..columns {
+                let cell = col + row * columns;
+                let tile = placed(cell);
+                if col + 1 < columns {
+                    score += tile[Side::East].match_weight(&placed(cell + 1)[Side::West]) as usize;
+                }
+                if row + 1 < rows {
+                    let below = placed(cell + columns);
+                    score += tile[Side::South].match_weight(&below[Side::North]) as usize;
+                }
+            }
+        }
+        score
+    }
+
+    /// A child taking a rectangle of cells from one parent and the rest, where it can, from
+    /// the other.
+    fn crossover<R: Rng>(&self, a: &Genome, b: &Genome, rng: &mut R) -> Genome {
+        let BoardShape { columns, rows } = self.shape;
+        let span = |size: usize, rng: &mut R| {
+            let start = rng.gen_range(0..size);
+            start..rng.gen_range(start..size) + 1
+        };
+        let (across, down) = (span(columns, rng), span(rows, rng));
+
+        let cells = self.locked.len();
+        let mut tiles = vec![None; cells];
+        let mut rotations = vec![None; cells];
+        let mut used = vec![false; self.spec.tiles.len()];
+        for cell in (0..cells).filter(|c| self.locked[*c].is_none()) {
+            let at = Indx::from_position(cell, columns);
+            if across.contains(&at.col) && down.contains(&at.row) {
+                tiles[cell] = Some(a.tiles[cell]);
+                rotations[cell] = Some(a.rotations[cell]);
+                used[a.tiles[cell].index()] = true;
+            }
+        }
+        for cell in (0..cells).filter(|c| self.locked[*c].is_none()) {
+            let tile = b.tiles[cell];
+            if tiles[cell].is_none() && !used[tile.index()] {
+                tiles[cell] = Some(tile);
+                rotations[cell] = Some(b.rotations[cell]);
+                used[tile.index()] = true;
+            }
+        }
+        for (kind, cells) in self.kinds.iter().enumerate() {
+            let mut left = self.pools[kind].iter().filter(|t| !used[t.index()]);
+            for cell in cells {
+                if tiles[*cell].is_none() {
+                    tiles[*cell] = left.next().copied();
+                }
+            }
+        }
+
+        // both parents keep every tile in a cell of its kind, so the tiles left over fill the
+        // cells left over
+        self.complete(tiles, rotations, rng)
+            .expect("crossover keeps tiles in cells of their kind")
+    }
+
+    /// Swap the tiles in two cells of the same kind, or turn an inside tile.
+    fn mutate<R: Rng>(&self, genome: &mut Genome, rng: &mut R) {
+        if let Some(found) = self.moves.random(rng) {
+            genome.apply(&found, self.shape.columns);
+        }
+    }
+}
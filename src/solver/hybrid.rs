@@ -0,0 +1,217 @@
+use rand::Rng;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge, Indx, SIDES};
+
+use super::{
+    local_score, prefix_clues, random_completion, Annealer, Backtracker, Budget, CellOrdering,
+    Geometric, Natural, NoRecords, Record, RecordHook, RowMajor, Schedule, SearchStats,
+    SolveOutcome, ValueOrdering,
+};
+
+/// A solver that takes turns between backtracking and simulated annealing.
+///
+/// Backtracking places tiles that match exactly, but gets stuck long before it fills a large
+/// board, while annealing fills the board, but only matches edges here and there.
+/// Each round of this solver backtracks from the clues and the tiles kept from the round
+/// before, until the backtracker's budget runs out, and fills the cells its deepest placement
+/// left empty at random, as [Annealer::anneal] does.
+/// It then anneals the whole board with [Annealer::refine_with_hook], never moving the clues,
+/// and keeps the tiles of the best board reached that match on every side, for the next round
+/// to extend.
+/// If the kept tiles can't be extended at all, the round after starts again from the clues.
+///
+/// A round that backtracks from the clues alone to the end proves there is no solution.
+/// The solver's own budget limits all the rounds together, and is checked between rounds.
+/// If every round is used up first, the outcome is [SolveOutcome::BudgetExhausted] with the
+/// highest-scoring board of any round.
+/// Nodes are the placements tried by backtracking and the moves tried by annealing, added up.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use e2rs::examples::{toy_4x4, SOLUTION};
+/// use e2rs::solver::{Annealer, Backtracker, Hybrid};
+///
+/// let spec = toy_4x4();
+/// let clue = spec.clue_set(SOLUTION).unwrap().clues[5];
+/// let annealer = Annealer { iterations: 1000, ..Annealer::default() };
+/// let mut hybrid = Hybrid::new(Backtracker::new().with_node_limit(10), annealer, 20);
+/// let outcome = hybrid.solve(&spec, &[clue], &mut StdRng::seed_from_u64(1)).unwrap();
+///
+/// let board = outcome.solution().unwrap();
+/// assert!(spec.verify(board, 0).is_ok());
+/// assert_eq!(board[clue.at], Some(clue.placed_tile()));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Hybrid<C = RowMajor, V = Natural, S = Geometric> {
+    backtracker: Backtracker<C, V>,
+    annealer: Annealer<S>,
+    rounds: usize,
+    budget: Budget,
+}
+
+impl<C, V, S> Hybrid<C, V, S> {
+    /// Take up to `rounds` turns with the backtracker and the annealer.
+    ///
+    /// The backtracker's budget limits each round of backtracking, so it should be given one
+    /// for any puzzle it can't search to the end.
+    pub fn new(backtracker: Backtracker<C, V>, annealer: Annealer<S>, rounds: usize) -> Self {
+        Hybrid {
+            backtracker,
+            annealer,
+            rounds,
+            budget: Budget::default(),
+        }
+    }
+
+    /// Give up when any limit of the budget is reached by all the rounds together.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Search for a solution extending the clues.
+    ///
+    /// Clues that don't fit the board are reported as by [BoardSpec::validate_clues], and
+    /// boards that can't be filled as by [Annealer::anneal].
+    pub fn solve<E, R>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        rng: &mut R,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+        S: Schedule,
+        R: Rng,
+    {
+        self.solve_with_hook(spec, clues, rng, &mut NoRecords)
+    }
+
+    /// Search for a solution as for [Hybrid::solve], telling the hook each time a round
+    /// anneals a board to a higher score than any round before, with the nodes of all the
+    /// rounds so far.
+    pub fn solve_with_hook<E, R>(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        rng: &mut R,
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError>
+    where
+        E: Copy + Edge + PartialEq,
+        C: CellOrdering<E>,
+        V: ValueOrdering<E>,
+        S: Schedule,
+        R: Rng,
+    {
+        spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+        let locked: Vec<Indx> = clues.iter().map(|clue| clue.at).collect();
+        let meter = self.budget.start();
+        let mut stats = SearchStats::default();
+        let mut best: Option<(Board<E>, usize)> = None;
+        let mut recorded: Option<usize> = None;
+        let mut start = clues.to_vec();
+
+        for _ in 0..self.rounds {
+            if meter.exhausted(&stats) {
+                break;
+            }
+            let outcome = self.backtracker.solve(spec, &start)?;
+            stats.nodes += outcome.stats().nodes;
+            stats.backtracks += outcome.stats().backtracks;
+            let partial = match outcome {
+                SolveOutcome::Solved { board, .. } => {
+                    stats.solutions = 1;
+                    hook.record(Record::new(&board, stats.nodes));
+                    return Ok(SolveOutcome::Solved { board, stats });
+                }
+                SolveOutcome::NoSolution { .. } if start.len() == clues.len() => {
+                    stats.complete = true;
+                    return Ok(SolveOutcome::NoSolution { stats });
+                }
+                SolveOutcome::NoSolution { .. } => {
+                    start = clues.to_vec();
+                    continue;
+                }
+                SolveOutcome::BudgetExhausted { best_partial, .. } => best_partial,
+            };
+
+            let board = random_completion(spec, partial, rng)?;
+            let offset = stats.nodes;
+            let refined =
+                self.annealer
+                    .refine_with_hook(board, &locked, rng, &mut |record: Record<E>| {
+                        if recorded.is_none_or(|score| record.score > score) {
+                            recorded = Some(record.score);
+                            hook.record(Record {
+                                nodes: offset + record.nodes,
+                                ..record
+                            });
+                        }
+                    });
+            stats.nodes += refined.stats.nodes;
+
+            if spec.verify(&refined.best, 0).is_ok() {
+                stats.solutions = 1;
+                return Ok(SolveOutcome::Solved {
+                    board: refined.best,
+                    stats,
+                });
+            }
+            start = kept(spec, &refined.best, clues);
+            if best
+                .as_ref()
+                .is_none_or(|(_, score)| refined.best_score > *score)
+            {
+                best = Some((refined.best, refined.best_score));
+            }
+        }
+
+        let best_partial = match best {
+            Some((board, _)) => board,
+            None => {
+                let mut board = spec.dimensions.new_board();
+                for clue in clues {
+                    board[clue.at] = Some(clue.placed_tile());
+                }
+                board
+            }
+        };
+        Ok(SolveOutcome::BudgetExhausted {
+            best_partial,
+            stats,
+        })
+    }
+}
+
+/// The clues, and the other tiles of a full board that score against their neighbours as
+/// much as matching on every side would, as clues.
+fn kept<E: Copy + Edge + PartialEq>(
+    spec: &BoardSpec<E>,
+    board: &Board<E>,
+    clues: &[Clue<E>],
+) -> Vec<Clue<E>> {
+    let shape = board.shape();
+    let mut matched = shape.new_board();
+    for (at, tile) in board.clone() {
+        let Some(placed) = tile else {
+            continue;
+        };
+        let clue = clues.iter().any(|c| c.at == at);
+        let full: usize = SIDES
+            .iter()
+            .filter(|side| !shape.on_border(at, **side))
+            .map(|side| placed[*side].match_weight(&placed[*side]) as usize)
+            .sum();
+        if !clue && local_score(shape, &[at], |a| board[a]) >= full {
+            matched[at] = tile;
+        }
+    }
+    let mut kept = clues.to_vec();
+    kept.extend(prefix_clues(spec, &matched));
+    kept
+}
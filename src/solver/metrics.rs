@@ -0,0 +1,82 @@
+use std::fmt;
+use std::time::Duration;
+
+use super::SearchStats;
+
+/// Measurements of a solver run, as given by [Solver::solve_with_stats], for comparing
+/// strategies.
+///
+/// Every solver fills in the node and backtrack counts of its [SearchStats], the time taken and
+/// the deepest placement it reported as a record.
+/// The per-depth counts and the propagation calls are only measured by solvers that keep them,
+/// such as the [Backtracker], and are left empty and zero by the others.
+///
+/// [Solver::solve_with_stats]: super::Solver::solve_with_stats
+/// [Backtracker]: super::Backtracker
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Backtracker, Solver};
+///
+/// let spec = toy_4x4();
+/// let (outcome, stats) = Backtracker::new().solve_with_stats(&spec, &[]).unwrap();
+/// assert!(outcome.solution().is_some());
+/// assert_eq!(stats.nodes, outcome.stats().nodes);
+/// assert_eq!(stats.max_depth, 16);
+/// assert_eq!(stats.depth_nodes.iter().sum::<u64>(), stats.nodes);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    /// The number of nodes the solver tried.
+    pub nodes: u64,
+    /// The number of times the solver backtracked.
+    pub backtracks: u64,
+    /// The most tiles beyond the clues the solver had placed at once.
+    pub max_depth: usize,
+    /// The nodes tried at each depth, indexed by the number of tiles beyond the clues already
+    /// placed.
+    pub depth_nodes: Vec<u64>,
+    /// The number of times a placement was passed to forward checking or edge counting.
+    pub propagations: u64,
+    /// How long the solver ran for.
+    pub elapsed: Duration,
+}
+
+impl SolveStats {
+    /// The stats of a run with these search stats, taking this long.
+    pub(crate) fn new(stats: &SearchStats, elapsed: Duration) -> Self {
+        SolveStats {
+            nodes: stats.nodes,
+            backtracks: stats.backtracks,
+            elapsed,
+            ..SolveStats::default()
+        }
+    }
+
+    /// Count a node tried at this depth.
+    pub(crate) fn expand(&mut self, depth: usize) {
+        if self.depth_nodes.len() <= depth {
+            self.depth_nodes.resize(depth + 1, 0);
+        }
+        self.depth_nodes[depth] += 1;
+    }
+
+    /// The nodes tried per second.
+    pub fn nodes_per_second(&self) -> f64 {
+        self.nodes as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for SolveStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "nodes:        {}", self.nodes)?;
+        writeln!(f, "backtracks:   {}", self.backtracks)?;
+        writeln!(f, "max depth:    {}", self.max_depth)?;
+        writeln!(f, "propagations: {}", self.propagations)?;
+        write!(f, "elapsed:      {:.3}s", self.elapsed.as_secs_f64())?;
+        for (depth, nodes) in self.depth_nodes.iter().enumerate() {
+            write!(f, "\ndepth {depth:>4}:   {nodes}")?;
+        }
+        Ok(())
+    }
+}
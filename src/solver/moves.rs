@@ -0,0 +1,355 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::model::{Board, BoardShape, Edge, Indx, Rotate, Rotation, Tile, ROTATIONS, SIDES};
+
+/// The kinds of [Move] a [Neighbourhood] makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MoveKind {
+    /// Turn the tile in an inside cell where it lies.
+    Turn,
+    /// Swap the tiles in two cells.
+    Swap,
+    /// Move the tiles in three cells round, each into the next.
+    Cycle,
+    /// Shuffle the tiles in a patch of the board.
+    Shuffle,
+}
+
+/// One tile's part in a [Move]: the tile in `from` goes to `to`, turned by `turn`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The cell the tile comes from.
+    pub from: Indx,
+    /// The cell the tile goes to.
+    pub to: Indx,
+    /// How much more the tile is turned in its new cell.
+    pub turn: Rotation,
+}
+
+/// A change to a full board that moves tiles between cells and turns them, keeping every tile
+/// on the board.
+///
+/// The cells the steps go to are the cells they come from, in some order, so every changed
+/// cell gets exactly one tile.
+/// A move is given by where tiles go rather than by the tiles themselves, so it can be made on
+/// any arrangement of the tiles, such as a [Genome](super::Genome).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    /// What kind of move this is.
+    pub kind: MoveKind,
+    /// Where each tile goes.
+    pub steps: Vec<Step>,
+}
+
+impl Move {
+    /// The cells the move changes.
+    pub fn cells(&self) -> impl Iterator<Item = Indx> + '_ {
+        self.steps.iter().map(|step| step.to)
+    }
+
+    /// The move that puts back what this one changes.
+    pub fn inverse(&self) -> Move {
+        Move {
+            kind: self.kind,
+            steps: self
+                .steps
+                .iter()
+                .map(|step| Step {
+                    from: step.to,
+                    to: step.from,
+                    turn: step.turn.reverse(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Make the move on a board.
+    pub fn apply<E: Copy + Into<u8>>(&self, board: &mut Board<E>) {
+        let moved = self.moved(board);
+        for (step, tile) in self.steps.iter().zip(moved) {
+            board.set(step.to, tile);
+        }
+    }
+
+    /// How much the move would change the score of a board, as by [Board::weighted_score],
+    /// found from the edges around the cells it changes.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::solver::Neighbourhood;
+    ///
+    /// let spec = toy_4x4();
+    /// let mut board = spec.dimensions.new_board();
+    /// for clue in &spec.clue_set(SOLUTION).unwrap().clues {
+    ///     clue.apply(&mut board).unwrap();
+    /// }
+    /// let best = board.score() as isize;
+    ///
+    /// let moves = Neighbourhood::new(spec.dimensions, &[]);
+    /// for found in moves.turns().chain(moves.swaps()).chain(moves.cycles()) {
+    ///     let delta = found.delta(&board);
+    ///     let mut moved = board.clone();
+    ///     found.apply(&mut moved);
+    ///     assert_eq!(moved.score() as isize, best + delta);
+    ///     assert!(delta <= 0);
+    /// }
+    /// ```
+    pub fn delta<E: Copy + Edge + PartialEq>(&self, board: &Board<E>) -> isize {
+        let cells: Vec<Indx> = self.cells().collect();
+        let moved = self.moved(board);
+        let after = |at: Indx| match cells.iter().position(|c| *c == at) {
+            Some(i) => moved[i],
+            None => board[at],
+        };
+        let shape = board.shape();
+        local_score(shape, &cells, after) as isize
+            - local_score(shape, &cells, |at| board[at]) as isize
+    }
+
+    /// The tile each step puts in its cell.
+    fn moved<E: Copy>(&self, board: &Board<E>) -> Vec<Option<Tile<E>>> {
+        self.steps
+            .iter()
+            .map(|step| board[step.from].map(|tile| (&tile).rotate(step.turn).apply()))
+            .collect()
+    }
+}
+
+/// The local moves on a full board whose tiles all face the border, as local search solvers
+/// keep them.
+///
+/// Tiles only move between cells with the same number of border sides, and are turned so
+/// their outside edges face the border in their new cells, so every move keeps the border
+/// right.
+/// Tiles on the border can't be turned where they lie, and tiles inside keep their rotation
+/// when they move.
+/// Locked cells, such as the clues, are never changed.
+///
+/// The moves of each kind can be listed in full, for searches that look at every neighbour,
+/// or drawn at random.
+///
+/// ```
+/// use e2rs::model::{BoardShape, Indx};
+/// use e2rs::solver::{MoveKind, Neighbourhood};
+///
+/// let shape = BoardShape { columns: 4, rows: 4 };
+/// let moves = Neighbourhood::new(shape, &[Indx { col: 1, row: 1 }]);
+///
+/// // three inside cells, each with three turns
+/// assert_eq!(moves.turns().count(), 9);
+/// // pairs of corners, of edges and of inside cells
+/// assert_eq!(moves.swaps().count(), 6 + 28 + 3);
+/// assert!(moves.cycles().all(|m| m.kind == MoveKind::Cycle && m.steps.len() == 3));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Neighbourhood {
+    shape: BoardShape,
+    /// The cells that may be changed, grouped by their number of border sides.
+    kinds: Vec<Vec<Indx>>,
+    /// The cells that may be changed.
+    free: Vec<Indx>,
+}
+
+impl Neighbourhood {
+    /// The moves on a board of this shape that leave the locked cells alone.
+    pub fn new(shape: BoardShape, locked: &[Indx]) -> Self {
+        let mut kinds = vec![Vec::new(); 5];
+        let mut free = Vec::new();
+        for row in 0..shape.rows {
+            for col in 0..shape.columns {
+                let at = Indx { col, row };
+                if !locked.contains(&at) {
+                    kinds[border_sides(shape, at)].push(at);
+                    free.push(at);
+                }
+            }
+        }
+        Neighbourhood { shape, kinds, free }
+    }
+
+    /// The cells the moves may change.
+    pub fn free(&self) -> &[Indx] {
+        &self.free
+    }
+
+    /// Every turn of a tile inside the board where it lies.
+    pub fn turns(&self) -> impl Iterator<Item = Move> + '_ {
+        self.kinds[0]
+            .iter()
+            .flat_map(|at| ROTATIONS[1..].iter().map(|turn| turn_move(*at, *turn)))
+    }
+
+    /// Every swap of the tiles in two cells of the same kind.
+    pub fn swaps(&self) -> impl Iterator<Item = Move> + '_ {
+        self.kinds.iter().flat_map(move |cells| {
+            (0..cells.len()).flat_map(move |i| {
+                (i + 1..cells.len()).map(move |j| self.cycle(MoveKind::Swap, &[cells[i], cells[j]]))
+            })
+        })
+    }
+
+    /// Every way of moving the tiles in three cells of the same kind round, in either
+    /// direction.
+    pub fn cycles(&self) -> impl Iterator<Item = Move> + '_ {
+        self.kinds.iter().flat_map(move |cells| {
+            let n = cells.len();
+            (0..n).flat_map(move |i| {
+                (i + 1..n).flat_map(move |j| {
+                    (j + 1..n).flat_map(move |k| {
+                        let (a, b, c) = (cells[i], cells[j], cells[k]);
+                        [[a, b, c], [a, c, b]].map(|round| self.cycle(MoveKind::Cycle, &round))
+                    })
+                })
+            })
+        })
+    }
+
+    /// A random swap or turn, each half the time, or the other if there is none of the one,
+    /// as the local search solvers make.
+    pub fn random<R: Rng>(&self, rng: &mut R) -> Option<Move> {
+        if rng.gen_bool(0.5) {
+            self.random_turn(rng).or_else(|| self.random_swap(rng))
+        } else {
+            self.random_swap(rng).or_else(|| self.random_turn(rng))
+        }
+    }
+
+    /// A random turn of a tile inside the board, or `None` if there are no free inside cells.
+    pub fn random_turn<R: Rng>(&self, rng: &mut R) -> Option<Move> {
+        let at = *self.kinds[0].choose(rng)?;
+        Some(turn_move(at, *ROTATIONS[1..].choose(rng)?))
+    }
+
+    /// A random swap of the tiles in two cells of the same kind, or `None` if no two free
+    /// cells are of the same kind.
+    pub fn random_swap<R: Rng>(&self, rng: &mut R) -> Option<Move> {
+        self.random_round(MoveKind::Swap, 2, rng)
+    }
+
+    /// A random cycle of the tiles in three cells of the same kind, or `None` if no three free
+    /// cells are of the same kind.
+    pub fn random_cycle<R: Rng>(&self, rng: &mut R) -> Option<Move> {
+        self.random_round(MoveKind::Cycle, 3, rng)
+    }
+
+    /// A random shuffle of the tiles in the free cells of a square of `size` cells each way,
+    /// with its top left corner at `at`, and cut off by the edges of the board.
+    ///
+    /// Tiles only change places with tiles of their own kind, and inside tiles are turned at
+    /// random as well.
+    pub fn random_shuffle<R: Rng>(&self, at: Indx, size: usize, rng: &mut R) -> Option<Move> {
+        let inside = |cell: &&Indx| {
+            (at.col..at.col + size).contains(&cell.col)
+                && (at.row..at.row + size).contains(&cell.row)
+        };
+        let mut steps = Vec::new();
+        for cells in &self.kinds {
+            let from: Vec<Indx> = cells.iter().filter(inside).copied().collect();
+            let mut to = from.clone();
+            to.shuffle(rng);
+            for (from, to) in from.into_iter().zip(to) {
+                let turn = if border_sides(self.shape, to) == 0 {
+                    *ROTATIONS.choose(rng)?
+                } else {
+                    turn_between(self.shape, from, to)
+                };
+                steps.push(Step { from, to, turn });
+            }
+        }
+        (!steps.is_empty()).then_some(Move {
+            kind: MoveKind::Shuffle,
+            steps,
+        })
+    }
+
+    /// A random move round `count` distinct cells of the same kind.
+    fn random_round<R: Rng>(&self, kind: MoveKind, count: usize, rng: &mut R) -> Option<Move> {
+        let a = *self.free.choose(rng)?;
+        let cells = &self.kinds[border_sides(self.shape, a)];
+        if cells.len() < count {
+            return None;
+        }
+        let mut round = vec![a];
+        while round.len() < count {
+            let next = *cells.choose(rng)?;
+            if !round.contains(&next) {
+                round.push(next);
+            }
+        }
+        Some(self.cycle(kind, &round))
+    }
+
+    /// The move taking the tile in each cell to the next, and the last to the first.
+    fn cycle(&self, kind: MoveKind, round: &[Indx]) -> Move {
+        let steps = round
+            .iter()
+            .zip(round.iter().cycle().skip(1))
+            .map(|(from, to)| Step {
+                from: *from,
+                to: *to,
+                turn: turn_between(self.shape, *from, *to),
+            })
+            .collect();
+        Move { kind, steps }
+    }
+}
+
+fn turn_move(at: Indx, turn: Rotation) -> Move {
+    Move {
+        kind: MoveKind::Turn,
+        steps: vec![Step {
+            from: at,
+            to: at,
+            turn,
+        }],
+    }
+}
+
+/// The number of sides of a cell on the border of the board.
+pub(crate) fn border_sides(shape: BoardShape, at: Indx) -> usize {
+    SIDES.iter().filter(|s| shape.on_border(at, **s)).count()
+}
+
+/// The turn that takes a tile facing the border around one cell to facing the border around
+/// another of the same kind; no turn at all for inside cells.
+fn turn_between(shape: BoardShape, from: Indx, to: Indx) -> Rotation {
+    let border = |at: Indx| SIDES.map(|side| shape.on_border(at, side));
+    let [north, east, south, west] = border(from);
+    let facing = Tile::new(north, east, south, west);
+    let wanted = border(to);
+    ROTATIONS
+        .into_iter()
+        .find(|turn| {
+            let turned = (&facing).rotate(*turn).apply();
+            SIDES.iter().zip(wanted).all(|(side, w)| turned[*side] == w)
+        })
+        .unwrap_or(Rotation::Rot0)
+}
+
+/// The weight of the matches around a group of cells, as by [Edge::match_weight], counting
+/// each edge once, with the tiles as given.
+pub(crate) fn local_score<E: Copy + Edge + PartialEq>(
+    shape: BoardShape,
+    cells: &[Indx],
+    tile_at: impl Fn(Indx) -> Option<Tile<E>>,
+) -> usize {
+    let mut score = 0;
+    for (i, at) in cells.iter().enumerate() {
+        let Some(tile) = tile_at(*at) else {
+            continue;
+        };
+        for side in SIDES {
+            let Some(next) = shape.neighbour(*at, side) else {
+                continue;
+            };
+            if cells[..i].contains(&next) {
+                continue;
+            }
+            if let Some(other) = tile_at(next) {
+                score += tile[side].match_weight(&other[side.flip()]) as usize;
+            }
+        }
+    }
+    score
+}
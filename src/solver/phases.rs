@@ -0,0 +1,165 @@
+use crate::model::{Board, BoardShape, CellOrder, Indx, Region};
+
+use super::{CellOrdering, SearchState};
+
+/// A stage of a phased solve: a named group of cells, filled in the order given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Phase {
+    /// What the phase is called, for reporting progress.
+    pub name: String,
+    /// The cells to fill, in order.
+    pub cells: Vec<Indx>,
+}
+
+impl Phase {
+    /// A phase filling the given cells in order.
+    pub fn new<S: Into<String>>(name: S, cells: Vec<Indx>) -> Self {
+        Phase {
+            name: name.into(),
+            cells,
+        }
+    }
+
+    /// The frame: the cells around the border, clockwise from the top-left corner.
+    ///
+    /// Only the corner and edge pieces, as told by [Tile::is_corner](crate::model::Tile::is_corner)
+    /// and [Tile::is_edge](crate::model::Tile::is_edge), fit the border, so a solver working on
+    /// this phase only ever tries those tiles.
+    pub fn frame(shape: BoardShape) -> Self {
+        Phase::new("frame", shape.ring(0))
+    }
+
+    /// The interior: every cell off the border, in row-major order.
+    pub fn interior(shape: BoardShape) -> Self {
+        let inside = Region {
+            origin: Indx { col: 1, row: 1 },
+            shape: BoardShape {
+                columns: shape.columns.saturating_sub(2),
+                rows: shape.rows.saturating_sub(2),
+            },
+        };
+        Phase::region("interior", inside)
+    }
+
+    /// The cells of a region, in row-major order.
+    pub fn region<S: Into<String>>(name: S, region: Region) -> Self {
+        Phase::new(name, region.cells().collect())
+    }
+
+    /// Every cell, spiralling clockwise inwards from the top-left corner.
+    pub fn spiral(shape: BoardShape) -> Self {
+        Phase::new("spiral", shape.cells_in(CellOrder::Spiral))
+    }
+}
+
+/// A cell ordering that fills the board in phases, finishing each phase before it starts the
+/// next.
+///
+/// Phases are tried in order, and within a phase its cells are filled in the order given.
+/// Any cells in no phase are filled last, in row-major order.
+/// A backtracking solver using this ordering backtracks into earlier phases when a later one
+/// has no solution, so, for instance, the frame is rebuilt when the interior can't be filled
+/// inside it.
+///
+/// ```
+/// use std::ops::ControlFlow;
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Backtracker, Phased};
+///
+/// let spec = toy_4x4();
+/// let phases = Phased::border_first(spec.dimensions);
+/// assert_eq!(phases.phases()[0].cells.len(), 12);
+/// assert_eq!(Phased::spiral(spec.dimensions).phases()[0].cells.len(), 16);
+/// let empty = spec.dimensions.new_board::<u8>();
+/// assert_eq!(phases.current(&empty).unwrap().name, "frame");
+///
+/// let stats = Backtracker::new()
+///     .with_cell_ordering(phases)
+///     .search(&spec, &[], |_| ControlFlow::Continue(()))
+///     .unwrap();
+/// assert_eq!(stats.solutions, 4);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Phased {
+    phases: Vec<Phase>,
+}
+
+impl Phased {
+    /// Fill the board in the given phases.
+    pub fn new(phases: Vec<Phase>) -> Self {
+        Phased { phases }
+    }
+
+    /// Fill the frame first, then the interior.
+    pub fn border_first(shape: BoardShape) -> Self {
+        Phased::new(vec![Phase::frame(shape), Phase::interior(shape)])
+    }
+
+    /// Fill the board in a single spiral, from the border inwards.
+    pub fn spiral(shape: BoardShape) -> Self {
+        Phased::new(vec![Phase::spiral(shape)])
+    }
+
+    /// Fill the board one quadrant at a time: top-left, top-right, bottom-right, then
+    /// bottom-left.
+    ///
+    /// Boards that don't divide evenly give the extra column to the right-hand quadrants and
+    /// the extra row to the bottom ones.
+    pub fn quadrants(shape: BoardShape) -> Self {
+        let (half_across, half_down) = (shape.columns / 2, shape.rows / 2);
+        let quadrant = |name: &str, col: usize, row: usize, columns: usize, rows: usize| {
+            Phase::region(
+                name,
+                Region {
+                    origin: Indx { col, row },
+                    shape: BoardShape { columns, rows },
+                },
+            )
+        };
+        let (right, below) = (shape.columns - half_across, shape.rows - half_down);
+        Phased::new(vec![
+            quadrant("top-left", 0, 0, half_across, half_down),
+            quadrant("top-right", half_across, 0, right, half_down),
+            quadrant("bottom-right", half_across, half_down, right, below),
+            quadrant("bottom-left", 0, half_down, half_across, below),
+        ])
+    }
+
+    /// Add a phase after the others.
+    pub fn then(mut self, phase: Phase) -> Self {
+        self.phases.push(phase);
+        self
+    }
+
+    /// The phases, in order.
+    pub fn phases(&self) -> &[Phase] {
+        &self.phases
+    }
+
+    /// The first phase with an empty cell on the board, which is the one being filled.
+    pub fn current<E>(&self, board: &Board<E>) -> Option<&Phase> {
+        let shape = board.shape();
+        self.phases.iter().find(|phase| {
+            phase
+                .cells
+                .iter()
+                .any(|at| shape.contains(*at) && board[*at].is_none())
+        })
+    }
+}
+
+impl<E> CellOrdering<E> for Phased {
+    fn next_cell(&mut self, state: &SearchState<'_, E>) -> Option<Indx> {
+        let board = state.board;
+        let shape = board.shape();
+        self.phases
+            .iter()
+            .flat_map(|phase| phase.cells.iter().copied())
+            .find(|at| shape.contains(*at) && board[*at].is_none())
+            .or_else(|| {
+                (0..board.rows)
+                    .flat_map(|row| (0..board.columns).map(move |col| Indx { col, row }))
+                    .find(|at| board[*at].is_none())
+            })
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use crate::error::PlacementError;
+use crate::model::{
+    Board, BoardShape, BoardSpec, Clue, Edge, Indx, Rotate, Side, Tile, ROTATIONS, SIDES,
+};
+
+use super::{Budget, NoRecords, Record, RecordHook, SearchStats, SolveOutcome, Solver};
+
+/// A [Solver] that fills the board cell by cell in row-major order, keeping every distinct
+/// way the filled cells can meet the empty ones, rather than one placement at a time.
+///
+/// After each cell, the state of a partial filling is its profile, the colours the filled
+/// cells show the empty ones: the south edge of the lowest filled cell in each column, and
+/// the east edge of the cell just filled.
+/// Together with the set of tiles used, that is all the rest of the board depends on, so
+/// fillings with the same state are merged and only counted.
+/// This counts the solutions of a board exactly, however many there are, in time and memory
+/// that grow with the number of states rather than with the number of fillings, which is
+/// practical for boards up to around 8×8.
+///
+/// The states kept after each cell are capped by `max_states`.
+/// When there are more, those reached by the fewest fillings are dropped, so a solution the
+/// solver finds is still a solution, but counts are only lower bounds and a board left with
+/// no states may still have solutions.
+///
+/// Clues are placed as in the [dlx](super::dlx) solver: only the clue placement is tried in a
+/// clue cell, and a clue tile is tried nowhere else.
+/// Rotations of a tile that look the same are only tried once, but identical tiles are each
+/// tried, so a tileset with repeated tiles gives each solution once per arrangement of them.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{ProfileSolver, Solver};
+///
+/// let spec = toy_4x4();
+/// let board = ProfileSolver::default()
+///     .solve(&spec, &[])
+///     .unwrap()
+///     .into_solution()
+///     .unwrap();
+/// assert!(spec.verify(&board, 0).is_ok());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileSolver {
+    /// The most states to keep after each cell.
+    pub max_states: usize,
+    /// Limits on the search.
+    ///
+    /// Nodes are the ways of extending a state tried, and the search backtracks each time a
+    /// state can't be extended at all.
+    pub budget: Budget,
+}
+
+impl Default for ProfileSolver {
+    /// A solver keeping up to a million states after each cell, with no budget.
+    fn default() -> Self {
+        ProfileSolver {
+            max_states: 1_000_000,
+            budget: Budget::default(),
+        }
+    }
+}
+
+/// The solutions counted by a [ProfileSolver].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProfileCount {
+    /// The number of solutions, or [u128::MAX] if there are more than that.
+    pub solutions: u128,
+    /// True if the count is exact: no states were dropped, and the budget didn't run out.
+    pub exact: bool,
+    /// The most states reached after any one cell, before dropping any over the cap.
+    pub peak_states: usize,
+    /// What the search did, with [SearchStats::solutions] saturating at [u64::MAX].
+    pub stats: SearchStats,
+}
+
+impl ProfileSolver {
+    /// Keep at most this many states after each cell.
+    pub fn with_max_states(mut self, max_states: usize) -> Self {
+        self.max_states = max_states;
+        self
+    }
+
+    /// Give up when any limit of the budget is reached.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Count the solutions extending the clues.
+    ///
+    /// Clues are checked as for [Solver::solve].
+    ///
+    /// ```
+    /// use e2rs::examples::toy_3x3;
+    /// use e2rs::solver::ProfileSolver;
+    ///
+    /// // the toy puzzle is solved uniquely, up to turning the whole board
+    /// let spec = toy_3x3();
+    /// let count = ProfileSolver::default().count(&spec, &[]).unwrap();
+    /// assert!(count.exact);
+    /// assert_eq!(count.solutions, 4);
+    ///
+    /// // keeping one state after each cell only gives a lower bound
+    /// let capped = ProfileSolver::default()
+    ///     .with_max_states(1)
+    ///     .count(&spec, &[])
+    ///     .unwrap();
+    /// assert!(!capped.exact);
+    /// assert!(capped.solutions <= 4);
+    /// ```
+    pub fn count<E: Copy + Edge + PartialEq>(
+        &self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<ProfileCount, PlacementError> {
+        Ok(self.run(spec, clues, &mut NoRecords)?.count)
+    }
+
+    /// Fill the board, telling the hook of the first state after each cell that places
+    /// another tile.
+    fn run<E: Copy + Edge + PartialEq>(
+        &self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<Run<E>, PlacementError> {
+        spec.validate_clues(clues).map_err(PlacementError::Clues)?;
+        if let Some(clue) = clues
+            .iter()
+            .find(|c| spec.tiles.identify(&c.placed_tile()).is_none())
+        {
+            return Err(PlacementError::UnknownTile(clue.at));
+        }
+        let shape = spec.dimensions;
+        let cells = placements(spec, clues);
+        let placed = |history: &[Vec<Step>], state: usize| {
+            board(shape, clues, &cells, history, state)
+        };
+
+        let mut stats = SearchStats::default();
+        let meter = self.budget.start();
+        let mut frontier = vec![State {
+            key: (vec![0; shape.columns + 1].into_boxed_slice(), [0; 4]),
+            ways: 1,
+        }];
+        let mut history: Vec<Vec<Step>> = Vec::with_capacity(cells.len());
+        let (mut pruned, mut stopped, mut dead) = (false, false, false);
+        let mut peak_states = 1;
+        let mut deepest = clues.len();
+
+        for (cell, fits) in cells.iter().enumerate() {
+            let col = cell % shape.columns;
+            let west = shape.columns;
+            let mut index: HashMap<Key, usize> = HashMap::new();
+            let mut ways: Vec<u128> = Vec::new();
+            let mut steps: Vec<Step> = Vec::new();
+            for (parent, state) in frontier.iter().enumerate() {
+                if meter.exhausted(&stats) {
+                    stopped = true;
+                    break;
+                }
+                let (profile, used) = &state.key;
+                let mut extended = false;
+                for (i, placement) in fits.iter().enumerate() {
+                    let (word, bit) = (placement.tile / 64, 1 << (placement.tile % 64));
+                    if placement.north != profile[col]
+                        || placement.west != profile[west]
+                        || used[word] & bit != 0
+                    {
+                        continue;
+                    }
+                    stats.nodes += 1;
+                    extended = true;
+                    let mut profile = profile.clone();
+                    profile[col] = placement.south;
+                    profile[west] = placement.east;
+                    let mut used = *used;
+                    used[word] |= bit;
+                    match index.entry((profile, used)) {
+                        Entry::Occupied(entry) => {
+                            let w = &mut ways[*entry.get()];
+                            *w = w.saturating_add(state.ways);
+                        }
+                        Entry::Vacant(entry) => {
+                            entry.insert(ways.len());
+                            ways.push(state.ways);
+                            steps.push(Step {
+                                parent,
+                                placement: i,
+                            });
+                        }
+                    }
+                }
+                if !extended {
+                    stats.backtracks += 1;
+                }
+            }
+            if stopped {
+                break;
+            }
+
+            let mut layer: Vec<(Key, usize)> = index.into_iter().collect();
+            peak_states = peak_states.max(layer.len());
+            if layer.len() > self.max_states {
+                pruned = true;
+                layer.sort_by(|(_, a), (_, b)| ways[*b].cmp(&ways[*a]).then(a.cmp(b)));
+                layer.truncate(self.max_states);
+            } else {
+                layer.sort_by_key(|(_, i)| *i);
+            }
+            if layer.is_empty() {
+                dead = true;
+                break;
+            }
+            let mut kept = Vec::with_capacity(layer.len());
+            frontier = layer
+                .into_iter()
+                .map(|(key, i)| {
+                    kept.push(steps[i]);
+                    State { key, ways: ways[i] }
+                })
+                .collect();
+            history.push(kept);
+
+            let record = Record::new(&placed(&history, 0), stats.nodes);
+            if record.placed > deepest {
+                deepest = record.placed;
+                hook.record(record);
+            }
+        }
+
+        let best_partial = placed(&history, 0);
+        let finished = !stopped && !dead;
+        let solutions = if finished {
+            frontier
+                .iter()
+                .fold(0u128, |total, state| total.saturating_add(state.ways))
+        } else {
+            0
+        };
+        let exact = !pruned && !stopped;
+        stats.solutions = solutions.try_into().unwrap_or(u64::MAX);
+        stats.complete = exact;
+        Ok(Run {
+            count: ProfileCount {
+                solutions,
+                exact,
+                peak_states,
+                stats,
+            },
+            solution: finished.then(|| best_partial.clone()),
+            best_partial,
+        })
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> Solver<E> for ProfileSolver {
+    /// Clues that don't fit the board are reported as by [BoardSpec::validate_clues], and every
+    /// clue tile must be in the tileset.
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        let Run {
+            count,
+            solution,
+            best_partial,
+        } = self.run(spec, clues, hook)?;
+        Ok(SolveOutcome::from_search(solution, best_partial, count.stats))
+    }
+}
+
+/// The profile of a state, with the colour to the west of the next cell last, and the tiles
+/// it has used.
+type Key = (Box<[u16]>, [u64; 4]);
+
+/// The states after a cell.
+struct State {
+    key: Key,
+    /// The number of fillings reaching the state, saturating at the largest count.
+    ways: u128,
+}
+
+/// How a state was first reached.
+#[derive(Clone, Copy, Debug)]
+struct Step {
+    /// The state it was reached from, after the cell before.
+    parent: usize,
+    /// The placement made in the cell.
+    placement: usize,
+}
+
+/// A placement that fits a cell, with the colours of its sides, 0 being the border.
+#[derive(Clone, Copy, Debug)]
+struct Placement<E> {
+    clue: Clue<E>,
+    tile: usize,
+    north: u16,
+    east: u16,
+    south: u16,
+    west: u16,
+}
+
+/// What [ProfileSolver::run] found.
+struct Run<E> {
+    count: ProfileCount,
+    solution: Option<Board<E>>,
+    best_partial: Board<E>,
+}
+
+/// The placements that fit each cell, in row-major order, against the border and the clues.
+fn placements<E: Copy + Edge + PartialEq>(
+    spec: &BoardSpec<E>,
+    clues: &[Clue<E>],
+) -> Vec<Vec<Placement<E>>> {
+    let shape = spec.dimensions;
+    let clue_tiles: Vec<_> = clues
+        .iter()
+        .filter_map(|clue| spec.tiles.identify(&clue.placed_tile()).map(|(id, _)| id))
+        .collect();
+    let mut palette: Vec<E> = Vec::new();
+    let mut colour = |edge: E| {
+        if edge.is_border() {
+            return 0;
+        }
+        match palette.iter().position(|e| *e == edge) {
+            Some(i) => i as u16 + 1,
+            None => {
+                palette.push(edge);
+                palette.len() as u16
+            }
+        }
+    };
+
+    let mut cells = Vec::with_capacity(shape.columns * shape.rows);
+    for row in 0..shape.rows {
+        for col in 0..shape.columns {
+            let at = Indx { col, row };
+            let clue = clues.iter().find(|c| c.at == at);
+            let mut fits = Vec::new();
+            for (id, tile) in spec.tiles.ids().zip(&spec.tiles) {
+                let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+                for rotation in ROTATIONS {
+                    let placed = tile.rotate(rotation).apply();
+                    if seen.contains(&placed) {
+                        continue;
+                    }
+                    seen.push(placed);
+                    let allowed = match clue {
+                        Some(clue) => clue.placed_tile() == placed,
+                        None => !clue_tiles.contains(&id),
+                    };
+                    if allowed
+                        && SIDES
+                            .iter()
+                            .all(|s| placed[*s].is_border() == shape.on_border(at, *s))
+                    {
+                        fits.push(Placement {
+                            clue: Clue {
+                                tile: *tile,
+                                rotation,
+                                at,
+                            },
+                            tile: id.index(),
+                            north: colour(placed[Side::North]),
+                            east: colour(placed[Side::East]),
+                            south: colour(placed[Side::South]),
+                            west: colour(placed[Side::West]),
+                        });
+                    }
+                }
+            }
+            cells.push(fits);
+        }
+    }
+    cells
+}
+
+/// The board with the clues and the placements on the way to a state after the last cell in
+/// the history.
+fn board<E: Copy>(
+    shape: BoardShape,
+    clues: &[Clue<E>],
+    cells: &[Vec<Placement<E>>],
+    history: &[Vec<Step>],
+    mut state: usize,
+) -> Board<E> {
+    let mut board = shape.new_board();
+    for clue in clues {
+        board[clue.at] = Some(clue.placed_tile());
+    }
+    for (cell, steps) in history.iter().enumerate().rev() {
+        let step = steps[state];
+        let clue = &cells[cell][step.placement].clue;
+        board[clue.at] = Some(clue.placed_tile());
+        state = step.parent;
+    }
+    board
+}
@@ -0,0 +1,241 @@
+use crate::model::{BoardShape, BoardSpec, Edge, Indx, Rotate, Tile, TileId, ROTATIONS, SIDES};
+
+use super::Candidate;
+
+/// Forward checking for constructive searches: the candidates left for every empty cell,
+/// kept up to date as tiles are placed and taken back.
+///
+/// Each cell starts with every tile, in each rotation that fits the border there, as a
+/// candidate.
+/// Placing a tile removes it from every other cell, and removes from the cells next to it the
+/// candidates that don't match its edges, so a search can see a dead end as soon as some
+/// empty cell has no candidates left, rather than when it gets there.
+/// Every change is recorded, so [Propagator::undo] takes a placement back in time proportional
+/// to what it changed.
+///
+/// Rotations of a tile that look the same are only kept once.
+/// Candidates are held in no particular order; solvers that care should sort them, as with a
+/// [ValueOrdering](super::ValueOrdering).
+///
+/// ```
+/// use e2rs::examples::{toy_3x3, SOLUTION};
+/// use e2rs::model::Indx;
+/// use e2rs::solver::{Candidate, Propagator};
+///
+/// let spec = toy_3x3();
+/// let mut propagator = Propagator::new(&spec);
+/// let corner = Indx { col: 0, row: 0 };
+/// let before = propagator.domain_size(Indx { col: 1, row: 0 });
+///
+/// let clue = spec.clue_set(SOLUTION).unwrap().clues[0];
+/// let (tile, _) = spec.tiles.identify(&clue.tile).unwrap();
+/// assert!(propagator.place(corner, Candidate { tile, rotation: clue.rotation }));
+/// assert!(propagator.domain_size(Indx { col: 1, row: 0 }) < before);
+/// assert!(propagator.placed(corner).is_some());
+///
+/// propagator.undo();
+/// assert_eq!(propagator.domain_size(Indx { col: 1, row: 0 }), before);
+/// assert_eq!(propagator.depth(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Propagator<E> {
+    shape: BoardShape,
+    /// The tiles, unrotated, by [TileId::index](crate::model::TileId::index).
+    tiles: Vec<Tile<E>>,
+    /// Every distinct placement of every tile, as the candidate and the tile as it lies.
+    options: Vec<(Candidate, Tile<E>)>,
+    /// The options of each tile are `first[index]..first[index + 1]`.
+    first: Vec<usize>,
+    /// The options still possible in each cell are the first `live[cell]` of `domains[cell]`.
+    domains: Vec<Vec<usize>>,
+    live: Vec<usize>,
+    /// The option placed in each cell.
+    placed: Vec<Option<usize>>,
+    /// The cells whose domains have shrunk, with their size before, to be restored on undo.
+    trail: Vec<(usize, usize)>,
+    /// For each placement, its cell and the length of the trail before it.
+    levels: Vec<(usize, usize)>,
+}
+
+impl<E: Copy + Edge + PartialEq> Propagator<E> {
+    /// Start with an empty board, every cell holding every candidate that fits the border there.
+    pub fn new(spec: &BoardSpec<E>) -> Self {
+        let shape = spec.dimensions;
+        let mut options = Vec::new();
+        let mut first = Vec::with_capacity(spec.tiles.len() + 1);
+        for (tile, unrotated) in spec.tiles.ids().zip(&spec.tiles) {
+            first.push(options.len());
+            let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+            for rotation in ROTATIONS {
+                let placed = unrotated.rotate(rotation).apply();
+                if !seen.contains(&placed) {
+                    seen.push(placed);
+                    options.push((Candidate { tile, rotation }, placed));
+                }
+            }
+        }
+        first.push(options.len());
+
+        let mut domains = Vec::with_capacity(shape.columns * shape.rows);
+        for row in 0..shape.rows {
+            for col in 0..shape.columns {
+                let at = Indx { col, row };
+                let domain: Vec<usize> = (0..options.len())
+                    .filter(|o| {
+                        let placed = &options[*o].1;
+                        SIDES
+                            .iter()
+                            .all(|s| placed[*s].is_border() == shape.on_border(at, *s))
+                    })
+                    .collect();
+                domains.push(domain);
+            }
+        }
+
+        Propagator {
+            shape,
+            live: domains.iter().map(Vec::len).collect(),
+            placed: vec![None; domains.len()],
+            domains,
+            tiles: spec.tiles[..].to_vec(),
+            options,
+            first,
+            trail: Vec::new(),
+            levels: Vec::new(),
+        }
+    }
+
+    /// Place a tile in an empty cell, and remove the candidates that no longer fit elsewhere.
+    ///
+    /// This gives false if the candidate was not left for the cell, or if placing it leaves
+    /// some empty cell with no candidates; the placement is made either way, and should then
+    /// be taken back with [Propagator::undo].
+    /// This panics if the cell is outside the board or already holds a tile, or if the tile is
+    /// not in the tileset.
+    pub fn place(&mut self, at: Indx, candidate: Candidate) -> bool {
+        let cell = self.cell(at);
+        assert!(self.placed[cell].is_none(), "cell already holds a tile");
+        self.levels.push((cell, self.trail.len()));
+
+        let index = candidate.tile.index();
+        let wanted = self.tiles[index].rotate(candidate.rotation).apply();
+        let placed = (self.first[index]..self.first[index + 1])
+            .find(|o| self.options[*o].1 == wanted)
+            .unwrap();
+        let domain = &self.domains[cell][..self.live[cell]];
+        let mut fits = domain.contains(&placed);
+        self.placed[cell] = Some(placed);
+
+        let (candidate, tile) = self.options[placed];
+        let neighbours = SIDES.map(|side| self.shape.neighbour(at, side).map(|n| self.cell(n)));
+        for other in 0..self.domains.len() {
+            if self.placed[other].is_some() {
+                continue;
+            }
+            let facing = SIDES
+                .iter()
+                .zip(&neighbours)
+                .find(|(_, n)| **n == Some(other))
+                .map(|(side, _)| (side.flip(), tile[*side]));
+
+            let before = self.live[other];
+            let mut live = before;
+            let mut i = 0;
+            while i < live {
+                let (c, t) = &self.options[self.domains[other][i]];
+                let keep =
+                    c.tile != candidate.tile && facing.is_none_or(|(side, edge)| t[side] == edge);
+                if keep {
+                    i += 1;
+                } else {
+                    live -= 1;
+                    self.domains[other].swap(i, live);
+                }
+            }
+            if live != before {
+                self.live[other] = live;
+                self.trail.push((other, before));
+            }
+            if live == 0 {
+                fits = false;
+            }
+        }
+        fits
+    }
+}
+
+impl<E> Propagator<E> {
+    /// Remove a tile from the candidates of an empty cell, in every rotation.
+    ///
+    /// Like a placement, this is taken back by [Propagator::undo] of the last placement made
+    /// before it, and lasts for the whole search if made before any.
+    /// This gives false if it leaves the cell with no candidates.
+    pub fn exclude(&mut self, at: Indx, tile: TileId) -> bool {
+        let cell = self.cell(at);
+        let before = self.live[cell];
+        let mut live = before;
+        let mut i = 0;
+        while i < live {
+            if self.options[self.domains[cell][i]].0.tile == tile {
+                live -= 1;
+                self.domains[cell].swap(i, live);
+            } else {
+                i += 1;
+            }
+        }
+        if live != before {
+            self.live[cell] = live;
+            self.trail.push((cell, before));
+        }
+        live > 0
+    }
+
+    /// The shape of the board.
+    pub fn shape(&self) -> BoardShape {
+        self.shape
+    }
+
+    /// Take back the last placement, restoring every candidate it removed.
+    ///
+    /// This does nothing if nothing has been placed.
+    pub fn undo(&mut self) {
+        let Some((cell, mark)) = self.levels.pop() else {
+            return;
+        };
+        self.placed[cell] = None;
+        while self.trail.len() > mark {
+            let (other, before) = self.trail.pop().unwrap();
+            self.live[other] = before;
+        }
+    }
+
+    /// The number of placements made and not taken back.
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// The candidates left for a cell.
+    ///
+    /// A cell holding a tile keeps the candidates it had when the tile was placed.
+    pub fn candidates(&self, at: Indx) -> impl Iterator<Item = Candidate> + '_ {
+        let cell = self.cell(at);
+        self.domains[cell][..self.live[cell]]
+            .iter()
+            .map(|o| self.options[*o].0)
+    }
+
+    /// The number of candidates left for a cell.
+    pub fn domain_size(&self, at: Indx) -> usize {
+        self.live[self.cell(at)]
+    }
+
+    /// The candidate placed in a cell, if it holds one.
+    pub fn placed(&self, at: Indx) -> Option<Candidate> {
+        self.placed[self.cell(at)].map(|o| self.options[o].0)
+    }
+
+    fn cell(&self, at: Indx) -> usize {
+        assert!(self.shape.contains(at), "cell is outside the board");
+        at.col + at.row * self.shape.columns
+    }
+}
@@ -0,0 +1,57 @@
+use crate::model::{Board, Edge};
+
+/// A new best board reached by a solver.
+///
+/// Constructive solvers, which place tiles one at a time, set a record each time they reach a
+/// deeper consistent placement than before.
+/// Local search solvers, which move tiles around a full board, set one each time they reach a
+/// higher score, starting with the board they start from.
+#[derive(Clone, Debug)]
+pub struct Record<E> {
+    /// The board.
+    pub board: Board<E>,
+    /// The number of tiles on the board.
+    pub placed: usize,
+    /// The score of the board, as by [Board::score].
+    pub score: usize,
+    /// The number of nodes the solver had tried when it set the record.
+    pub nodes: u64,
+}
+
+impl<E: Copy + Edge + PartialEq> Record<E> {
+    /// A record of this board.
+    pub(crate) fn new(board: &Board<E>, nodes: u64) -> Self {
+        Record {
+            placed: board
+                .squares
+                .iter()
+                .filter(|square| square.is_some())
+                .count(),
+            score: board.score(),
+            board: board.clone(),
+            nodes,
+        }
+    }
+}
+
+/// Something to tell when a solver sets a new [Record].
+///
+/// Any `FnMut(Record<E>)` closure is a hook.
+pub trait RecordHook<E> {
+    /// Take a new record.
+    fn record(&mut self, record: Record<E>);
+}
+
+impl<E, F: FnMut(Record<E>)> RecordHook<E> for F {
+    fn record(&mut self, record: Record<E>) {
+        self(record)
+    }
+}
+
+/// A hook that ignores every record.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoRecords;
+
+impl<E> RecordHook<E> for NoRecords {
+    fn record(&mut self, _record: Record<E>) {}
+}
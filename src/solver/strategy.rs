@@ -0,0 +1,425 @@
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge};
+
+use super::dlx::DlxSolver;
+use super::{
+    evolve_with_hook, AnnealOutcome, Annealer, Backtracker, Budget, CellOrdering, Discrepancies,
+    Evolved, GeneticConfig, Hybrid, MostConstrained, NoRecords, ProfileSolver, Record, RecordHook,
+    Restarts, Schedule, SearchStats, SolveOutcome, SolveStats, ValueOrdering,
+};
+
+/// A strategy for solving edge-matching puzzles.
+///
+/// Every solver in this crate implements this, so code can work with any of them, or choose
+/// one at runtime from a [Registry].
+///
+/// ```
+/// use e2rs::examples::toy_3x3;
+/// use e2rs::solver::{Backtracker, Solver};
+///
+/// fn solved_by(solver: &mut dyn Solver<u8>) -> bool {
+///     let spec = toy_3x3();
+///     solver.solve(&spec, &[]).unwrap().solution().is_some()
+/// }
+/// assert!(solved_by(&mut Backtracker::new()));
+/// ```
+pub trait Solver<E> {
+    /// Search for a solution extending the clues.
+    ///
+    /// Clues that can't be placed are reported as an error.
+    /// The clues are locked: every board the solver reports, as its outcome or as a record,
+    /// has each clue tile in its cell in the clue's rotation, and never anywhere else.
+    ///
+    /// ```
+    /// use e2rs::examples::{toy_4x4, SOLUTION};
+    /// use e2rs::solver::{Budget, Record, Registry, SolveOutcome};
+    ///
+    /// let spec = toy_4x4();
+    /// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+    /// let clues = [solution[0], solution[6], solution[9]];
+    /// let locked = |board: &e2rs::model::Board<u8>| {
+    ///     clues.iter().all(|clue| board[clue.at] == Some(clue.placed_tile()))
+    /// };
+    ///
+    /// let registry = Registry::<u8>::standard();
+    /// for name in registry.names() {
+    ///     let mut solver = registry.create(name, Budget::default().with_nodes(2000), 1).unwrap();
+    ///     let mut records = true;
+    ///     let outcome = solver
+    ///         .solve_with_hook(&spec, &clues, &mut |record: Record<u8>| {
+    ///             records &= locked(&record.board)
+    ///         })
+    ///         .unwrap();
+    ///     let board = match &outcome {
+    ///         SolveOutcome::Solved { board, .. } => board,
+    ///         SolveOutcome::BudgetExhausted { best_partial, .. } => best_partial,
+    ///         SolveOutcome::NoSolution { .. } => panic!("{name} found no solution"),
+    ///     };
+    ///     assert!(records && locked(board), "{name} moved a clue");
+    /// }
+    /// ```
+    fn solve(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        self.solve_with_hook(spec, clues, &mut NoRecords)
+    }
+
+    /// Search for a solution extending the clues as for [Solver::solve], telling the hook of
+    /// each new [Record](super::Record) the solver sets.
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError>;
+
+    /// Search for a solution extending the clues as for [Solver::solve], measuring the run.
+    ///
+    /// The maximum depth is taken from the records the solver sets, and solvers that measure
+    /// more give the rest of the [SolveStats] too.
+    ///
+    /// ```
+    /// use e2rs::examples::toy_4x4;
+    /// use e2rs::solver::{Budget, Registry};
+    ///
+    /// let spec = toy_4x4();
+    /// let registry = Registry::<u8>::standard();
+    /// for name in registry.names() {
+    ///     let mut solver = registry.create(name, Budget::default().with_nodes(2000), 1).unwrap();
+    ///     let (outcome, stats) = solver.solve_with_stats(&spec, &[]).unwrap();
+    ///     assert_eq!(stats.nodes, outcome.stats().nodes, "{name}");
+    ///     assert!(stats.max_depth <= 16, "{name}");
+    /// }
+    /// ```
+    fn solve_with_stats(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<(SolveOutcome<E>, SolveStats), PlacementError> {
+        let mut max_depth = 0;
+        let start = Instant::now();
+        let outcome = self.solve_with_hook(spec, clues, &mut |record: Record<E>| {
+            max_depth = max_depth.max(record.placed.saturating_sub(clues.len()));
+        })?;
+        let mut stats = SolveStats::new(outcome.stats(), start.elapsed());
+        stats.max_depth = max_depth;
+        Ok((outcome, stats))
+    }
+}
+
+impl<E, C, V> Solver<E> for Backtracker<C, V>
+where
+    E: Copy + Edge + PartialEq,
+    C: CellOrdering<E>,
+    V: ValueOrdering<E>,
+{
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        Backtracker::solve_with_hook(self, spec, clues, hook)
+    }
+
+    fn solve_with_stats(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+    ) -> Result<(SolveOutcome<E>, SolveStats), PlacementError> {
+        Backtracker::solve_with_stats(self, spec, clues)
+    }
+}
+
+/// A randomised solver together with the random number generator it draws from.
+///
+/// Local search solvers can't tell when there is no solution, so they give
+/// [SolveOutcome::Solved] if their best board is a solution, and otherwise
+/// [SolveOutcome::BudgetExhausted] with their best board.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::solver::{Annealer, Seeded, Solver};
+///
+/// let spec = toy_4x4();
+/// let mut solver = Seeded::new(Annealer::default(), 7);
+/// let outcome = solver.solve(&spec, &[]).unwrap();
+/// assert_eq!(outcome.stats().nodes, 100_000);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Seeded<T> {
+    /// The solver.
+    pub solver: T,
+    /// The random number generator.
+    pub rng: StdRng,
+}
+
+impl<T> Seeded<T> {
+    /// Run the solver with a random number generator seeded from `seed`.
+    pub fn new(solver: T, seed: u64) -> Self {
+        Seeded {
+            solver,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl<E, S> Solver<E> for Seeded<Annealer<S>>
+where
+    E: Copy + Edge + PartialEq,
+    S: Schedule,
+{
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        let AnnealOutcome { best, stats, .. } =
+            self.solver
+                .anneal_with_hook(spec, clues, &mut self.rng, hook)?;
+        Ok(local_outcome(spec, best, stats))
+    }
+}
+
+impl<E: Copy + Edge + PartialEq> Solver<E> for Seeded<GeneticConfig> {
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        let Evolved { board, stats, .. } =
+            evolve_with_hook(&self.solver, spec, clues, &mut self.rng, hook)?;
+        Ok(local_outcome(spec, board, stats))
+    }
+}
+
+impl<E, C, V, S> Solver<E> for Seeded<Hybrid<C, V, S>>
+where
+    E: Copy + Edge + PartialEq,
+    C: CellOrdering<E>,
+    V: ValueOrdering<E>,
+    S: Schedule,
+{
+    fn solve_with_hook(
+        &mut self,
+        spec: &BoardSpec<E>,
+        clues: &[Clue<E>],
+        hook: &mut dyn RecordHook<E>,
+    ) -> Result<SolveOutcome<E>, PlacementError> {
+        self.solver
+            .solve_with_hook(spec, clues, &mut self.rng, hook)
+    }
+}
+
+/// The outcome of a local search, which is solved only if its best board is a solution.
+fn local_outcome<E: Copy + Edge + PartialEq>(
+    spec: &BoardSpec<E>,
+    best: Board<E>,
+    mut stats: SearchStats,
+) -> SolveOutcome<E> {
+    if spec.verify(&best, 0).is_ok() {
+        stats.solutions = 1;
+        SolveOutcome::Solved { board: best, stats }
+    } else {
+        SolveOutcome::BudgetExhausted {
+            best_partial: best,
+            stats,
+        }
+    }
+}
+
+/// Makes a solver from a budget and a seed for its random number generator, which only
+/// randomised solvers use.
+pub type Constructor<E> = Box<dyn Fn(Budget, u64) -> Box<dyn Solver<E>> + Send + Sync>;
+
+/// Solvers by name, so that they can be chosen at runtime.
+///
+/// ```
+/// use e2rs::examples::toy_3x3;
+/// use e2rs::solver::{Budget, Registry};
+///
+/// let registry = Registry::<u8>::standard();
+/// assert!(registry.names().any(|name| name == "dlx"));
+///
+/// let spec = toy_3x3();
+/// let mut solver = registry.create("backtrack", Budget::default(), 0).unwrap();
+/// assert!(solver.solve(&spec, &[]).unwrap().solution().is_some());
+/// assert!(registry.create("oracle", Budget::default(), 0).is_none());
+/// ```
+pub struct Registry<E> {
+    entries: Vec<(String, String, Constructor<E>)>,
+}
+
+impl<E> Default for Registry<E> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+impl<E> Registry<E> {
+    /// A registry with no solvers.
+    pub fn new() -> Self {
+        Registry {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a solver under a name, with a one-line description, replacing any solver already
+    /// registered under that name.
+    pub fn register<N, D>(&mut self, name: N, description: D, constructor: Constructor<E>)
+    where
+        N: Into<String>,
+        D: Into<String>,
+    {
+        let (name, description) = (name.into(), description.into());
+        match self.entries.iter_mut().find(|(n, _, _)| *n == name) {
+            Some(entry) => *entry = (name, description, constructor),
+            None => self.entries.push((name, description, constructor)),
+        }
+    }
+
+    /// The names of the solvers, in the order they were registered.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(name, _, _)| name.as_str())
+    }
+
+    /// The description of the solver with this name.
+    pub fn description(&self, name: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, description, _)| description.as_str())
+    }
+
+    /// Make the solver with this name, limited by the budget.
+    pub fn create(&self, name: &str, budget: Budget, seed: u64) -> Option<Box<dyn Solver<E>>> {
+        self.entries
+            .iter()
+            .find(|(n, _, _)| n == name)
+            .map(|(_, _, constructor)| constructor(budget, seed))
+    }
+}
+
+impl<E: Copy + Edge + PartialEq + 'static> Registry<E> {
+    /// A registry holding the solvers of this crate, with their default settings:
+    ///
+    /// * `backtrack`: a [Backtracker] filling cells in row-major order;
+    /// * `forward-checking`: a [Backtracker] with forward checking, filling the most
+    ///   constrained cell first;
+    /// * `restarts`: the `forward-checking` search, restarted as by [Restarts] with a new random
+    ///   value ordering every 100,000 nodes, up to 1,000 times;
+    /// * `lds`: the `forward-checking` search, by limited discrepancy search as by
+    ///   [Discrepancies];
+    /// * `dlx`: a [DlxSolver];
+    /// * `profile`: a [ProfileSolver], keeping up to a million states after each cell;
+    /// * `anneal`: an [Annealer];
+    /// * `genetic`: the genetic algorithm of [evolve];
+    /// * `hybrid`: a [Hybrid] of the `forward-checking` search, stopped after 100,000 nodes a
+    ///   round, and an [Annealer], for up to 100 rounds.
+    pub fn standard() -> Self {
+        let mut registry = Registry::new();
+        registry.register(
+            "backtrack",
+            "depth-first search in row-major order",
+            Box::new(|budget, _| Box::new(Backtracker::new().with_budget(budget))),
+        );
+        registry.register(
+            "forward-checking",
+            "depth-first search with forward checking, most constrained cell first",
+            Box::new(|budget, _| {
+                Box::new(
+                    Backtracker::new()
+                        .with_cell_ordering(MostConstrained)
+                        .with_forward_checking()
+                        .with_budget(budget),
+                )
+            }),
+        );
+        registry.register(
+            "restarts",
+            "forward-checking search, restarted with random value orderings",
+            Box::new(|budget, seed| {
+                let solver = Backtracker::new()
+                    .with_cell_ordering(MostConstrained)
+                    .with_forward_checking()
+                    .with_budget(budget);
+                let seeds = (0..1000).map(move |run| seed.wrapping_add(run));
+                Box::new(Restarts::new(solver, seeds, 100_000))
+            }),
+        );
+        registry.register(
+            "lds",
+            "forward-checking search, by limited discrepancy search",
+            Box::new(|budget, _| {
+                let solver = Backtracker::new()
+                    .with_cell_ordering(MostConstrained)
+                    .with_forward_checking()
+                    .with_budget(budget);
+                Box::new(Discrepancies::new(solver))
+            }),
+        );
+        registry.register(
+            "dlx",
+            "exact cover with dancing links",
+            Box::new(|budget, _| Box::new(DlxSolver { budget })),
+        );
+        registry.register(
+            "profile",
+            "dynamic programming over the edges between filled and empty cells",
+            Box::new(|budget, _| Box::new(ProfileSolver::default().with_budget(budget))),
+        );
+        registry.register(
+            "anneal",
+            "simulated annealing",
+            Box::new(|budget, seed| {
+                let annealer = Annealer {
+                    budget,
+                    ..Annealer::default()
+                };
+                Box::new(Seeded::new(annealer, seed))
+            }),
+        );
+        registry.register(
+            "genetic",
+            "genetic algorithm",
+            Box::new(|budget, seed| {
+                let config = GeneticConfig {
+                    budget,
+                    ..GeneticConfig::default()
+                };
+                Box::new(Seeded::new(config, seed))
+            }),
+        );
+        registry.register(
+            "hybrid",
+            "forward-checking search and simulated annealing, taking turns",
+            Box::new(|budget, seed| {
+                // each round stops when the whole run is cancelled
+                let round = Budget {
+                    cancel: budget.cancel.clone(),
+                    ..Budget::default()
+                };
+                let backtracker = Backtracker::new()
+                    .with_cell_ordering(MostConstrained)
+                    .with_forward_checking()
+                    .with_budget(round.clone().with_nodes(100_000));
+                let annealer = Annealer {
+                    budget: round,
+                    ..Annealer::default()
+                };
+                let hybrid = Hybrid::new(backtracker, annealer, 100).with_budget(budget);
+                Box::new(Seeded::new(hybrid, seed))
+            }),
+        );
+        registry
+    }
+}
@@ -0,0 +1,117 @@
+use crate::model::{BoardShape, BoardSpec, Clue, Edge, Indx, Rotate, Rotation, TileId};
+
+/// A restriction that keeps a search from finding each solution again turned around.
+///
+/// Turning a solution of a square board by a quarter, half or three quarters gives another
+/// solution, and so does turning a solution of any board by a half, so a full search finds
+/// every solution up to four times.
+/// This picks the corner tile with the smallest [TileId] and, of each set of corners that the
+/// turns move it between, allows it in only one, so exactly one turn of each solution is left.
+/// A square board keeps the tile in the top left corner, and an oblong board keeps it in the
+/// top left or top right.
+///
+/// The clues must look the same after a turn for that turn to take one solution to another, so
+/// only the turns that keep the clues are broken, and the tile is chosen from corner tiles that
+/// aren't clues.
+/// With clues that look the same under no turn there is nothing to break.
+/// Reflections are not broken: a mirrored tile has its edges the other way round, so it is not
+/// in general a tile of the set, and a mirrored solution is not a solution.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::model::Indx;
+/// use e2rs::solver::SymmetryBreak;
+///
+/// let spec = toy_4x4();
+/// let symmetry = SymmetryBreak::new(&spec, &[]).unwrap();
+/// assert_eq!(symmetry.allowed, vec![Indx { col: 0, row: 0 }]);
+/// assert!(!symmetry.allows(symmetry.tile, Indx { col: 3, row: 3 }));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymmetryBreak {
+    /// The corner tile held in place.
+    pub tile: TileId,
+    /// The corners the tile may be placed in.
+    pub allowed: Vec<Indx>,
+    /// The corners the tile may not be placed in.
+    pub forbidden: Vec<Indx>,
+}
+
+impl SymmetryBreak {
+    /// The restriction breaking the turns of the board that keep the clues, or `None` if there
+    /// are none to break, or no corner tile to hold in place.
+    pub fn new<E: Copy + Edge + PartialEq>(spec: &BoardSpec<E>, clues: &[Clue<E>]) -> Option<Self> {
+        let shape = spec.dimensions;
+        if shape.columns < 2 || shape.rows < 2 {
+            return None;
+        }
+        let turns: Vec<Rotation> = turns(shape)
+            .into_iter()
+            .filter(|turn| {
+                clues.iter().all(|clue| {
+                    let at = shape.turn_cell(*turn, clue.at);
+                    let placed = (&clue.placed_tile()).rotate(*turn).apply();
+                    clues
+                        .iter()
+                        .any(|other| other.at == at && other.placed_tile() == placed)
+                })
+            })
+            .collect();
+        if turns.len() < 2 {
+            return None;
+        }
+
+        let clue_tiles: Vec<TileId> = clues
+            .iter()
+            .filter_map(|clue| spec.tiles.identify(&clue.placed_tile()).map(|(id, _)| id))
+            .collect();
+        let tile = spec
+            .tiles
+            .ids()
+            .zip(&spec.tiles)
+            .find(|(id, tile)| tile.is_corner() && !clue_tiles.contains(id))
+            .map(|(id, _)| id)?;
+
+        let (columns, rows) = (shape.columns - 1, shape.rows - 1);
+        let corners = [(0, 0), (columns, 0), (columns, rows), (0, rows)]
+            .map(|(col, row)| Indx { col, row })
+            .into_iter()
+            .filter(|at| !clues.iter().any(|clue| clue.at == *at));
+        let mut allowed: Vec<Indx> = Vec::new();
+        let mut forbidden = Vec::new();
+        for corner in corners {
+            let seen = turns
+                .iter()
+                .any(|turn| allowed.contains(&shape.turn_cell(*turn, corner)));
+            if seen {
+                forbidden.push(corner);
+            } else {
+                allowed.push(corner);
+            }
+        }
+        Some(SymmetryBreak {
+            tile,
+            allowed,
+            forbidden,
+        })
+    }
+
+    /// True if the restriction lets the tile be placed in the cell.
+    pub fn allows(&self, tile: TileId, at: Indx) -> bool {
+        tile != self.tile || !self.forbidden.contains(&at)
+    }
+}
+
+/// The turns that take a board of this shape to itself.
+fn turns(shape: BoardShape) -> Vec<Rotation> {
+    if shape.columns == shape.rows {
+        vec![
+            Rotation::Rot0,
+            Rotation::Rot90,
+            Rotation::Rot180,
+            Rotation::Rot270,
+        ]
+    } else {
+        vec![Rotation::Rot0, Rotation::Rot180]
+    }
+}
@@ -0,0 +1,133 @@
+use crate::model::{Rotate, Side, Tile, TileSet, ROTATIONS, SIDES};
+
+use super::Candidate;
+
+/// Every placement of every tile, looked up by the edges it shows to the west and north.
+///
+/// A search that fills cells in row-major order always knows what a cell's west and north
+/// neighbours show it, or that they are the border, so it can find the tiles that fit those two
+/// sides with one lookup instead of trying every tile in every rotation.
+/// The placements for each pair of edges are in tileset order, then rotation order, and
+/// rotations of a tile that look the same are only given once.
+/// The east and south sides are not part of the key, so a search must still check them against
+/// the border, and skip tiles it has already placed.
+///
+/// Edges are looked up by their numbers, so the table is as large as the square of the highest
+/// edge number.
+///
+/// ```
+/// use e2rs::examples::toy_4x4;
+/// use e2rs::model::{Side, Tile};
+/// use e2rs::solver::CandidateTable;
+///
+/// // count the solutions by filling cells row by row
+/// fn fill(
+///     table: &CandidateTable<u8>,
+///     cells: &mut Vec<Tile<u8>>,
+///     used: &mut [bool],
+///     columns: usize,
+///     rows: usize,
+/// ) -> usize {
+///     let cell = cells.len();
+///     if cell == columns * rows {
+///         return 1;
+///     }
+///     let (col, row) = (cell % columns, cell / columns);
+///     let west = if col == 0 { 0 } else { cells[cell - 1][Side::East] };
+///     let north = if row == 0 { 0 } else { cells[cell - columns][Side::South] };
+///     let mut solutions = 0;
+///     for (candidate, tile) in table.get(west, north) {
+///         let east_ok = (tile[Side::East] == 0) == (col + 1 == columns);
+///         let south_ok = (tile[Side::South] == 0) == (row + 1 == rows);
+///         if east_ok && south_ok && !used[candidate.tile.index()] {
+///             used[candidate.tile.index()] = true;
+///             cells.push(*tile);
+///             solutions += fill(table, cells, used, columns, rows);
+///             cells.pop();
+///             used[candidate.tile.index()] = false;
+///         }
+///     }
+///     solutions
+/// }
+///
+/// let spec = toy_4x4();
+/// let table = CandidateTable::new(&spec.tiles);
+/// let mut used = vec![false; spec.tiles.len()];
+/// assert_eq!(fill(&table, &mut Vec::new(), &mut used, 4, 4), 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CandidateTable<E> {
+    /// One more than the highest edge number.
+    size: usize,
+    /// The placements for west edge `w` and north edge `n` are
+    /// `entries[starts[w * size + n]..starts[w * size + n + 1]]`.
+    starts: Vec<usize>,
+    /// Every distinct placement, as the candidate and the tile as it lies.
+    entries: Vec<(Candidate, Tile<E>)>,
+}
+
+impl<E: Copy + PartialEq + Into<u8>> CandidateTable<E> {
+    /// Build the table for a tileset.
+    pub fn new(tiles: &TileSet<E>) -> Self {
+        let label = |e: E| -> usize { Into::<u8>::into(e) as usize };
+        let size = tiles
+            .into_iter()
+            .flat_map(|tile| SIDES.map(|side| label(tile[side])))
+            .max()
+            .map_or(0, |max| max + 1);
+
+        let mut entries = Vec::new();
+        for (tile, unrotated) in tiles.ids().zip(tiles) {
+            let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+            for rotation in ROTATIONS {
+                let placed = unrotated.rotate(rotation).apply();
+                if !seen.contains(&placed) {
+                    seen.push(placed);
+                    entries.push((Candidate { tile, rotation }, placed));
+                }
+            }
+        }
+
+        // a stable sort keeps the placements for each key in order
+        let key = |placed: &Tile<E>| label(placed[Side::West]) * size + label(placed[Side::North]);
+        entries.sort_by_key(|(_, placed)| key(placed));
+        let mut starts = vec![0; size * size + 1];
+        for (_, placed) in &entries {
+            starts[key(placed) + 1] += 1;
+        }
+        for k in 0..size * size {
+            starts[k + 1] += starts[k];
+        }
+
+        CandidateTable {
+            size,
+            starts,
+            entries,
+        }
+    }
+
+    /// The placements that show `west` to the west and `north` to the north.
+    pub fn get(&self, west: E, north: E) -> &[(Candidate, Tile<E>)] {
+        let (west, north) = (
+            Into::<u8>::into(west) as usize,
+            Into::<u8>::into(north) as usize,
+        );
+        if west >= self.size || north >= self.size {
+            return &[];
+        }
+        let key = west * self.size + north;
+        &self.entries[self.starts[key]..self.starts[key + 1]]
+    }
+}
+
+impl<E> CandidateTable<E> {
+    /// The number of placements in the table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// True if the table holds no placements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use crate::model::{BoardSpec, Clue, Edge, Indx, Rotate, Side, Tile, TileId, ROTATIONS, SIDES};
+
+/// The node standing for no fillings at all.
+const NONE: usize = 0;
+/// The node standing for the one empty filling, of no cells.
+const EMPTY: usize = 1;
+
+/// Every consistent filling of one row of a board, as a zero-suppressed decision diagram.
+///
+/// A filling places a tile in each cell of the row, with the north edges of the profile above
+/// it, the border on the outside of the board, matching edges between neighbouring cells, and
+/// no tile twice or from the tiles already used elsewhere.
+/// The diagram shares the fillings that end the same way, so that rows with far too many
+/// fillings to list can still be counted and sampled from, in time that grows with the size of
+/// the diagram.
+///
+/// Each node of the diagram asks whether a filling places one tile, in one rotation, in one
+/// cell: the `hi` branch holds the fillings that do, and the `lo` branch those that don't.
+/// Placements are asked about in a fixed order, cell by cell from the west, and nodes whose
+/// `hi` branch holds no fillings are left out, as are nodes the same as one already built.
+///
+/// The profile for the top row is the border edges.
+/// A profile of the wrong length has no fillings.
+///
+/// ```
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use e2rs::examples::{toy_4x4, SOLUTION};
+/// use e2rs::model::{Clue, Side};
+/// use e2rs::solver::RowDiagram;
+///
+/// let spec = toy_4x4();
+/// let solution = &spec.clue_set(SOLUTION).unwrap().clues;
+/// let row = |r: usize| -> Vec<_> { solution.iter().filter(|c| c.at.row == r).collect() };
+///
+/// let top = RowDiagram::new(&spec, 0, &[0; 4], &[]);
+/// assert_eq!(top.count(), top.fillings().count() as u128);
+/// let same = |filling: &[Clue<u8>]| filling.iter().zip(row(0)).all(|(a, b)| a.tile == b.tile);
+/// assert!(top.fillings().any(|filling| same(&filling)));
+///
+/// // the next row, under the solution's first row and without its tiles
+/// let south: Vec<u8> = row(0).iter().map(|c| c.placed_tile()[Side::South]).collect();
+/// let used: Vec<_> = row(0)
+///     .iter()
+///     .map(|c| spec.tiles.identify(&c.placed_tile()).unwrap().0)
+///     .collect();
+/// let next = RowDiagram::new(&spec, 1, &south, &used);
+/// let filling = next.sample(&mut StdRng::seed_from_u64(1)).unwrap();
+/// for (cell, clue) in filling.iter().enumerate() {
+///     assert_eq!(clue.placed_tile()[Side::North], south[cell]);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RowDiagram<E> {
+    /// Every placement that fits a cell of the row against the profile and the border, in the
+    /// order the diagram asks about them.
+    placements: Vec<Clue<E>>,
+    /// The nodes, each after the nodes it branches to, with the two terminals first.
+    nodes: Vec<Node>,
+    /// The number of fillings each node holds, saturating at the largest count.
+    counts: Vec<u128>,
+    /// The node holding every filling of the row.
+    root: usize,
+}
+
+/// A node of a [RowDiagram].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Node {
+    /// The placement the node asks about.
+    placement: usize,
+    /// The fillings without the placement.
+    lo: usize,
+    /// The fillings with the placement, less the placement itself.
+    hi: usize,
+}
+
+impl<E: Copy + Edge + PartialEq> RowDiagram<E> {
+    /// Build the diagram of every filling of a row under a profile of north edges, one for
+    /// each column, without the tiles already used.
+    pub fn new(spec: &BoardSpec<E>, row: usize, north: &[E], used: &[TileId]) -> Self {
+        let shape = spec.dimensions;
+        let mut palette: Vec<E> = Vec::new();
+        let mut colour = |edge: E| {
+            if edge.is_border() {
+                return 0;
+            }
+            match palette.iter().position(|e| *e == edge) {
+                Some(i) => i + 1,
+                None => {
+                    palette.push(edge);
+                    palette.len()
+                }
+            }
+        };
+
+        let mut builder = Builder {
+            columns: Vec::new(),
+            unique: HashMap::new(),
+            memo: HashMap::new(),
+            nodes: vec![Node::terminal(), Node::terminal()],
+            counts: vec![0, 1],
+        };
+        let mut placements = Vec::new();
+        if north.len() == shape.columns && row < shape.rows {
+            for (col, north) in north.iter().enumerate() {
+                let at = Indx { col, row };
+                let mut column = Vec::new();
+                for (id, tile) in spec.tiles.ids().zip(&spec.tiles) {
+                    if used.contains(&id) {
+                        continue;
+                    }
+                    let mut seen: Vec<Tile<E>> = Vec::with_capacity(4);
+                    for rotation in ROTATIONS {
+                        let placed = tile.rotate(rotation).apply();
+                        if seen.contains(&placed) {
+                            continue;
+                        }
+                        seen.push(placed);
+                        let fits = placed[Side::North] == *north
+                            && SIDES.iter().all(|s| {
+                                *s == Side::North
+                                    || placed[*s].is_border() == shape.on_border(at, *s)
+                            });
+                        if fits {
+                            column.push(Placement {
+                                index: placements.len(),
+                                tile: id.index(),
+                                west: colour(placed[Side::West]),
+                                east: colour(placed[Side::East]),
+                            });
+                            placements.push(Clue {
+                                tile: *tile,
+                                rotation,
+                                at,
+                            });
+                        }
+                    }
+                }
+                builder.columns.push(column);
+            }
+        }
+
+        let root = if builder.columns.len() == shape.columns {
+            builder.build(0, 0, [0; 4])
+        } else {
+            NONE
+        };
+        RowDiagram {
+            placements,
+            nodes: builder.nodes,
+            counts: builder.counts,
+            root,
+        }
+    }
+}
+
+impl<E: Copy> RowDiagram<E> {
+    /// The number of fillings of the row, or [u128::MAX] if there are more than that.
+    pub fn count(&self) -> u128 {
+        self.counts[self.root]
+    }
+
+    /// The number of nodes in the diagram, not counting the two terminals.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - 2
+    }
+
+    /// True if the diagram has no nodes, as when the row has no fillings.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// A filling drawn uniformly at random from all the fillings of the row, placed cell by
+    /// cell from the west, or nothing if there are none.
+    ///
+    /// Counts too large for a [u128] are treated as the largest count, which skews the draw
+    /// a little towards the fillings counted in full.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Option<Vec<Clue<E>>> {
+        if self.counts[self.root] == 0 {
+            return None;
+        }
+        let mut filling = Vec::new();
+        let mut node = self.root;
+        let mut draw = rng.gen_range(0..self.counts[node]);
+        while node != EMPTY {
+            let Node { placement, lo, hi } = self.nodes[node];
+            if draw < self.counts[hi] {
+                filling.push(self.placements[placement]);
+                node = hi;
+            } else {
+                draw -= self.counts[hi];
+                node = lo;
+            }
+        }
+        Some(filling)
+    }
+
+    /// Every filling of the row, each placed cell by cell from the west.
+    pub fn fillings(&self) -> Fillings<'_, E> {
+        Fillings {
+            diagram: self,
+            stack: vec![(self.root, 0)],
+            path: Vec::new(),
+        }
+    }
+}
+
+/// The fillings of a [RowDiagram], as given by [RowDiagram::fillings].
+#[derive(Clone, Debug)]
+pub struct Fillings<'a, E> {
+    diagram: &'a RowDiagram<E>,
+    /// The nodes still to visit, each with the number of placements on the path to it.
+    stack: Vec<(usize, usize)>,
+    /// The placements on the path to the node being visited.
+    path: Vec<usize>,
+}
+
+impl<E: Copy> Iterator for Fillings<'_, E> {
+    type Item = Vec<Clue<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, depth)) = self.stack.pop() {
+            self.path.truncate(depth);
+            match node {
+                NONE => {}
+                EMPTY => {
+                    let placements = &self.diagram.placements;
+                    return Some(self.path.iter().map(|p| placements[*p]).collect());
+                }
+                _ => {
+                    let Node { placement, lo, hi } = self.diagram.nodes[node];
+                    self.stack.push((lo, depth));
+                    self.stack.push((hi, depth + 1));
+                    self.path.push(placement);
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Node {
+    /// The placeholder for a terminal, which asks about nothing.
+    fn terminal() -> Self {
+        Node {
+            placement: usize::MAX,
+            lo: NONE,
+            hi: NONE,
+        }
+    }
+}
+
+/// A placement that fits a cell of the row, as the builder sees it.
+#[derive(Clone, Copy, Debug)]
+struct Placement {
+    index: usize,
+    tile: usize,
+    /// The colours of the west and east edges, with 0 for the border.
+    west: usize,
+    east: usize,
+}
+
+/// The working state of [RowDiagram::new].
+struct Builder {
+    /// The placements that fit each cell.
+    columns: Vec<Vec<Placement>>,
+    /// The node for each distinct question and pair of branches.
+    unique: HashMap<Node, usize>,
+    /// The node holding the fillings of the rest of the row from a column, by the colour
+    /// shown to that column from the west and the tiles used so far.
+    memo: HashMap<(usize, usize, [u64; 4]), usize>,
+    nodes: Vec<Node>,
+    counts: Vec<u128>,
+}
+
+impl Builder {
+    /// The node holding the fillings of the row from a column on.
+    fn build(&mut self, col: usize, west: usize, used: [u64; 4]) -> usize {
+        if col == self.columns.len() {
+            return EMPTY;
+        }
+        if let Some(node) = self.memo.get(&(col, west, used)) {
+            return *node;
+        }
+        // the first placement asked about is the last joined on
+        let mut node = NONE;
+        for i in (0..self.columns[col].len()).rev() {
+            let placement = self.columns[col][i];
+            let (word, bit) = (placement.tile / 64, 1 << (placement.tile % 64));
+            if placement.west != west || used[word] & bit != 0 {
+                continue;
+            }
+            let mut rest = used;
+            rest[word] |= bit;
+            let hi = self.build(col + 1, placement.east, rest);
+            node = self.node(placement.index, node, hi);
+        }
+        self.memo.insert((col, west, used), node);
+        node
+    }
+
+    /// The node asking about a placement, made only if it is needed and new.
+    fn node(&mut self, placement: usize, lo: usize, hi: usize) -> usize {
+        if hi == NONE {
+            return lo;
+        }
+        let node = Node { placement, lo, hi };
+        if let Some(found) = self.unique.get(&node) {
+            return *found;
+        }
+        self.nodes.push(node);
+        self.counts
+            .push(self.counts[lo].saturating_add(self.counts[hi]));
+        self.unique.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+}
@@ -0,0 +1,128 @@
+//! Transformations that derive new puzzles from existing ones.
+
+use crate::error::PlacementError;
+use crate::model::{Board, BoardSpec, Clue, Edge, Indx, Region, Rotate, TileId, TileSet, SIDES};
+
+mod scramble;
+pub use scramble::*;
+
+/// A puzzle cut from a region of a board, as made by [extract_region].
+#[derive(Debug)]
+pub struct SubPuzzle<E> {
+    /// The puzzle.
+    pub spec: BoardSpec<E>,
+    /// The placements that solve the puzzle, as taken from the board.
+    pub solution: Vec<Clue<E>>,
+    /// The original ID of each tile in the puzzle, in puzzle order.
+    pub original: Vec<TileId>,
+}
+
+impl<E> SubPuzzle<E> {
+    /// The solution in the clues file format, with anti-clockwise rotations.
+    pub fn solution_file(&self) -> String {
+        let mut txt = String::new();
+        for (i, clue) in self.solution.iter().enumerate() {
+            txt.push_str(&format!(
+                "{} {} {} {}\n",
+                i + 1,
+                clue.at.col,
+                clue.at.row,
+                clue.rotation as usize
+            ));
+        }
+        txt
+    }
+}
+
+/// Cut a standalone puzzle out of a region of a board.
+///
+/// Every cell in the region must hold a tile from the tileset.
+/// The tiles keep their original orientation, and are renumbered from 1 in the order of their
+/// original numbers.
+/// Where the region is cut out of the board, the edges facing the cut are replaced by the outside
+/// edge, which is edge 0, so the region's tiles make up a frame of their own.
+///
+/// ```
+/// use e2rs::generate::{generate, GeneratorConfig};
+/// use e2rs::model::{BoardShape, Indx, Region};
+/// use e2rs::transform::extract_region;
+///
+/// let config = GeneratorConfig {
+///     shape: BoardShape { columns: 8, rows: 8 },
+///     ..GeneratorConfig::E2_LIKE
+/// };
+/// let puzzle = generate(&config, &mut rand::thread_rng()).unwrap();
+/// let spec = puzzle.spec().unwrap();
+/// let mut board = spec.dimensions.new_board();
+/// for clue in spec.load_clues(&puzzle.solution_file(), false).unwrap() {
+///     clue.apply(&mut board).unwrap();
+/// }
+///
+/// let region = Region { origin: Indx { col: 2, row: 3 }, shape: BoardShape { columns: 4, rows: 3 } };
+/// let sub = extract_region(&board, &spec.tiles, region).unwrap();
+/// assert_eq!(sub.spec.tiles.len(), 12);
+/// assert_eq!(sub.spec.dimensions, region.shape);
+/// assert!(sub.spec.validate_clues(&sub.solution).is_ok());
+/// ```
+pub fn extract_region<E>(
+    board: &Board<E>,
+    tiles: &TileSet<E>,
+    region: Region,
+) -> Result<SubPuzzle<E>, PlacementError>
+where
+    E: Copy + Edge + PartialEq + From<u8>,
+{
+    let shape = board.shape();
+    let mut pieces = Vec::new();
+    for at in region.cells() {
+        if !shape.contains(at) {
+            return Err(PlacementError::OutOfBounds {
+                at,
+                columns: shape.columns,
+                rows: shape.rows,
+            });
+        }
+        let placed = board[at].ok_or(PlacementError::EmptyCell(at))?;
+        let (id, rotation) = tiles
+            .identify(&placed)
+            .ok_or(PlacementError::UnknownTile(at))?;
+
+        let mut cut = placed;
+        for side in SIDES {
+            let inside = shape
+                .neighbour(at, side)
+                .is_none_or(|n| region.contains(n));
+            if !inside {
+                cut[side] = E::from(0);
+            }
+        }
+
+        let local = Indx {
+            col: at.col - region.origin.col,
+            row: at.row - region.origin.row,
+        };
+        // rotating the stored tile by `rotation` must give back the cut tile
+        let tile = cut.rotate(rotation.reverse()).apply();
+        pieces.push((id, tile, rotation, local));
+    }
+
+    pieces.sort_by_key(|(id, ..)| *id);
+    let solution = pieces
+        .iter()
+        .map(|(_, tile, rotation, at)| Clue {
+            tile: *tile,
+            rotation: *rotation,
+            at: *at,
+        })
+        .collect();
+
+    Ok(SubPuzzle {
+        spec: BoardSpec {
+            dimensions: region.shape,
+            tiles: TileSet::new(pieces.iter().map(|(_, tile, ..)| *tile).collect()),
+            clue_sets: Vec::new(),
+        },
+        solution,
+        original: pieces.iter().map(|(id, ..)| *id).collect(),
+    })
+}
@@ -0,0 +1,133 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::error::PlacementError;
+use crate::model::{BoardSpec, Clue, Rotate, Rotation, Tile, TileId, TileSet, ROTATIONS, SIDES};
+
+/// A puzzle disguised by [scramble], with the key to carry placements over to it.
+#[derive(Debug)]
+pub struct Scrambled<E> {
+    /// The disguised puzzle.
+    pub spec: BoardSpec<E>,
+    /// The colour each colour of the original puzzle became, indexed by the original colour.
+    pub colours: Vec<u8>,
+    /// The original tile behind each tile of the disguised puzzle, in disguised order, with the
+    /// rotation that was applied to it.
+    pub original: Vec<(TileId, Rotation)>,
+}
+
+impl<E: Copy + PartialEq + Into<u8> + From<u8>> Scrambled<E> {
+    /// Carry placements on the original puzzle over to the disguised one.
+    ///
+    /// Each clue must place a tile of the original tileset, which is looked up with
+    /// [TileSet::identify].
+    pub fn scramble_clues(
+        &self,
+        tiles: &TileSet<E>,
+        clues: &[Clue<E>],
+    ) -> Result<Vec<Clue<E>>, PlacementError> {
+        let mut disguised = vec![None; tiles.len()];
+        for (i, (id, turned)) in self.original.iter().enumerate() {
+            if let Some(slot) = disguised.get_mut(id.index()) {
+                *slot = Some((i, *turned));
+            }
+        }
+
+        clues
+            .iter()
+            .map(|clue| {
+                let unknown = PlacementError::UnknownTile(clue.at);
+                let (id, rotation) = tiles.identify(&clue.placed_tile()).ok_or(unknown.clone())?;
+                let (i, turned) = disguised[id.index()].ok_or(unknown)?;
+                // the disguised tile was turned by `turned`, so turn it back before placing it
+                Ok(Clue {
+                    tile: self.spec.tiles[..][i],
+                    rotation: turned.reverse() + rotation,
+                    at: clue.at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Disguise a puzzle, keeping its solutions.
+///
+/// The edge colours are shuffled among themselves, keeping the outside edge, the tiles are
+/// shuffled, and each tile is given a random turn.
+/// Two tiles fit together along an edge exactly when their recoloured edges do, because the
+/// recolouring is a one-to-one map, and border tiles stay border tiles, because the outside edge
+/// is kept.
+/// Neither renumbering nor turning a tile changes which placements are possible, only how they
+/// are written down.
+/// So the solutions of the disguised puzzle are exactly those of the original, which
+/// [Scrambled::scramble_clues] carries over.
+///
+/// This makes fresh-looking instances for blind solver testing and competitions.
+///
+/// ```
+/// use e2rs::analysis::isomorphic;
+/// use e2rs::generate::{generate, GeneratorConfig};
+/// use e2rs::model::BoardShape;
+/// use e2rs::transform::scramble;
+///
+/// let mut rng = rand::thread_rng();
+/// let config = GeneratorConfig {
+///     shape: BoardShape { columns: 6, rows: 6 },
+///     ..GeneratorConfig::E2_LIKE
+/// };
+/// let puzzle = generate(&config, &mut rng).unwrap();
+/// let spec = puzzle.spec().unwrap();
+/// let solution = spec.load_clues(&puzzle.solution_file(), false).unwrap();
+///
+/// let scrambled = scramble(&spec, &mut rng);
+/// assert!(isomorphic(&spec.tiles, &scrambled.spec.tiles).is_some());
+///
+/// let moved = scrambled.scramble_clues(&spec.tiles, &solution).unwrap();
+/// assert!(scrambled.spec.validate_clues(&moved).is_ok());
+/// ```
+pub fn scramble<E, R>(spec: &BoardSpec<E>, rng: &mut R) -> Scrambled<E>
+where
+    E: Copy + Into<u8> + From<u8>,
+    R: Rng,
+{
+    let mut used = [false; 256];
+    for tile in &spec.tiles {
+        for side in SIDES {
+            used[Into::<u8>::into(tile[side]) as usize] = true;
+        }
+    }
+    let max = used.iter().rposition(|u| *u).unwrap_or(0);
+
+    let present: Vec<u8> = (1..=max as u8).filter(|c| used[*c as usize]).collect();
+    let mut shuffled = present.clone();
+    shuffled.shuffle(rng);
+    let mut colours: Vec<u8> = (0..=max as u8).collect();
+    for (from, to) in present.iter().zip(&shuffled) {
+        colours[*from as usize] = *to;
+    }
+
+    let mut original: Vec<(TileId, Rotation)> = spec
+        .tiles
+        .ids()
+        .map(|id| (id, *ROTATIONS.choose(rng).unwrap_or(&Rotation::Rot0)))
+        .collect();
+    original.shuffle(rng);
+
+    let recoloured = spec
+        .tiles
+        .remap_edges(|e| E::from(colours[Into::<u8>::into(e) as usize]));
+    let tiles: Vec<Tile<E>> = original
+        .iter()
+        .map(|(id, turned)| recoloured[..][id.index()].rotate(*turned).apply())
+        .collect();
+
+    Scrambled {
+        spec: BoardSpec {
+            dimensions: spec.dimensions,
+            tiles: TileSet::new(tiles),
+            clue_sets: Vec::new(),
+        },
+        colours,
+        original,
+    }
+}